@@ -0,0 +1,29 @@
+use std::{
+    fmt::Display,
+    io::{self, stdout},
+};
+
+use crossterm::{
+    execute,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme};
+
+/// Prints the message to stdout, in the color if enabled
+/// (see "avalanche_ops::color::enabled").
+pub fn print<T: Display>(enabled: bool, color: Color, msg: T) -> io::Result<()> {
+    if enabled {
+        execute!(stdout(), SetForegroundColor(color), Print(msg), ResetColor)
+    } else {
+        execute!(stdout(), Print(msg))
+    }
+}
+
+/// Returns the prompt theme, without colors if disabled.
+pub fn theme(enabled: bool) -> Box<dyn Theme> {
+    if enabled {
+        Box::<ColorfulTheme>::default()
+    } else {
+        Box::new(SimpleTheme)
+    }
+}
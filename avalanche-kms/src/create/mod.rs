@@ -2,7 +2,7 @@ use std::{
     collections::HashMap,
     env,
     fs::{self, File},
-    io::{self, stdout, Error, ErrorKind, Write},
+    io::{self, Error, ErrorKind, Write},
     path::Path,
 };
 
@@ -13,16 +13,16 @@ use avalanche_types::{
 };
 use aws_manager::{self, kms, sts};
 use clap::{value_parser, Arg, Command};
-use crossterm::{
-    execute,
-    style::{Color, Print, ResetColor, SetForegroundColor},
-};
-use dialoguer::{theme::ColorfulTheme, Select};
+use crossterm::style::Color;
+use dialoguer::Select;
+use ethers_providers::{Http, Provider};
 use primitive_types::U256;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use tokio::time::{sleep, Duration};
 
+use crate::{color, evm_signer, evm_tx};
+
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -85,7 +85,7 @@ pub const NAME: &str = "create";
 
 pub fn command() -> Command {
     Command::new(NAME)
-        .about("Create and fund AWS KMS keys")
+        .about("Create and fund AWS KMS keys (optionally with an alias and tags)")
         .arg(
             Arg::new("LOG_LEVEL")
                 .long("log-level")
@@ -147,6 +147,22 @@ pub fn command() -> Command {
                 .default_value("1"),
         )
 
+        // optional to name/label AWS KMS keys
+        .arg(
+            Arg::new("ALIAS")
+                .long("alias")
+                .help("Sets the KMS key alias name without the 'alias/' prefix (only for aws-kms key type, suffixed with the key index if more than one key)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("TAGS")
+                .long("tags")
+                .help("Sets the comma-separated KMS key tags (e.g., 'Team=infra,Env=dev', only for aws-kms key type)")
+                .required(false)
+                .num_args(1),
+        )
+
         // optional for cross-account grants
         .arg(
             Arg::new("GRANTEE_PRINCIPAL")
@@ -185,6 +201,13 @@ pub fn command() -> Command {
                 .required(false)
                 .num_args(1),
         )
+        .arg(
+            Arg::new("LEGACY_TX")
+                .long("legacy-tx")
+                .help("Forces the legacy (type-0) funding transaction with the gas price (by default, EIP-1559 type-2 if the chain has the base fee)")
+                .required(false)
+                .num_args(0),
+        )
 
         .arg(
             Arg::new("SKIP_PROMPT")
@@ -207,16 +230,20 @@ pub fn command() -> Command {
 #[allow(clippy::too_many_arguments)]
 pub async fn execute(
     log_level: &str,
+    color_enabled: bool,
     region: &str,
     key_type: KeyType,
     key_name_prefix: &str,
     keys: usize,
     keys_file_output: &str,
     keys_file_chunks: usize,
+    alias: &str,
+    tags: HashMap<String, String>,
     grantee_principal: &str,
     evm_chain_rpc_url: &str,
     evm_funding_hotkey: &str,
     evm_funding_amount_navax: U256,
+    legacy_tx: bool,
     skip_prompt: bool,
     profile_name: String,
 ) -> io::Result<()> {
@@ -225,12 +252,29 @@ pub async fn execute(
         env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, log_level),
     );
 
+    if !matches!(key_type, KeyType::AwsKms) && (!alias.is_empty() || !tags.is_empty()) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "--alias and --tags are only supported for key type {}",
+                KeyType::AwsKms
+            ),
+        ));
+    }
+    let alias = alias.trim_start_matches("alias/");
+    if alias.starts_with("aws/") {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("alias '{alias}' cannot use the reserved 'aws/' prefix"),
+        ));
+    }
+
     if !skip_prompt {
         let options = &[
             format!("No, I am not ready to create new {keys} with key type {key_type}."),
             format!("Yes, let's create new {keys} with key type {key_type}."),
         ];
-        let selected = Select::with_theme(&ColorfulTheme::default())
+        let selected = Select::with_theme(color::theme(color_enabled).as_ref())
             .with_prompt("Select your 'create' option")
             .items(&options[..])
             .default(0)
@@ -262,13 +306,10 @@ pub async fn execute(
             log::info!("current identity {:?}", current_identity);
             println!();
 
-            execute!(
-                stdout(),
-                SetForegroundColor(Color::Green),
-                Print(format!(
-                    "\nCreating new KMS keys with '{key_name_prefix}' in region {region}\n",
-                )),
-                ResetColor
+            color::print(
+                color_enabled,
+                Color::Green,
+                format!("\nCreating new KMS keys with '{key_name_prefix}' in region {region}\n",),
             )?;
             let mut kms_keys = Vec::new();
             let mut kms_grant_tokens = Vec::new();
@@ -281,15 +322,58 @@ pub async fn execute(
 
                 println!();
                 log::info!("[{i}] creating KMS key");
-                let mut tags = HashMap::new();
-                tags.insert(
+
+                let alias_name = if alias.is_empty() {
+                    String::new()
+                } else if keys > 1 {
+                    format!("alias/{alias}-{:03}", i + 1)
+                } else {
+                    format!("alias/{alias}")
+                };
+                if !alias_name.is_empty() {
+                    check_alias_available(&kms_manager, &alias_name).await?;
+                }
+
+                let mut key_tags = HashMap::new();
+                key_tags.insert(
                     String::from("Name"),
                     format!("{key_name_prefix}-{:03}", i + 1),
                 );
-                let key = secp256k1::kms::aws::Key::create(kms_manager.clone(), tags)
+                key_tags.extend(tags.clone());
+                let key = secp256k1::kms::aws::Key::create(kms_manager.clone(), key_tags)
                     .await
                     .unwrap();
 
+                if !alias_name.is_empty() {
+                    log::info!("assigning alias '{alias_name}' to {}", key.arn);
+                    kms_manager
+                        .cli
+                        .create_alias()
+                        .alias_name(&alias_name)
+                        .target_key_id(&key.id)
+                        .send()
+                        .await
+                        .map_err(|e| {
+                            Error::new(
+                                ErrorKind::Other,
+                                format!(
+                                    "failed to create alias '{alias_name}' for {} ({:?})",
+                                    key.arn, e
+                                ),
+                            )
+                        })?;
+                    color::print(
+                        color_enabled,
+                        Color::Green,
+                        format!(
+                            "
+created KMS key {} with alias '{alias_name}'
+",
+                            key.arn
+                        ),
+                    )?;
+                }
+
                 let key_info: secp256k1::Info = key.to_info(1).unwrap();
                 kms_keys.push(key_info.clone());
 
@@ -335,17 +419,11 @@ pub async fn execute(
                 log::info!("loaded funding key: {}", funding_key_info.eth_address);
 
                 let transferee_addr = key_info.h160_address;
-                execute!(
-                stdout(),
-                SetForegroundColor(Color::Green),
-                Print(format!(
+                color::print(color_enabled, Color::Green, format!(
                     "\ntransfering {evm_funding_amount_navax} ({} ETH/AVAX) from {} to {transferee_addr} via {evm_chain_rpc_url}\n",
                     units::cast_evm_navax_to_avax_i64(evm_funding_amount_navax), funding_key_info.eth_address
-                )),
-                    ResetColor
-                )?;
-                let funding_key_signer: ethers_signers::LocalWallet =
-                    funding_key.to_ethers_core_signing_key().into();
+                ))?;
+                let funding_key_signer = evm_signer::local_signer(&funding_key, chain_id)?;
 
                 let w = wallet::Builder::new(&funding_key)
                     .base_http_url(evm_chain_rpc_url.to_string())
@@ -374,16 +452,25 @@ pub async fn execute(
                     units::cast_evm_navax_to_avax_i64(transferee_balance)
                 );
 
-                let tx_id = funding_evm_wallet
-                    .eip1559()
-                    .recipient(transferee_addr)
-                    .value(evm_funding_amount_navax)
-                    .urgent()
-                    .check_acceptance(true)
-                    .submit()
-                    .await
-                    .unwrap();
-                log::info!("evm ethers wallet SUCCESS with transaction id {}", tx_id);
+                let provider = Provider::<Http>::try_from(evm_chain_rpc_url).map_err(|e| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("invalid chain RPC URL '{evm_chain_rpc_url}' ({})", e),
+                    )
+                })?;
+                let fees = evm_tx::resolve_fees(&provider, legacy_tx).await?;
+                let tx_id = evm_tx::transfer(
+                    &provider,
+                    &funding_key_signer,
+                    transferee_addr,
+                    evm_funding_amount_navax,
+                    fees,
+                )
+                .await?;
+                log::info!(
+                    "evm ethers wallet SUCCESS with transaction id 0x{:x}",
+                    tx_id
+                );
             }
 
             for (i, k) in kms_keys.iter().enumerate() {
@@ -412,21 +499,19 @@ pub async fn execute(
             }
             println!("{} evm-transfer-from-hotkey --chain-rpc-url={evm_chain_rpc_url} --transferer-key=[FUNDING_HOTKEY] --transfer-amount-in-avax \"30000000\" --transferee-addresses {}", exec_path.display(), addresses.join(","));
 
-            execute!(
-                stdout(),
-                SetForegroundColor(Color::Green),
-                Print(format!("\nWrote keys to {keys_file_output}\n",)),
-                ResetColor
+            color::print(
+                color_enabled,
+                Color::Green,
+                format!("\nWrote keys to {keys_file_output}\n",),
             )?;
             let keys = Keys(entries);
             keys.sync(keys_file_output)?;
 
             if keys_file_chunks > 1 {
-                execute!(
-                    stdout(),
-                    SetForegroundColor(Color::Green),
-                    Print("\nWrote keys in chunk\n".to_string()),
-                    ResetColor
+                color::print(
+                    color_enabled,
+                    Color::Green,
+                    "\nWrote keys in chunk\n".to_string(),
                 )?;
 
                 let mut chunk_size = keys.0.len() / keys_file_chunks;
@@ -442,12 +527,7 @@ pub async fn execute(
             }
         }
         KeyType::Hot => {
-            execute!(
-                stdout(),
-                SetForegroundColor(Color::Green),
-                Print("\nCreating new hot keys\n"),
-                ResetColor
-            )?;
+            color::print(color_enabled, Color::Green, "\nCreating new hot keys\n")?;
             for i in 0..keys {
                 let k = Key::generate().unwrap();
                 let key_info = k.to_info(1).unwrap();
@@ -466,3 +546,59 @@ pub async fn execute(
 
     Ok(())
 }
+
+/// Parses the comma-separated "key=value" tags (e.g., "Team=infra,Env=dev").
+pub fn parse_tags(s: &str) -> io::Result<HashMap<String, String>> {
+    let mut tags = HashMap::new();
+    for kv in s.split(',').map(|kv| kv.trim()).filter(|kv| !kv.is_empty()) {
+        let (k, v) = kv.split_once('=').ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid tag '{kv}' (expected 'key=value')"),
+            )
+        })?;
+        if k.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid tag '{kv}' (empty key)"),
+            ));
+        }
+        tags.insert(k.trim().to_string(), v.trim().to_string());
+    }
+    Ok(tags)
+}
+
+/// Fails if the alias is already assigned to another KMS key,
+/// so that no key is created without its alias.
+pub async fn check_alias_available(kms_manager: &kms::Manager, alias_name: &str) -> io::Result<()> {
+    match kms_manager
+        .cli
+        .describe_key()
+        .key_id(alias_name)
+        .send()
+        .await
+    {
+        Ok(out) => {
+            let existing = out
+                .key_metadata()
+                .and_then(|m| m.arn())
+                .unwrap_or_default()
+                .to_string();
+            Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("alias '{alias_name}' already exists for KMS key '{existing}' (use a different --alias)"),
+            ))
+        }
+        Err(e) => {
+            let msg = format!("{:?}", e);
+            if msg.contains("NotFoundException") {
+                Ok(())
+            } else {
+                Err(Error::new(
+                    ErrorKind::Other,
+                    format!("failed to describe alias '{alias_name}' ({})", msg),
+                ))
+            }
+        }
+    }
+}
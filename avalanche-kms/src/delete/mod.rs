@@ -1,15 +1,14 @@
-use std::io::{self, stdout};
+use std::io;
 
 use avalanche_types::key;
 use aws_manager::{self, kms, sts};
 use clap::{value_parser, Arg, Command};
-use crossterm::{
-    execute,
-    style::{Color, Print, ResetColor, SetForegroundColor},
-};
-use dialoguer::{theme::ColorfulTheme, Select};
+use crossterm::style::Color;
+use dialoguer::Select;
 use tokio::time::Duration;
 
+use crate::color;
+
 pub const NAME: &str = "delete";
 
 pub fn command() -> Command {
@@ -70,6 +69,7 @@ pub fn command() -> Command {
 
 pub async fn execute(
     log_level: &str,
+    color_enabled: bool,
     region: &str,
     key_arn: &str,
     pending_windows_in_days: i32,
@@ -96,14 +96,13 @@ pub async fn execute(
     log::info!("current identity {:?}", current_identity);
     println!();
 
-    execute!(
-        stdout(),
-        SetForegroundColor(Color::Red),
-        Print(format!(
+    color::print(
+        color_enabled,
+        Color::Red,
+        format!(
             "\nLoading the KMS key {} in region {} for deletion\n",
             key_arn, region
-        )),
-        ResetColor
+        ),
     )?;
     let key = key::secp256k1::kms::aws::Key::from_arn(kms_manager.clone(), key_arn)
         .await
@@ -125,7 +124,7 @@ pub async fn execute(
                 region, key_arn, pending_windows_in_days
             ),
         ];
-        let selected = Select::with_theme(&ColorfulTheme::default())
+        let selected = Select::with_theme(color::theme(color_enabled).as_ref())
             .with_prompt("Select your 'delete' option")
             .items(&options[..])
             .default(0)
@@ -0,0 +1,91 @@
+use std::io::{self, Error, ErrorKind};
+
+use avalanche_types::key::secp256k1;
+use clap::{value_parser, Arg, Command};
+use crossterm::style::Color;
+
+use crate::color;
+
+pub const NAME: &str = "derive-address";
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Derives the addresses from a secp256k1 public key (offline, no AWS credentials required)")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("PUBLIC_KEY_HEX")
+                .long("public-key-hex")
+                .help("Hex-encoded secp256k1 public key (SEC1 compressed 33-byte or uncompressed 65-byte)")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("NETWORK_ID")
+                .long("network-id")
+                .help("Sets the network Id for the X/P-chain bech32 addresses")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u32))
+                .default_value("1"),
+        )
+}
+
+pub fn execute(
+    log_level: &str,
+    color_enabled: bool,
+    public_key_hex: &str,
+    network_id: u32,
+) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, log_level),
+    );
+
+    let b = hex::decode(public_key_hex.trim_start_matches("0x")).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("failed to decode public key hex ({})", e),
+        )
+    })?;
+    let pubkey = secp256k1::public_key::Key::from_sec1_bytes(&b).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid SEC1 public key ({})", e),
+        )
+    })?;
+    log::info!("loaded public key (network Id {network_id})");
+
+    // same derivation as the KMS key info
+    let derive_error = |what: &str, e: avalanche_types::errors::Error| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to derive the {what} ({})", e),
+        )
+    };
+    let x_address = pubkey
+        .to_hrp_address(network_id, "X")
+        .map_err(|e| derive_error("X-chain address", e))?;
+    let p_address = pubkey
+        .to_hrp_address(network_id, "P")
+        .map_err(|e| derive_error("P-chain address", e))?;
+    let short_address = pubkey
+        .to_short_id()
+        .map_err(|e| derive_error("short address", e))?;
+
+    color::print(color_enabled, Color::Green, format!(
+            "\nx: {x_address}\np: {p_address}\nshort_address: {short_address}\neth_address: {}\nh160_address: {:?}\n\n(network Id {network_id})\n",
+            pubkey.to_eth_address(),
+            pubkey.to_h160(),
+        ))?;
+
+    Ok(())
+}
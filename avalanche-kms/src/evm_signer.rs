@@ -0,0 +1,56 @@
+use std::io::{self, Error, ErrorKind};
+
+use avalanche_types::key::secp256k1::private_key::Key;
+use ethers_signers::{LocalWallet, Signer};
+use primitive_types::U256;
+
+/// Returns the hotkey signer bound to the chain Id fetched from the RPC ("eth_chainId"),
+/// so that every signed transaction encodes the chain Id (EIP-155) and cannot be replayed
+/// on other chains. Without it, the signer defaults to the chain Id 1.
+/// ref. <https://eips.ethereum.org/EIPS/eip-155>
+pub fn local_signer(key: &Key, chain_id: U256) -> io::Result<LocalWallet> {
+    if chain_id.is_zero() || chain_id > U256::from(u64::MAX) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid EVM chain Id {chain_id} (must be non-zero and fit in u64)"),
+        ));
+    }
+    let signer: LocalWallet = key.to_ethers_core_signing_key().into();
+    Ok(signer.with_chain_id(chain_id.as_u64()))
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-kms --bin avalanche-kms -- evm_signer::test_local_signer --exact --show-output
+#[test]
+fn test_local_signer() {
+    use ethers_core::types::{
+        transaction::eip2718::TypedTransaction, Transaction, TransactionRequest,
+    };
+
+    // ewoq test key
+    let key =
+        Key::from_hex("56289e99c94b6912bfc12adc093c9b51124f0dc54ac7a766b2bc5ccf558d8027").unwrap();
+    let chain_id = U256::from(43112);
+    let signer = local_signer(&key, chain_id).unwrap();
+    assert_eq!(signer.chain_id(), 43112);
+
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(signer.address())
+        .value(1)
+        .nonce(0)
+        .gas(21000)
+        .gas_price(1)
+        .into();
+    let sig = signer.sign_transaction_sync(&tx).unwrap();
+    // EIP-155 "v = chain_id * 2 + 35 + recovery id"
+    assert!(sig.v == 43112 * 2 + 35 || sig.v == 43112 * 2 + 36);
+
+    let decoded: Transaction = ethers_core::utils::rlp::decode(&tx.rlp_signed(&sig)).unwrap();
+    assert_eq!(
+        decoded.chain_id,
+        Some(ethers_core::types::U256::from(43112))
+    );
+    assert_eq!(decoded.recover_from().unwrap(), signer.address());
+
+    assert!(local_signer(&key, U256::zero()).is_err());
+    assert!(local_signer(&key, U256::from(u64::MAX) + 1).is_err());
+}
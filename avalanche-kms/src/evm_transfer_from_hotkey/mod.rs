@@ -1,17 +1,17 @@
-use std::io::{self, stdout};
+use std::io;
 
 use avalanche_types::{
     jsonrpc::client::{evm as json_client_evm, info as json_client_info},
     key, units, wallet,
 };
 use clap::{Arg, Command};
-use crossterm::{
-    execute,
-    style::{Color, Print, ResetColor, SetForegroundColor},
-};
-use dialoguer::{theme::ColorfulTheme, Select};
+use crossterm::style::Color;
+use dialoguer::Select;
+use ethers_providers::{Http, Provider};
 use primitive_types::{H160, U256};
 
+use crate::{color, evm_signer, evm_tx};
+
 pub const NAME: &str = "evm-transfer-from-hotkey";
 
 pub fn command() -> Command {
@@ -62,6 +62,13 @@ pub fn command() -> Command {
                 .required(true)
                 .num_args(1),
         )
+        .arg(
+            Arg::new("LEGACY_TX")
+                .long("legacy-tx")
+                .help("Forces the legacy (type-0) transaction with the gas price (by default, EIP-1559 type-2 if the chain has the base fee)")
+                .required(false)
+                .num_args(0),
+        )
         .arg(
             Arg::new("SKIP_PROMPT")
                 .long("skip-prompt")
@@ -72,12 +79,15 @@ pub fn command() -> Command {
         )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn execute(
     log_level: &str,
+    color_enabled: bool,
     chain_rpc_url: &str,
     transferer_key: &str,
     transfer_amount_navax: U256,
     transferee_addrs: Vec<H160>,
+    legacy_tx: bool,
     skip_prompt: bool,
 ) -> io::Result<()> {
     // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
@@ -108,7 +118,7 @@ pub async fn execute(
                 units::cast_evm_navax_to_avax_i64(transfer_amount_navax), transferer_key_info.eth_address, transferee_addrs.len(), transferee_addrs
             ),
         ];
-        let selected = Select::with_theme(&ColorfulTheme::default())
+        let selected = Select::with_theme(color::theme(color_enabled).as_ref())
             .with_prompt("Select your 'create' option")
             .items(&options[..])
             .default(0)
@@ -121,18 +131,19 @@ pub async fn execute(
         log::info!("skipping prompt...")
     }
 
+    let provider = Provider::<Http>::try_from(chain_rpc_url).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid chain RPC URL '{chain_rpc_url}' ({})", e),
+        )
+    })?;
+
     for transferee_addr in transferee_addrs.iter() {
-        execute!(
-            stdout(),
-            SetForegroundColor(Color::Green),
-            Print(format!(
+        color::print(color_enabled, Color::Green, format!(
                 "\ntransfering {transfer_amount_navax} ({} ETH/AVAX) from {} to {transferee_addr} via {chain_rpc_url}\n",
                 units::cast_evm_navax_to_avax_i64(transfer_amount_navax), transferer_key_info.eth_address
-            )),
-            ResetColor
-        )?;
-        let transferer_key_signer: ethers_signers::LocalWallet =
-            transferer_key.to_ethers_core_signing_key().into();
+            ))?;
+        let transferer_key_signer = evm_signer::local_signer(&transferer_key, chain_id)?;
 
         let w = wallet::Builder::new(&transferer_key)
             .base_http_url(chain_rpc_url.to_string())
@@ -160,15 +171,16 @@ pub async fn execute(
             units::cast_evm_navax_to_avax_i64(transferee_balance)
         );
 
-        let tx_id = transferer_evm_wallet
-            .eip1559()
-            .recipient(*transferee_addr)
-            .value(transfer_amount_navax)
-            .urgent()
-            .check_acceptance(true)
-            .submit()
-            .await
-            .unwrap();
+        // fees are resolved for each transfer, as the base fee changes per block
+        let fees = evm_tx::resolve_fees(&provider, legacy_tx).await?;
+        let tx_id = evm_tx::transfer(
+            &provider,
+            &transferer_key_signer,
+            *transferee_addr,
+            transfer_amount_navax,
+            fees,
+        )
+        .await?;
         log::info!(
             "evm ethers wallet SUCCESS with transaction id 0x{:x}",
             tx_id
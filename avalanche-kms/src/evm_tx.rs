@@ -0,0 +1,210 @@
+use std::io::{self, Error, ErrorKind};
+
+use ethers_core::types::{
+    transaction::eip2718::TypedTransaction, BlockNumber, Eip1559TransactionRequest,
+    TransactionRequest, H160, H256, U256,
+};
+use ethers_providers::{Http, Middleware, Provider};
+use ethers_signers::{LocalWallet, Signer};
+
+/// Transaction fees resolved from the chain RPC.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Fees {
+    /// Type-2 transaction, for chains with the base fee (EIP-1559).
+    Eip1559 {
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+    },
+    /// Type-0 transaction, for chains without the base fee (or forced with "--legacy-tx").
+    Legacy { gas_price: U256 },
+}
+
+/// Resolves the fees from the RPC: EIP-1559 fees ("eth_feeHistory") if the latest block
+/// has the base fee, otherwise the legacy gas price ("eth_gasPrice").
+pub async fn resolve_fees(provider: &Provider<Http>, legacy_tx: bool) -> io::Result<Fees> {
+    let base_fee = if legacy_tx {
+        None
+    } else {
+        let block = provider
+            .get_block(BlockNumber::Latest)
+            .await
+            .map_err(|e| rpc_error("eth_getBlockByNumber", e))?;
+        block.and_then(|b| b.base_fee_per_gas)
+    };
+
+    if let Some(base_fee) = base_fee {
+        let (max_fee_per_gas, max_priority_fee_per_gas) = provider
+            .estimate_eip1559_fees(None)
+            .await
+            .map_err(|e| rpc_error("eth_feeHistory", e))?;
+        log::info!("using EIP-1559 fees (base fee {base_fee}, max fee {max_fee_per_gas}, max priority fee {max_priority_fee_per_gas})");
+        return Ok(Fees::Eip1559 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        });
+    }
+
+    let gas_price = provider
+        .get_gas_price()
+        .await
+        .map_err(|e| rpc_error("eth_gasPrice", e))?;
+    if legacy_tx {
+        log::info!("using legacy gas price {gas_price} (--legacy-tx)");
+    } else {
+        log::info!("using legacy gas price {gas_price} (no base fee in the latest block)");
+    }
+    Ok(Fees::Legacy { gas_price })
+}
+
+/// Builds the native token transfer with the chain Id (EIP-155) and the fees (without gas limit).
+pub fn transfer_request(
+    from: H160,
+    to: H160,
+    value: U256,
+    nonce: U256,
+    chain_id: u64,
+    fees: Fees,
+) -> TypedTransaction {
+    match fees {
+        Fees::Eip1559 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        } => Eip1559TransactionRequest::new()
+            .from(from)
+            .to(to)
+            .value(value)
+            .nonce(nonce)
+            .chain_id(chain_id)
+            .max_fee_per_gas(max_fee_per_gas)
+            .max_priority_fee_per_gas(max_priority_fee_per_gas)
+            .into(),
+        Fees::Legacy { gas_price } => TransactionRequest::new()
+            .from(from)
+            .to(to)
+            .value(value)
+            .nonce(nonce)
+            .chain_id(chain_id)
+            .gas_price(gas_price)
+            .into(),
+    }
+}
+
+/// Transfers the native tokens, and waits for the successful receipt.
+/// Returns the transaction hash.
+pub async fn transfer(
+    provider: &Provider<Http>,
+    signer: &LocalWallet,
+    to: H160,
+    value: U256,
+    fees: Fees,
+) -> io::Result<H256> {
+    let from = signer.address();
+    let nonce = provider
+        .get_transaction_count(from, Some(BlockNumber::Pending.into()))
+        .await
+        .map_err(|e| rpc_error("eth_getTransactionCount", e))?;
+    let mut tx = transfer_request(from, to, value, nonce, signer.chain_id(), fees);
+    let gas = provider
+        .estimate_gas(&tx, None)
+        .await
+        .map_err(|e| rpc_error("eth_estimateGas", e))?;
+    tx.set_gas(gas);
+
+    let sig = signer.sign_transaction(&tx).await.map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to sign transaction ({})", e),
+        )
+    })?;
+    let pending = provider
+        .send_raw_transaction(tx.rlp_signed(&sig))
+        .await
+        .map_err(|e| rpc_error("eth_sendRawTransaction", e))?;
+    let tx_hash = pending.tx_hash();
+    log::info!("sent transaction 0x{:x}, waiting for the receipt", tx_hash);
+
+    let receipt = pending
+        .await
+        .map_err(|e| rpc_error("eth_getTransactionReceipt", e))?
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::Other,
+                format!("transaction 0x{:x} dropped from the mempool", tx_hash),
+            )
+        })?;
+    if receipt.status != Some(1.into()) {
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!(
+                "transaction 0x{:x} failed (status {:?})",
+                tx_hash, receipt.status
+            ),
+        ));
+    }
+    Ok(tx_hash)
+}
+
+fn rpc_error(method: &str, e: impl std::fmt::Display) -> Error {
+    Error::new(ErrorKind::Other, format!("failed '{method}' ({})", e))
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-kms --bin avalanche-kms -- evm_tx::test_transfer_request --exact --show-output
+#[test]
+fn test_transfer_request() {
+    use ethers_core::types::Transaction;
+
+    // ewoq test key
+    let signer: LocalWallet = "56289e99c94b6912bfc12adc093c9b51124f0dc54ac7a766b2bc5ccf558d8027"
+        .parse::<LocalWallet>()
+        .unwrap()
+        .with_chain_id(43112u64);
+    let from = signer.address();
+    let to = H160::from_low_u64_be(1);
+
+    let mut tx = transfer_request(
+        from,
+        to,
+        U256::from(100),
+        U256::zero(),
+        43112,
+        Fees::Eip1559 {
+            max_fee_per_gas: U256::from(30_000_000_000u64),
+            max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+        },
+    );
+    tx.set_gas(21000);
+    let sig = signer.sign_transaction_sync(&tx).unwrap();
+    let raw = tx.rlp_signed(&sig);
+    // EIP-2718 type-2 envelope
+    assert_eq!(raw[0], 0x02);
+    let decoded: Transaction = ethers_core::utils::rlp::decode(&raw).unwrap();
+    assert_eq!(decoded.transaction_type, Some(2.into()));
+    assert_eq!(decoded.chain_id, Some(43112.into()));
+    assert_eq!(decoded.max_fee_per_gas, Some(30_000_000_000u64.into()));
+    assert_eq!(
+        decoded.max_priority_fee_per_gas,
+        Some(1_000_000_000u64.into())
+    );
+    assert_eq!(decoded.recover_from().unwrap(), from);
+
+    let mut tx = transfer_request(
+        from,
+        to,
+        U256::from(100),
+        U256::zero(),
+        43112,
+        Fees::Legacy {
+            gas_price: U256::from(25_000_000_000u64),
+        },
+    );
+    tx.set_gas(21000);
+    let sig = signer.sign_transaction_sync(&tx).unwrap();
+    let raw = tx.rlp_signed(&sig);
+    // legacy RLP list, not a typed envelope
+    assert!(raw[0] >= 0xc0);
+    let decoded: Transaction = ethers_core::utils::rlp::decode(&raw).unwrap();
+    assert_eq!(decoded.transaction_type, None);
+    assert_eq!(decoded.chain_id, Some(43112.into()));
+    assert_eq!(decoded.gas_price, Some(25_000_000_000u64.into()));
+    assert_eq!(decoded.recover_from().unwrap(), from);
+}
@@ -0,0 +1,115 @@
+use std::io::{self, Error, ErrorKind};
+
+use aws_manager::{self, kms, sts};
+use clap::{Arg, Command};
+use crossterm::style::Color;
+use tokio::time::Duration;
+
+use crate::color;
+
+pub const NAME: &str = "grant";
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Grants the AWS KMS key signing permissions (Sign, GetPublicKey, DescribeKey) to a principal")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("REGION")
+                .long("region")
+                .short('r')
+                .help("Sets the AWS region for API calls/endpoints")
+                .required(true)
+                .num_args(1)
+                .default_value("us-west-2"),
+        )
+        .arg(
+            Arg::new("KEY_ARN")
+                .long("key-arn")
+                .short('a')
+                .help("KMS key ARN")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("GRANTEE_PRINCIPAL")
+                .long("grantee-principal")
+                .help("KMS key grantee principal ARN (e.g., the IAM role ARN that runs avalanche-ops)")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("PROFILE_NAME")
+                .long("profile-name")
+                .help("Sets the AWS credential profile name for API calls/endpoints")
+                .required(false)
+                .default_value("default")
+                .num_args(1),
+        )
+}
+
+pub async fn execute(
+    log_level: &str,
+    color_enabled: bool,
+    region: &str,
+    key_arn: &str,
+    grantee_principal: &str,
+    profile_name: String,
+) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, log_level),
+    );
+
+    if !grantee_principal.starts_with("arn:") {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid grantee principal '{grantee_principal}' (expected an ARN)"),
+        ));
+    }
+
+    log::info!("requesting to grant {key_arn} ({region}) to {grantee_principal}");
+
+    let shared_config = aws_manager::load_config(
+        Some(region.to_string()),
+        Some(profile_name),
+        Some(Duration::from_secs(30)),
+    )
+    .await;
+    let kms_manager = kms::Manager::new(&shared_config);
+
+    let sts_manager = sts::Manager::new(&shared_config);
+    let current_identity = sts_manager.get_identity().await.unwrap();
+    log::info!("current identity {:?}", current_identity);
+    println!();
+
+    color::print(
+        color_enabled,
+        Color::Green,
+        format!("\nGranting the KMS key {key_arn} in region {region} to {grantee_principal}\n"),
+    )?;
+    let (grant_id, grant_token) = kms_manager
+        .create_grant_for_sign_reads(key_arn, grantee_principal)
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to grant {key_arn} to {grantee_principal} ({})", e),
+            )
+        })?;
+
+    println!();
+    log::info!("successfully granted KMS key signer");
+    println!("grant id: {grant_id}");
+    println!("grant token: {grant_token}");
+
+    Ok(())
+}
@@ -0,0 +1,373 @@
+use std::{
+    fs,
+    io::{self, Error, ErrorKind},
+    path::Path,
+};
+
+use avalanche_types::key::secp256k1;
+use aws_manager::{self, kms, sts};
+use aws_sdk_kms::{
+    primitives::Blob,
+    types::{
+        AlgorithmSpec, ExpirationModelType, KeySpec, KeyUsageType, OriginType, Tag, WrappingKeySpec,
+    },
+};
+use clap::{Arg, Command};
+use crossterm::style::Color;
+use k256::pkcs8::EncodePrivateKey;
+use rsa::{
+    pkcs8::DecodePublicKey,
+    rand_core::{OsRng, RngCore},
+    Oaep, RsaPublicKey,
+};
+use tokio::time::Duration;
+use zeroize::Zeroizing;
+
+use crate::color;
+
+pub const NAME: &str = "import";
+
+/// Wrapping algorithm for the asymmetric key material.
+/// ref. <https://docs.aws.amazon.com/kms/latest/developerguide/importing-keys-get-public-key-and-token.html>
+const WRAPPING_ALGORITHM: &str = "RSA_AES_KEY_WRAP_SHA_256";
+const WRAPPING_KEY_SPEC: &str = "RSA_4096";
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Imports an existing secp256k1 private key into a new AWS KMS key (external key material)")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("REGION")
+                .long("region")
+                .short('r')
+                .help("Sets the AWS region for API calls/endpoints")
+                .required(true)
+                .num_args(1)
+                .default_value("us-west-2"),
+        )
+        .arg(
+            Arg::new("ALIAS")
+                .long("alias")
+                .help("Sets the KMS key alias name without the 'alias/' prefix (assigned once the imported key is verified)")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("PRIVATE_KEY")
+                .long("private-key")
+                .help("Hex-encoded private key, or the path to the file with the hex-encoded private key")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("PROFILE_NAME")
+                .long("profile-name")
+                .help("Sets the AWS credential profile name for API calls/endpoints")
+                .required(false)
+                .default_value("default")
+                .num_args(1),
+        )
+}
+
+pub async fn execute(
+    log_level: &str,
+    color_enabled: bool,
+    region: &str,
+    alias: &str,
+    private_key: &str,
+    profile_name: String,
+) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, log_level),
+    );
+
+    let alias = alias.trim_start_matches("alias/");
+    if alias.is_empty() || alias.starts_with("aws/") {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid alias '{alias}' (empty or the reserved 'aws/' prefix)"),
+        ));
+    }
+    let alias_name = format!("alias/{alias}");
+
+    let private_key_hex = Zeroizing::new(if Path::new(private_key).is_file() {
+        Zeroizing::new(fs::read_to_string(private_key)?)
+            .trim()
+            .to_string()
+    } else {
+        private_key.trim().to_string()
+    });
+    let source_key =
+        secp256k1::private_key::Key::from_hex(private_key_hex.as_str()).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "invalid private key (expected a private key in hex format) ({})",
+                    e
+                ),
+            )
+        })?;
+    let source_info = source_key.to_info(1).unwrap();
+    let private_key_bytes = Zeroizing::new(
+        hex::decode(private_key_hex.trim_start_matches("0x"))
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("invalid hex ({})", e)))?,
+    );
+    let key_material = to_pkcs8_der(&private_key_bytes)?;
+
+    let shared_config = aws_manager::load_config(
+        Some(region.to_string()),
+        Some(profile_name),
+        Some(Duration::from_secs(30)),
+    )
+    .await;
+    let kms_manager = kms::Manager::new(&shared_config);
+
+    let sts_manager = sts::Manager::new(&shared_config);
+    let current_identity = sts_manager.get_identity().await.unwrap();
+    log::info!("current identity {:?}", current_identity);
+    println!();
+
+    crate::create::check_alias_available(&kms_manager, &alias_name).await?;
+
+    color::print(
+        color_enabled,
+        Color::Green,
+        format!(
+            "\nImporting the key {} into a new KMS key '{alias_name}' in region {region}\n",
+            source_info.eth_address
+        ),
+    )?;
+    let out = kms_manager
+        .cli
+        .create_key()
+        .key_spec(KeySpec::EccSecgP256K1)
+        .key_usage(KeyUsageType::SignVerify)
+        .origin(OriginType::External)
+        .tags(Tag::builder().tag_key("Name").tag_value(alias).build())
+        .send()
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to create KMS key for import ({:?})", e),
+            )
+        })?;
+    let key_arn = out
+        .key_metadata()
+        .and_then(|m| m.arn())
+        .ok_or_else(|| Error::new(ErrorKind::Other, "created KMS key has no ARN"))?
+        .to_string();
+    log::info!("created KMS key {key_arn} pending import");
+
+    // the key is unusable until the import succeeds, delete it on any failure
+    if let Err(e) = import_key_material(&kms_manager, &key_arn, &key_material).await {
+        schedule_to_delete(&kms_manager, &key_arn).await;
+        return Err(e);
+    }
+
+    let imported_key = match secp256k1::kms::aws::Key::from_arn(kms_manager.clone(), &key_arn).await
+    {
+        Ok(k) => k,
+        Err(e) => {
+            schedule_to_delete(&kms_manager, &key_arn).await;
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!(
+                    "failed to load imported KMS key '{key_arn}' ({}), scheduled the KMS key to delete",
+                    e
+                ),
+            ));
+        }
+    };
+    let imported_info = imported_key.to_info(1).unwrap();
+    if imported_info.eth_address != source_info.eth_address
+        || imported_info.h160_address != source_info.h160_address
+    {
+        schedule_to_delete(&kms_manager, &key_arn).await;
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "imported KMS key '{key_arn}' derives {} but the source key derives {}, scheduled the KMS key to delete",
+                imported_info.eth_address, source_info.eth_address
+            ),
+        ));
+    }
+    log::info!("verified imported KMS key {key_arn} derives the source key addresses");
+
+    // an unaliased key would be left behind unnoticed, so delete it as well
+    if let Err(e) = kms_manager
+        .cli
+        .create_alias()
+        .alias_name(&alias_name)
+        .target_key_id(&key_arn)
+        .send()
+        .await
+    {
+        schedule_to_delete(&kms_manager, &key_arn).await;
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!(
+                "failed to create alias '{alias_name}' for {key_arn} ({:?}), scheduled the KMS key to delete",
+                e
+            ),
+        ));
+    }
+
+    println!();
+    println!(
+        "imported KMS key with alias '{alias_name}'\n\n{}\n",
+        imported_info
+    );
+    color::print(
+        color_enabled,
+        Color::Yellow,
+        "\nThe source private key still exists: delete its copies once the KMS key is in use\n",
+    )?;
+
+    Ok(())
+}
+
+/// Fetches the wrapping public key and the import token, and imports the
+/// wrapped key material that never expires.
+/// ref. <https://docs.aws.amazon.com/kms/latest/developerguide/importing-keys.html>
+async fn import_key_material(
+    kms_manager: &kms::Manager,
+    key_arn: &str,
+    key_material: &[u8],
+) -> io::Result<()> {
+    let params = kms_manager
+        .cli
+        .get_parameters_for_import()
+        .key_id(key_arn)
+        .wrapping_algorithm(AlgorithmSpec::from(WRAPPING_ALGORITHM))
+        .wrapping_key_spec(WrappingKeySpec::from(WRAPPING_KEY_SPEC))
+        .send()
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed get_parameters_for_import for '{key_arn}' ({:?})", e),
+            )
+        })?;
+    let (wrapping_public_key, import_token) = match (params.public_key(), params.import_token()) {
+        (Some(k), Some(t)) => (k.as_ref().to_vec(), t.clone()),
+        _ => {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("no wrapping public key or import token for '{key_arn}'"),
+            ))
+        }
+    };
+
+    let encrypted_key_material = wrap_key_material(key_material, &wrapping_public_key)?;
+    kms_manager
+        .cli
+        .import_key_material()
+        .key_id(key_arn)
+        .import_token(import_token)
+        .encrypted_key_material(Blob::new(encrypted_key_material))
+        .expiration_model(ExpirationModelType::KeyMaterialDoesNotExpire)
+        .send()
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed import_key_material for '{key_arn}' ({:?})", e),
+            )
+        })?;
+    log::info!("imported key material into {key_arn}");
+    Ok(())
+}
+
+async fn schedule_to_delete(kms_manager: &kms::Manager, key_arn: &str) {
+    // 7 days is the minimum pending window
+    if let Err(e) = kms_manager.schedule_to_delete(key_arn, 7).await {
+        log::warn!("failed to schedule to delete KMS key '{key_arn}' ({e}), delete it manually");
+    }
+}
+
+/// Encodes the secp256k1 private key in PKCS #8 DER, as required by the import.
+fn to_pkcs8_der(private_key: &[u8]) -> io::Result<Zeroizing<Vec<u8>>> {
+    let secret_key = k256::SecretKey::from_slice(private_key).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid secp256k1 private key ({})", e),
+        )
+    })?;
+    let der = secret_key.to_pkcs8_der().map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to encode the private key in PKCS #8 ({})", e),
+        )
+    })?;
+    Ok(Zeroizing::new(der.as_bytes().to_vec()))
+}
+
+/// Wraps the key material with "RSA_AES_KEY_WRAP_SHA_256": the key material is
+/// wrapped with a random AES-256 key (RFC 5649 key wrap with padding), and the
+/// AES key is encrypted with the RSA wrapping public key (RSAES-OAEP SHA-256).
+/// Returns the RSA ciphertext followed by the wrapped key material.
+fn wrap_key_material(key_material: &[u8], wrapping_public_key_der: &[u8]) -> io::Result<Vec<u8>> {
+    let wrapping_key = RsaPublicKey::from_public_key_der(wrapping_public_key_der).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("invalid KMS wrapping public key ({})", e),
+        )
+    })?;
+
+    let mut aes_key = Zeroizing::new([0u8; 32]);
+    OsRng.fill_bytes(aes_key.as_mut());
+    let wrapped_key_material = aes_kw::KekAes256::from(*aes_key)
+        .wrap_with_padding_vec(key_material)
+        .map_err(|e| Error::new(ErrorKind::Other, format!("failed AES key wrap ({})", e)))?;
+    let mut encrypted = wrapping_key
+        .encrypt(&mut OsRng, Oaep::new::<sha2::Sha256>(), aes_key.as_ref())
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed RSA-OAEP encryption ({})", e),
+            )
+        })?;
+    encrypted.extend(wrapped_key_material);
+    Ok(encrypted)
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-kms --bin avalanche-kms -- import::test_wrap_key_material --exact --show-output
+#[test]
+fn test_wrap_key_material() {
+    use rsa::{pkcs8::EncodePublicKey, RsaPrivateKey};
+
+    let private_key =
+        hex::decode("56289e99c94b6912bfc12adc093c9b51124f0dc54ac7a766b2bc5ccf558d8027").unwrap();
+    let key_material = to_pkcs8_der(&private_key).unwrap();
+    assert!(to_pkcs8_der(&[0u8; 32]).is_err());
+
+    // 2048-bit to keep the test fast (KMS uses 4096-bit)
+    let wrapping_key = RsaPrivateKey::new(&mut OsRng, 2048).unwrap();
+    let wrapping_public_key_der = wrapping_key.to_public_key().to_public_key_der().unwrap();
+    let encrypted = wrap_key_material(&key_material, wrapping_public_key_der.as_bytes()).unwrap();
+
+    // RSA-OAEP ciphertext (of the RSA key size) followed by the wrapped key material
+    let (encrypted_aes_key, wrapped_key_material) = encrypted.split_at(256);
+    let aes_key: [u8; 32] = wrapping_key
+        .decrypt(Oaep::new::<sha2::Sha256>(), encrypted_aes_key)
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let unwrapped = aes_kw::KekAes256::from(aes_key)
+        .unwrap_with_padding_vec(wrapped_key_material)
+        .unwrap();
+    assert_eq!(unwrapped, *key_material);
+
+    assert!(wrap_key_material(&key_material, b"invalid").is_err());
+}
@@ -1,5 +1,5 @@
 use std::{
-    io::{self, stdout},
+    io::{self, Error, ErrorKind},
     str::FromStr,
 };
 
@@ -9,12 +9,11 @@ use avalanche_types::{
     units, utils,
 };
 use aws_manager::{self, kms, sts};
-use clap::{Arg, Command};
-use crossterm::{
-    execute,
-    style::{Color, Print, ResetColor, SetForegroundColor},
-};
-use tokio::time::Duration;
+use clap::{value_parser, Arg, Command};
+use crossterm::style::Color;
+use tokio::time::{sleep, Duration, Instant};
+
+use crate::color;
 
 pub const NAME: &str = "info";
 
@@ -70,21 +69,50 @@ pub fn command() -> Command {
                 .default_value("default")
                 .num_args(1),
         )
+        .arg(
+            Arg::new("KMS_LOAD_TIMEOUT_SECONDS")
+                .long("kms-load-timeout-seconds")
+                .help("Sets the timeout in seconds to retry loading the KMS key while not found (e.g., right after its creation, until KMS is consistent; 0 to try once)")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("KMS_LOAD_INTERVAL_SECONDS")
+                .long("kms-load-interval-seconds")
+                .help("Sets the interval in seconds between the KMS key load retries")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("10"),
+        )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn execute(
     log_level: &str,
+    color_enabled: bool,
     region: &str,
     key_type: &str,
     key: &str,
     chain_rpc_url: &str,
     profile_name: String,
+    kms_load_timeout: Duration,
+    kms_load_interval: Duration,
 ) -> io::Result<()> {
     // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
     env_logger::init_from_env(
         env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, log_level),
     );
 
+    if kms_load_interval.is_zero() && !kms_load_timeout.is_zero() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "--kms-load-interval-seconds must be greater than 0 to retry",
+        ));
+    }
+
     log::info!(
         "requesting info for KMS key {key_type} ({region}) with chain RPC URL '{chain_rpc_url}'"
     );
@@ -122,20 +150,19 @@ pub async fn execute(
     log::info!("current identity {:?}", current_identity);
     println!();
 
-    execute!(
-        stdout(),
-        SetForegroundColor(Color::Green),
-        Print(format!(
+    color::print(
+        color_enabled,
+        Color::Green,
+        format!(
             "\nLoading the hotkey or KMS key {} in region {}\n",
             key, region
-        )),
-        ResetColor
+        ),
     )?;
     let converted_key_type = KeyType::from_str(key_type).unwrap();
     match converted_key_type {
         KeyType::AwsKms => {
             let kms_manager = kms::Manager::new(&shared_config);
-            let key = secp256k1::kms::aws::Key::from_arn(kms_manager.clone(), key)
+            let key = load_kms_key(&kms_manager, key, kms_load_timeout, kms_load_interval)
                 .await
                 .unwrap();
             let key_info = key.to_info(network_id).unwrap();
@@ -180,3 +207,59 @@ pub async fn execute(
 
     Ok(())
 }
+
+/// Loads the KMS key, retrying the not found errors until the timeout, as a
+/// newly created key may not be available right away (KMS is eventually
+/// consistent). Other errors (e.g., access denied) fail right away.
+async fn load_kms_key(
+    kms_manager: &kms::Manager,
+    key_arn: &str,
+    timeout: Duration,
+    interval: Duration,
+) -> io::Result<secp256k1::kms::aws::Key> {
+    let start = Instant::now();
+    loop {
+        match secp256k1::kms::aws::Key::from_arn(kms_manager.clone(), key_arn).await {
+            Ok(key) => return Ok(key),
+            Err(e) => {
+                if !is_kms_key_not_found(&e) || start.elapsed() + interval > timeout {
+                    return Err(Error::new(
+                        ErrorKind::Other,
+                        format!(
+                            "failed to load KMS key '{key_arn}' in {:?} ({})",
+                            start.elapsed(),
+                            e
+                        ),
+                    ));
+                }
+                log::warn!(
+                    "failed to load KMS key '{key_arn}' ({}), retrying in {:?}",
+                    e,
+                    interval
+                );
+                sleep(interval).await;
+            }
+        }
+    }
+}
+
+/// Returns true if the KMS key load failed as not found (the KMS
+/// "NotFoundException" of a key not yet propagated), to retry.
+fn is_kms_key_not_found(e: &avalanche_types::errors::Error) -> bool {
+    e.contains("NotFoundException")
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-kms --bin avalanche-kms -- info::test_is_kms_key_not_found --exact --show-output
+#[test]
+fn test_is_kms_key_not_found() {
+    let api_error = |message: &str| avalanche_types::errors::Error::API {
+        message: message.to_string(),
+        retryable: false,
+    };
+    assert!(is_kms_key_not_found(&api_error(
+        "failed get_public_key ServiceError { err: NotFoundException(NotFoundException { message: Some(\"Key 'arn:aws:kms:us-west-2:123:key/abc' does not exist\") }) }"
+    )));
+    assert!(!is_kms_key_not_found(&api_error(
+        "failed get_public_key ServiceError { err: AccessDeniedException(..) }"
+    )));
+}
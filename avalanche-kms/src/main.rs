@@ -1,7 +1,13 @@
+mod color;
 mod create;
 mod delete;
+mod derive_address;
 mod evm_balance;
+mod evm_signer;
 mod evm_transfer_from_hotkey;
+mod evm_tx;
+mod grant;
+mod import;
 mod info;
 
 use std::{
@@ -10,8 +16,9 @@ use std::{
 };
 
 use avalanche_types::{key::secp256k1::KeyType, units};
-use clap::{crate_version, Command};
+use clap::{crate_version, Arg, Command};
 use primitive_types::{H160, U256};
+use tokio::time::Duration;
 
 const APP_NAME: &str = "avalanche-kms";
 
@@ -20,15 +27,33 @@ async fn main() -> io::Result<()> {
     let matches = Command::new(APP_NAME)
         .version(crate_version!())
         .about("Avalanche KMS AWS tools")
+        .arg(
+            Arg::new("NO_COLOR")
+                .long("no-color")
+                .help("Disables the colored output (also disabled if NO_COLOR is set or stdout is not a terminal)")
+                .required(false)
+                .num_args(0)
+                .global(true),
+        )
         .subcommands(vec![
             evm_balance::command(),
             create::command(),
             delete::command(),
+            grant::command(),
+            import::command(),
             info::command(),
+            derive_address::command(),
             evm_transfer_from_hotkey::command(),
         ])
         .get_matches();
 
+    let no_color = matches.get_flag("NO_COLOR")
+        || matches
+            .subcommand()
+            .map(|(_, sub_matches)| sub_matches.get_flag("NO_COLOR"))
+            .unwrap_or(false);
+    let color_enabled = avalanche_ops::color::enabled(no_color);
+
     match matches.subcommand() {
         Some((evm_balance::NAME, sub_matches)) => {
             let addr = sub_matches
@@ -113,11 +138,22 @@ async fn main() -> io::Result<()> {
                 .unwrap_or(&random_manager::tmp_path(15, Some(".yaml"))?)
                 .clone();
 
+            let alias = sub_matches
+                .get_one::<String>("ALIAS")
+                .unwrap_or(&String::new())
+                .clone();
+            let tags = create::parse_tags(
+                sub_matches
+                    .get_one::<String>("TAGS")
+                    .unwrap_or(&String::new()),
+            )?;
+
             create::execute(
                 &sub_matches
                     .get_one::<String>("LOG_LEVEL")
                     .unwrap_or(&String::from("info"))
                     .clone(),
+                color_enabled,
                 &sub_matches.get_one::<String>("REGION").unwrap().clone(),
                 key_type,
                 &key_name_prefix,
@@ -126,10 +162,13 @@ async fn main() -> io::Result<()> {
                 *sub_matches
                     .get_one::<usize>("KEYS_FILE_CHUNKS")
                     .unwrap_or(&1),
+                &alias,
+                tags,
                 &grantee_principal,
                 &evm_chain_rpc_url,
                 &evm_funding_hotkey,
                 evm_funding_amount_navax,
+                sub_matches.get_flag("LEGACY_TX"),
                 sub_matches.get_flag("SKIP_PROMPT"),
                 sub_matches
                     .get_one::<String>("PROFILE_NAME")
@@ -150,6 +189,7 @@ async fn main() -> io::Result<()> {
                     .get_one::<String>("LOG_LEVEL")
                     .unwrap_or(&String::from("info"))
                     .clone(),
+                color_enabled,
                 &sub_matches.get_one::<String>("REGION").unwrap().clone(),
                 &sub_matches.get_one::<String>("KEY_ARN").unwrap().clone(),
                 pending_windows_in_days,
@@ -163,12 +203,57 @@ async fn main() -> io::Result<()> {
             .unwrap();
         }
 
+        Some((import::NAME, sub_matches)) => {
+            import::execute(
+                &sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+                color_enabled,
+                &sub_matches.get_one::<String>("REGION").unwrap().clone(),
+                &sub_matches.get_one::<String>("ALIAS").unwrap().clone(),
+                &sub_matches
+                    .get_one::<String>("PRIVATE_KEY")
+                    .unwrap()
+                    .clone(),
+                sub_matches
+                    .get_one::<String>("PROFILE_NAME")
+                    .unwrap()
+                    .clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        Some((grant::NAME, sub_matches)) => {
+            grant::execute(
+                &sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+                color_enabled,
+                &sub_matches.get_one::<String>("REGION").unwrap().clone(),
+                &sub_matches.get_one::<String>("KEY_ARN").unwrap().clone(),
+                &sub_matches
+                    .get_one::<String>("GRANTEE_PRINCIPAL")
+                    .unwrap()
+                    .clone(),
+                sub_matches
+                    .get_one::<String>("PROFILE_NAME")
+                    .unwrap()
+                    .clone(),
+            )
+            .await
+            .unwrap();
+        }
+
         Some((info::NAME, sub_matches)) => {
             info::execute(
                 &sub_matches
                     .get_one::<String>("LOG_LEVEL")
                     .unwrap_or(&String::from("info"))
                     .clone(),
+                color_enabled,
                 &sub_matches.get_one::<String>("REGION").unwrap().clone(),
                 &sub_matches.get_one::<String>("KEY_TYPE").unwrap().clone(),
                 &sub_matches.get_one::<String>("KEY").unwrap().clone(),
@@ -180,11 +265,37 @@ async fn main() -> io::Result<()> {
                     .get_one::<String>("PROFILE_NAME")
                     .unwrap()
                     .clone(),
+                Duration::from_secs(
+                    *sub_matches
+                        .get_one::<u64>("KMS_LOAD_TIMEOUT_SECONDS")
+                        .unwrap_or(&0),
+                ),
+                Duration::from_secs(
+                    *sub_matches
+                        .get_one::<u64>("KMS_LOAD_INTERVAL_SECONDS")
+                        .unwrap_or(&10),
+                ),
             )
             .await
             .unwrap();
         }
 
+        Some((derive_address::NAME, sub_matches)) => {
+            derive_address::execute(
+                &sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+                color_enabled,
+                &sub_matches
+                    .get_one::<String>("PUBLIC_KEY_HEX")
+                    .unwrap()
+                    .clone(),
+                *sub_matches.get_one::<u32>("NETWORK_ID").unwrap_or(&1),
+            )
+            .unwrap();
+        }
+
         Some((evm_transfer_from_hotkey::NAME, sub_matches)) => {
             let transferer_key = sub_matches
                 .get_one::<String>("TRANSFERER_KEY")
@@ -238,6 +349,7 @@ async fn main() -> io::Result<()> {
                     .get_one::<String>("LOG_LEVEL")
                     .unwrap_or(&String::from("info"))
                     .clone(),
+                color_enabled,
                 &sub_matches
                     .get_one::<String>("CHAIN_RPC_URL")
                     .unwrap_or(&String::new())
@@ -245,6 +357,7 @@ async fn main() -> io::Result<()> {
                 &transferer_key,
                 transfer_amount_navax,
                 transferee_addrs,
+                sub_matches.get_flag("LEGACY_TX"),
                 sub_matches.get_flag("SKIP_PROMPT"),
             )
             .await
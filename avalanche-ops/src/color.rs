@@ -0,0 +1,24 @@
+use std::io::{stdout, IsTerminal};
+
+/// Returns true if the colored output is enabled: not disabled by the flag
+/// (e.g., "--no-color") or the "NO_COLOR" environment variable, and stdout
+/// is a terminal. The callers pass the result down, instead of mutating the
+/// process environment.
+/// ref. <https://no-color.org>
+pub fn enabled(no_color: bool) -> bool {
+    if no_color {
+        return false;
+    }
+    if let Some(v) = std::env::var_os("NO_COLOR") {
+        if !v.is_empty() {
+            return false;
+        }
+    }
+    stdout().is_terminal()
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-ops --lib -- color::test_enabled --exact --show-output
+#[test]
+fn test_enabled() {
+    assert!(!enabled(true));
+}
@@ -1,2 +1,4 @@
 pub mod artifacts;
 pub mod aws;
+pub mod color;
+pub mod logger;
@@ -0,0 +1,105 @@
+use std::{
+    fs::{self, File},
+    io::{self, Error, ErrorKind},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Initializes the logger to stderr at the log level (overridable via "RUST_LOG").
+/// If the log file path is not empty, also writes all logs at "debug" level to the file,
+/// suffixed with the current Unix timestamp so that repeated runs do not overwrite each other
+/// (e.g., "install.log" becomes "install-1685000000.log").
+/// Returns the path of the log file, if any.
+pub fn init(log_level: &str, log_file: &str) -> io::Result<Option<String>> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    let env = env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, log_level);
+    if log_file.is_empty() {
+        env_logger::init_from_env(env);
+        return Ok(None);
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let file_path = timestamped_path(log_file, now);
+    if let Some(parent_dir) = Path::new(&file_path).parent() {
+        if !parent_dir.as_os_str().is_empty() {
+            fs::create_dir_all(parent_dir)?;
+        }
+    }
+    let f = File::create(&file_path).map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to create log file '{file_path}' ({})", e),
+        )
+    })?;
+
+    let console = env_logger::Builder::from_env(env).build();
+    let file = env_logger::Builder::new()
+        .filter_level(log::LevelFilter::Debug)
+        .write_style(env_logger::WriteStyle::Never)
+        .target(env_logger::Target::Pipe(Box::new(f)))
+        .build();
+    let max_level = console.filter().max(file.filter());
+
+    log::set_boxed_logger(Box::new(Tee { console, file }))
+        .map_err(|e| Error::new(ErrorKind::Other, format!("failed to set logger ({})", e)))?;
+    log::set_max_level(max_level);
+
+    log::info!("writing logs to '{file_path}'");
+    Ok(Some(file_path))
+}
+
+/// Returns the file path with the timestamp inserted before the extension.
+pub fn timestamped_path(file_path: &str, timestamp: u64) -> String {
+    let path = Path::new(file_path);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(ext) => format!("{stem}-{timestamp}.{}", ext.to_string_lossy()),
+        None => format!("{stem}-{timestamp}"),
+    };
+    path.with_file_name(file_name).display().to_string()
+}
+
+/// Writes each record to both loggers, each with its own level filter.
+struct Tee {
+    console: env_logger::Logger,
+    file: env_logger::Logger,
+}
+
+impl log::Log for Tee {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.console.enabled(metadata) || self.file.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.console.log(record);
+        self.file.log(record);
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+        self.file.flush();
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-ops --lib -- logger::test_timestamped_path --exact --show-output
+#[test]
+fn test_timestamped_path() {
+    assert_eq!(
+        timestamped_path("install.log", 1685000000),
+        "install-1685000000.log"
+    );
+    assert_eq!(
+        timestamped_path("/tmp/logs/install.log", 1685000000),
+        "/tmp/logs/install-1685000000.log"
+    );
+    assert_eq!(
+        timestamped_path("/tmp/logs/install", 1685000000),
+        "/tmp/logs/install-1685000000"
+    );
+}
@@ -0,0 +1,126 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Error, ErrorKind},
+    path::Path,
+    str::FromStr,
+};
+
+use avalanche_types::ids;
+use clap::{Arg, Command};
+use serde::{Deserialize, Serialize};
+
+pub const NAME: &str = "alias-chain";
+
+/// Defines "alias-chain" option.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Flags {
+    pub log_level: String,
+
+    pub blockchain_id: String,
+    pub chain_alias: String,
+    pub chain_aliases_path: String,
+}
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Adds a blockchain alias to the avalanchego chain aliases file (merges with existing aliases)")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("BLOCKCHAIN_ID")
+                .long("blockchain-id")
+                .help("Blockchain Id to alias")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("CHAIN_ALIAS")
+                .long("chain-alias")
+                .help("Human-readable alias for the blockchain")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("CHAIN_ALIASES_PATH")
+                .long("chain-aliases-path")
+                .help("avalanchego chain aliases file path (see avalanchego '--chain-aliases-file')")
+                .required(true)
+                .num_args(1),
+        )
+}
+
+pub fn execute(opts: Flags) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, opts.log_level),
+    );
+
+    let blockchain_id = ids::Id::from_str(&opts.blockchain_id)?;
+    log::info!(
+        "aliasing blockchain '{blockchain_id}' to '{}' in {}",
+        opts.chain_alias,
+        opts.chain_aliases_path
+    );
+
+    // e.g., {"2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt": ["subnetevm"]}
+    let path = Path::new(&opts.chain_aliases_path);
+    let mut aliases: BTreeMap<String, Vec<String>> = if path.exists() {
+        let b = fs::read(path)?;
+        serde_json::from_slice(&b).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "failed to parse chain aliases file '{}' ({})",
+                    opts.chain_aliases_path, e
+                ),
+            )
+        })?
+    } else {
+        BTreeMap::new()
+    };
+
+    for (id, existing) in aliases.iter() {
+        if *id != blockchain_id.to_string() && existing.contains(&opts.chain_alias) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "alias '{}' is already used by the blockchain '{id}'",
+                    opts.chain_alias
+                ),
+            ));
+        }
+    }
+
+    let chain_aliases = aliases.entry(blockchain_id.to_string()).or_default();
+    if chain_aliases.contains(&opts.chain_alias) {
+        log::info!("alias '{}' already exists; skipping...", opts.chain_alias);
+        return Ok(());
+    }
+    chain_aliases.push(opts.chain_alias.clone());
+
+    if let Some(parent_dir) = path.parent() {
+        log::info!(
+            "creating parent dir '{}' for chain aliases",
+            parent_dir.display()
+        );
+        fs::create_dir_all(parent_dir)?;
+    }
+    let d = serde_json::to_vec_pretty(&aliases).map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to serialize chain aliases ({})", e),
+        )
+    })?;
+    fs::write(path, d)?;
+
+    Ok(())
+}
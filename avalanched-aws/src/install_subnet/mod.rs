@@ -28,6 +28,7 @@ pub struct Flags {
     pub vm_binary_local_path: String,
 
     pub subnet_id_to_track: String,
+    pub track_subnets_mode: String,
     pub avalanchego_config_path: String,
 }
 
@@ -77,15 +78,15 @@ pub fn command() -> Command {
         .arg(
             Arg::new("VM_BINARY_S3_KEY")
                 .long("vm-binary-s3-key")
-                .help("Download VM binary from S3")
-                .required(true)
+                .help("Download VM binary from S3 (if empty, do not download)")
+                .required(false)
                 .num_args(1),
         )
         .arg(
             Arg::new("VM_BINARY_LOCAL_PATH")
                 .long("vm-binary-local-path")
-                .help("VM binary local file path")
-                .required(true)
+                .help("VM binary local file path (required with --vm-binary-s3-key)")
+                .required(false)
                 .num_args(1),
         )
         .arg(
@@ -95,6 +96,15 @@ pub fn command() -> Command {
                 .required(true)
                 .num_args(1),
         )
+        .arg(
+            Arg::new("TRACK_SUBNETS_MODE")
+                .long("track-subnets-mode")
+                .help("Sets whether to merge the subnet Id into the existing track-subnets or replace them")
+                .required(false)
+                .num_args(1)
+                .value_parser(["merge", "replace"])
+                .default_value("merge"),
+        )
         .arg(
             Arg::new("AVALANCHEGO_CONFIG_PATH")
                 .long("avalanchego-config-path")
@@ -155,7 +165,13 @@ pub async fn execute(opts: Flags) -> io::Result<()> {
         log::info!("skipping downloading subnet config since empty");
     }
 
-    {
+    if !opts.vm_binary_s3_key.is_empty() {
+        if opts.vm_binary_local_path.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "vm_binary_s3_key not empty but vm_binary_local_path empty",
+            ));
+        }
         let path = Path::new(&opts.vm_binary_local_path);
         if path.exists() {
             log::warn!(
@@ -185,13 +201,16 @@ pub async fn execute(opts: Flags) -> io::Result<()> {
         if !exists {
             return Err(Error::new(ErrorKind::Other, "vm binary s3 file not found"));
         }
+    } else {
+        log::info!("skipping downloading VM binary since empty");
     }
 
     {
         log::info!(
-            "adding a subnet-id '{}' to track-subnets flag in {}",
+            "adding a subnet-id '{}' to track-subnets flag in {} (mode '{}')",
             opts.subnet_id_to_track,
             opts.avalanchego_config_path,
+            opts.track_subnets_mode,
         );
         let converted = ids::Id::from_str(&opts.subnet_id_to_track)?;
         log::info!("validated a subnet-id '{}'", converted);
@@ -207,7 +226,18 @@ pub async fn execute(opts: Flags) -> io::Result<()> {
                 config.config_file = Some(opts.avalanchego_config_path.clone());
             }
         }
-        config.add_track_subnets(Some(converted.to_string()));
+        if opts.track_subnets_mode == "replace" {
+            log::warn!(
+                "replacing existing track-subnets {:?} with '{}'",
+                config.track_subnets,
+                converted
+            );
+            config.track_subnets = Some(converted.to_string());
+        } else {
+            // merges into the existing comma-separated list, so other tracked subnets are kept
+            config.add_track_subnets(Some(converted.to_string()));
+        }
+        log::info!("updated track-subnets to {:?}", config.track_subnets);
 
         config.sync(None)?;
     }
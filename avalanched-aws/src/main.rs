@@ -1,4 +1,5 @@
 mod agent;
+mod alias_chain;
 mod install_artifacts;
 mod install_chain;
 mod install_subnet;
@@ -14,6 +15,7 @@ async fn main() {
         .about("Runs an Avalanche agent (daemon) on AWS")
         .subcommands(vec![
             agent::command(),
+            alias_chain::command(),
             install_artifacts::command(),
             install_chain::command(),
             install_subnet::command(),
@@ -108,16 +110,20 @@ async fn main() {
                     .to_string(),
                 vm_binary_s3_key: sub_matches
                     .get_one::<String>("VM_BINARY_S3_KEY")
-                    .unwrap()
+                    .unwrap_or(&String::new())
                     .to_string(),
                 vm_binary_local_path: sub_matches
                     .get_one::<String>("VM_BINARY_LOCAL_PATH")
-                    .unwrap()
+                    .unwrap_or(&String::new())
                     .to_string(),
                 subnet_id_to_track: sub_matches
                     .get_one::<String>("SUBNET_ID_TO_TRACK")
                     .unwrap()
                     .to_string(),
+                track_subnets_mode: sub_matches
+                    .get_one::<String>("TRACK_SUBNETS_MODE")
+                    .unwrap_or(&String::from("merge"))
+                    .to_string(),
                 avalanchego_config_path: sub_matches
                     .get_one::<String>("AVALANCHEGO_CONFIG_PATH")
                     .unwrap()
@@ -154,6 +160,28 @@ async fn main() {
             .unwrap();
         }
 
+        Some((alias_chain::NAME, sub_matches)) => {
+            alias_chain::execute(alias_chain::Flags {
+                log_level: sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .to_string(),
+                blockchain_id: sub_matches
+                    .get_one::<String>("BLOCKCHAIN_ID")
+                    .unwrap()
+                    .to_string(),
+                chain_alias: sub_matches
+                    .get_one::<String>("CHAIN_ALIAS")
+                    .unwrap()
+                    .to_string(),
+                chain_aliases_path: sub_matches
+                    .get_one::<String>("CHAIN_ALIASES_PATH")
+                    .unwrap()
+                    .to_string(),
+            })
+            .unwrap();
+        }
+
         _ => unreachable!("unknown subcommand"),
     }
 }
@@ -5,7 +5,7 @@ use std::{
     sync::Arc,
 };
 
-use avalanche_types::{ids, jsonrpc::client::info as json_client_info, key, units, wallet};
+use avalanche_types::{ids, key, wallet};
 use clap::{value_parser, Arg, Command};
 use crossterm::{
     execute,
@@ -14,6 +14,7 @@ use crossterm::{
 use dialoguer::{theme::ColorfulTheme, Select};
 use serde::{Deserialize, Serialize};
 use tokio::time::{sleep, Duration};
+use zeroize::Zeroizing;
 
 pub const NAME: &str = "add-primary-network-validators";
 
@@ -24,9 +25,11 @@ pub struct Flags {
     pub skip_prompt: bool,
     pub spec_file_path: String,
     pub chain_rpc_url: String,
-    pub key: String,
+    pub key: Zeroizing<String>,
     pub primary_network_validate_period_in_days: u64,
     pub staking_amount_in_avax: u64,
+    pub stake_asset_symbol: String,
+    pub staking_decimals: u32,
     pub target_node_ids: Vec<String>,
 }
 
@@ -116,6 +119,7 @@ pub fn command() -> Command {
                 .value_parser(value_parser!(u64))
                 .default_value("2000"),
         )
+        .args(avalancheup_aws::install_subnet_chain::staking_asset_args())
         .arg(
             Arg::new("TARGET_NODE_IDS")
                 .long("target-node-ids")
@@ -151,26 +155,51 @@ pub async fn execute(opts: Flags) -> io::Result<()> {
         }
     }
 
-    let resp = json_client_info::get_network_id(&opts.chain_rpc_url)
-        .await
-        .unwrap();
-    let network_id = resp.result.unwrap().network_id;
+    // the first network calls, retried so that a transient RPC failure does not abort the run
+    let network_id = avalancheup_aws::install_subnet_chain::fetch_network_id(
+        &opts.chain_rpc_url,
+        &[],
+        &avalancheup_aws::install_subnet_chain::ApiPaths::default(),
+    )
+    .await?;
+    let network_name = avalancheup_aws::networks::display_name(network_id);
 
-    let priv_key = key::secp256k1::private_key::Key::from_hex(&opts.key).unwrap();
-    let wallet_to_spend = wallet::Builder::new(&priv_key)
-        .base_http_url(opts.chain_rpc_url.clone())
-        .build()
-        .await
-        .unwrap();
+    let priv_key = avalancheup_aws::install_subnet_chain::load_private_key(&opts.key)?;
+    let wallet_to_spend =
+        avalancheup_aws::install_subnet_chain::build_wallet(&opts.chain_rpc_url, &priv_key).await?;
+
+    let staking_asset = avalancheup_aws::install_subnet_chain::StakingAsset::new(
+        "",
+        &opts.stake_asset_symbol,
+        opts.staking_decimals,
+    )?;
+    let (unit, base_unit) = (staking_asset.symbol(), staking_asset.base_symbol());
+    let stake_amount_in_navax = staking_asset.to_base_units(opts.staking_amount_in_avax);
+    if stake_amount_in_navax > primitive_types::U256::from(u64::MAX) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "staking amount {} {unit} overflows with {} decimals",
+                opts.staking_amount_in_avax, opts.staking_decimals
+            ),
+        ));
+    }
+    let stake_amount_in_navax = stake_amount_in_navax.as_u64();
 
-    let p_chain_balance = wallet_to_spend.p().balance().await.unwrap();
+    let p_chain_balance = avalancheup_aws::install_subnet_chain::fetch_p_chain_balance(
+        &opts.chain_rpc_url,
+        &wallet_to_spend,
+        &[],
+        &avalancheup_aws::install_subnet_chain::ApiPaths::default(),
+    )
+    .await?;
     let p_chain_address = priv_key
         .to_public_key()
         .to_hrp_address(network_id, "P")
         .unwrap();
     log::info!(
-        "loaded wallet '{p_chain_address}', fetched its P-chain balance {} AVAX ({p_chain_balance} nAVAX, network id {network_id})",
-        units::cast_xp_navax_to_avax(primitive_types::U256::from(p_chain_balance))
+        "loaded wallet '{p_chain_address}', fetched its P-chain balance {} {unit} ({p_chain_balance} {base_unit}, network id {network_id}, {network_name})",
+        staking_asset.to_display_units(primitive_types::U256::from(p_chain_balance))
     );
 
     let mut all_node_ids = Vec::new();
@@ -181,16 +210,15 @@ pub async fn execute(opts: Flags) -> io::Result<()> {
 
     // if all nodes need to be staked
     println!();
-    let estimated_required_avax =
-        units::cast_avax_to_xp_navax(primitive_types::U256::from(opts.staking_amount_in_avax))
-            .checked_mul(primitive_types::U256::from(all_node_ids.len()))
-            .unwrap();
+    let estimated_required_avax = primitive_types::U256::from(stake_amount_in_navax)
+        .checked_mul(primitive_types::U256::from(all_node_ids.len()))
+        .unwrap();
     log::info!(
-        "required AVAX to validate all nodes {estimated_required_avax} nAVAX ({} AVAX)",
-        units::cast_xp_navax_to_avax(estimated_required_avax)
+        "required {unit} to validate all nodes {estimated_required_avax} {base_unit} ({} {unit})",
+        staking_asset.to_display_units(estimated_required_avax)
     );
     if primitive_types::U256::from(p_chain_balance) < estimated_required_avax {
-        log::warn!("'{p_chain_address}' only has {p_chain_balance}, not enough to validate all nodes (needs {estimated_required_avax} nAVAX)");
+        log::warn!("'{p_chain_address}' only has {p_chain_balance}, not enough to validate all nodes (needs {estimated_required_avax} {base_unit})");
         let selected = Select::with_theme(&ColorfulTheme::default())
             .with_prompt("Should we still proceed?")
             .items(&["Yes...?", "No!!!"])
@@ -206,7 +234,7 @@ pub async fn execute(opts: Flags) -> io::Result<()> {
         stdout(),
         SetForegroundColor(Color::Green),
         Print(format!(
-            "\nAdding nodes as primary network validators with network Id '{network_id}', chain rpc url '{}', primary network validate period in days '{}', staking amount in avax '{}', node ids to instance ids '{:?}'\n",
+            "\nAdding nodes as primary network validators with network Id '{network_id}' ({network_name}), chain rpc url '{}', primary network validate period in days '{}', staking amount in {unit} '{}', node ids to instance ids '{:?}'\n",
             opts.chain_rpc_url,
             opts.primary_network_validate_period_in_days,
             opts.staking_amount_in_avax,
@@ -220,14 +248,14 @@ pub async fn execute(opts: Flags) -> io::Result<()> {
         println!();
         let options = &[
             format!(
-                "No, I am not ready to add primary network validators with the wallet {p_chain_address} of balance {} AVAX, staking amount {} AVAX, primary network staking {} days",
-                    units::cast_xp_navax_to_avax(primitive_types::U256::from(p_chain_balance)),
+                "No, I am not ready to add primary network validators on {network_name} with the wallet {p_chain_address} of balance {} {unit}, staking amount {} {unit}, primary network staking {} days",
+                    staking_asset.to_display_units(primitive_types::U256::from(p_chain_balance)),
                     opts.staking_amount_in_avax,
                     opts.primary_network_validate_period_in_days,
             ),
             format!(
-                "Yes, let's add primary network validators with the wallet {p_chain_address} of balance {} AVAX, staking amount {} AVAX, primary network staking {} days",
-                    units::cast_xp_navax_to_avax(primitive_types::U256::from(p_chain_balance)),
+                "Yes, let's add primary network validators on {network_name} with the wallet {p_chain_address} of balance {} {unit}, staking amount {} {unit}, primary network staking {} days",
+                    staking_asset.to_display_units(primitive_types::U256::from(p_chain_balance)),
                     opts.staking_amount_in_avax,
                     opts.primary_network_validate_period_in_days,
                 ),
@@ -257,10 +285,6 @@ pub async fn execute(opts: Flags) -> io::Result<()> {
         )),
         ResetColor
     )?;
-    let stake_amount_in_navax =
-        units::cast_avax_to_xp_navax(primitive_types::U256::from(opts.staking_amount_in_avax))
-            .as_u64();
-
     let mut handles = Vec::new();
     for (i, (node_id, instance_id)) in node_ids_to_instance_ids.iter().enumerate() {
         // randomly wait to prevent UTXO double spends from the same wallet
@@ -0,0 +1,262 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, stdout, Error, ErrorKind},
+};
+
+use aws_manager::{self, ssm};
+use aws_sdk_ssm::types::{DocumentFormat, DocumentType};
+use clap::{Arg, Command};
+use crossterm::{
+    execute,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+use serde::{Deserialize, Serialize};
+use tokio::time::Duration;
+
+pub const NAME: &str = "create-ssm-doc";
+
+/// Default name of the SSM document, same as the CloudFormation template.
+const DEFAULT_SSM_DOC_NAME: &str = "InstallSubnetChainV0";
+
+/// Defines "create-ssm-doc" option.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Flags {
+    pub log_level: String,
+
+    pub regions: Vec<String>,
+    pub ssm_doc_name: String,
+
+    pub profile_name: String,
+}
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Creates (or updates) the SSM document for 'install-subnet-chain' from the bundled template")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("REGIONS")
+                .long("regions")
+                .help("Sets the comma-separated AWS regions to create the SSM document in")
+                .required(false)
+                .num_args(1)
+                .default_value("us-west-2"),
+        )
+        .arg(
+            Arg::new("SSM_DOC_NAME")
+                .long("ssm-doc-name")
+                .help("Sets the SSM document name")
+                .required(false)
+                .num_args(1)
+                .default_value(DEFAULT_SSM_DOC_NAME),
+        )
+        .arg(
+            Arg::new("PROFILE_NAME")
+                .long("profile-name")
+                .help("Sets the AWS credential profile name for API calls/endpoints")
+                .required(false)
+                .default_value("default")
+                .num_args(1),
+        )
+}
+
+pub async fn execute(opts: Flags) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, opts.log_level),
+    );
+
+    if opts.regions.is_empty() {
+        return Err(Error::new(ErrorKind::InvalidInput, "--regions is empty"));
+    }
+    if opts.ssm_doc_name.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "--ssm-doc-name is empty",
+        ));
+    }
+
+    let content =
+        ssm_doc_content(&avalanche_ops::aws::artifacts::ssm_install_subnet_chain_yaml()?)?;
+
+    let mut region_to_ssm_doc = BTreeMap::new();
+    for region in opts.regions.iter() {
+        execute!(
+            stdout(),
+            SetForegroundColor(Color::Green),
+            Print(format!(
+                "\n\n\nSTEP: creating the SSM document '{}' in the region '{region}'\n\n",
+                opts.ssm_doc_name
+            )),
+            ResetColor
+        )?;
+        let shared_config = aws_manager::load_config(
+            Some(region.clone()),
+            Some(opts.profile_name.clone()),
+            Some(Duration::from_secs(30)),
+        )
+        .await;
+        let ssm_manager = ssm::Manager::new(&shared_config);
+
+        let (version, action) =
+            create_or_update_document(&ssm_manager, region, &opts.ssm_doc_name, &content).await?;
+        println!(
+            "{action} SSM document '{}' in the region '{region}' (default version {version})",
+            opts.ssm_doc_name
+        );
+        region_to_ssm_doc.insert(region.clone(), opts.ssm_doc_name.clone());
+    }
+
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Blue),
+        Print("\n\nSSM documents (for 'install-subnet-chain --ssm-docs'):\n"),
+        ResetColor
+    )?;
+    println!("{}", serde_json::to_string(&region_to_ssm_doc).unwrap());
+
+    Ok(())
+}
+
+/// Returns the SSM document content (YAML) of the bundled CloudFormation template,
+/// so the document is the same as the one created by "apply".
+fn ssm_doc_content(cfn_template: &str) -> io::Result<String> {
+    let tmpl: serde_yaml::Value = serde_yaml::from_str(cfn_template).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("failed to parse the SSM document template ({})", e),
+        )
+    })?;
+    let content = &tmpl["Resources"]["InstallSubnetChain"]["Properties"]["Content"];
+    if content.is_null() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "SSM document template has no 'Resources.InstallSubnetChain.Properties.Content'",
+        ));
+    }
+    serde_yaml::to_string(content).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("failed to encode the SSM document content ({})", e),
+        )
+    })
+}
+
+/// Creates the SSM document, or updates the existing one and makes the new version
+/// the default (unchanged if the content is the same).
+/// Returns the default document version, and the action taken.
+/// ref. <https://docs.aws.amazon.com/systems-manager/latest/APIReference/API_CreateDocument.html>
+/// ref. <https://docs.aws.amazon.com/systems-manager/latest/APIReference/API_UpdateDocument.html>
+async fn create_or_update_document(
+    ssm_manager: &ssm::Manager,
+    region: &str,
+    name: &str,
+    content: &str,
+) -> io::Result<(String, &'static str)> {
+    let created = ssm_manager
+        .cli
+        .create_document()
+        .name(name)
+        .content(content)
+        .document_type(DocumentType::Command)
+        .document_format(DocumentFormat::Yaml)
+        .send()
+        .await;
+    let e = match created {
+        Ok(out) => {
+            let version = out
+                .document_description()
+                .and_then(|d| d.document_version())
+                .unwrap_or("1")
+                .to_string();
+            return Ok((version, "created"));
+        }
+        Err(e) => e.into_service_error(),
+    };
+    if !e.is_document_already_exists() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!(
+                "failed create_document '{name}' in the region '{region}' ({:?})",
+                e
+            ),
+        ));
+    }
+    log::info!("SSM document '{name}' already exists in the region '{region}', updating");
+
+    let updated = ssm_manager
+        .cli
+        .update_document()
+        .name(name)
+        .content(content)
+        .document_version("$LATEST")
+        .document_format(DocumentFormat::Yaml)
+        .send()
+        .await;
+    let version = match updated.map_err(|e| e.into_service_error()) {
+        Ok(out) => out
+            .document_description()
+            .and_then(|d| d.document_version())
+            .unwrap_or_default()
+            .to_string(),
+        Err(e) if e.is_duplicate_document_content() => {
+            log::info!("SSM document '{name}' in the region '{region}' is up to date");
+            let out = ssm_manager
+                .cli
+                .describe_document()
+                .name(name)
+                .send()
+                .await
+                .map_err(|e| {
+                    Error::new(
+                        ErrorKind::Other,
+                        format!(
+                            "failed describe_document '{name}' in the region '{region}' ({:?})",
+                            e
+                        ),
+                    )
+                })?;
+            let version = out
+                .document()
+                .and_then(|d| d.default_version())
+                .unwrap_or_default()
+                .to_string();
+            return Ok((version, "unchanged"));
+        }
+        Err(e) => {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!(
+                    "failed update_document '{name}' in the region '{region}' ({:?})",
+                    e
+                ),
+            ))
+        }
+    };
+
+    ssm_manager
+        .cli
+        .update_document_default_version()
+        .name(name)
+        .document_version(version.clone())
+        .send()
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!(
+                    "failed update_document_default_version '{name}' to '{version}' in the region '{region}' ({:?})",
+                    e
+                ),
+            )
+        })?;
+    Ok((version, "updated"))
+}
@@ -0,0 +1,485 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, stdout, Error, ErrorKind},
+    str::FromStr,
+};
+
+use avalanche_types::ids::{self, node};
+use chrono::Utc;
+use clap::{Arg, Command};
+use crossterm::{
+    execute,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::subnet_validators::{self, SubnetValidator};
+
+pub const NAME: &str = "deploy-report";
+
+/// Exit code if the deployment does not match the intended nodes, distinct
+/// from the failures to build the report (exit code 1).
+pub const EXIT_CODE_OUT_OF_SYNC: i32 = 2;
+
+/// Defines "deploy-report" option.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Flags {
+    pub log_level: String,
+
+    pub chain_rpc_url: String,
+    pub subnet_id: String,
+
+    pub target_nodes: HashMap<String, avalanche_ops::aws::spec::RegionMachineId>,
+    pub non_validating_node_ids: Vec<String>,
+
+    pub output: String,
+}
+
+/// Diff of the intended nodes against the current validator sets.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct DeployReport {
+    pub subnet_id: String,
+    /// Intended nodes, sorted by the node Id.
+    pub nodes: Vec<NodeReport>,
+    /// Current subnet validators that are not in the intended nodes.
+    pub unexpected_validators: Vec<SubnetValidator>,
+    /// True if every intended node is as intended, with no unexpected validator.
+    pub in_sync: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct NodeReport {
+    pub node_id: String,
+    pub region: String,
+    pub machine_id: String,
+    /// False if the node is intended to only track the subnet (non-validating).
+    pub validator: bool,
+    pub primary_validator: bool,
+    /// None if the node is not a current subnet validator.
+    pub subnet_end_time: Option<u64>,
+    pub status: NodeReportStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeReportStatus {
+    /// The node validates the subnet (or does not, if non-validating) as intended.
+    Ok,
+    /// The node is not a current primary network validator, thus cannot validate the subnet.
+    NotPrimaryValidator,
+    /// The node is a primary network validator, but does not validate the subnet.
+    NotSubnetValidator,
+    /// The node is intended to only track the subnet, but validates it.
+    UnexpectedSubnetValidator,
+}
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Reports the diff of the intended nodes against the current primary network and subnet validators")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("CHAIN_RPC_URL")
+                .long("chain-rpc-url")
+                .help("Sets the P-chain or Avalanche RPC endpoint")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("SUBNET_ID")
+                .long("subnet-id")
+                .help("Sets the subnet Id to check the validators of")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("TARGET_NODES")
+                .long("target-nodes")
+                .help("Sets the hash map of the intended node Id to the corresponding EC2 region, and instance Id in JSON format (same as 'install-subnet-chain')")
+                .required(true)
+                .value_parser(
+                    avalancheup_aws::install_subnet_chain::HashMapStringToRegionInstanceIdParser {},
+                )
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("NON_VALIDATING_NODE_IDS")
+                .long("non-validating-node-ids")
+                .help("Sets the comma-separated node Ids of --target-nodes that are intended to only track the subnet (not validate it)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("OUTPUT")
+                .long("output")
+                .short('o')
+                .help("Sets the output format")
+                .required(false)
+                .num_args(1)
+                .value_parser(["text", "json"])
+                .default_value("text"),
+        )
+}
+
+/// Prints the report, and returns it to exit with "EXIT_CODE_OUT_OF_SYNC"
+/// if not in sync.
+pub async fn execute(opts: Flags) -> io::Result<DeployReport> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default()
+            .filter_or(env_logger::DEFAULT_FILTER_ENV, opts.clone().log_level),
+    );
+
+    let subnet_id = ids::Id::from_str(&opts.subnet_id).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid --subnet-id '{}' ({})", opts.subnet_id, e),
+        )
+    })?;
+    let intended = parse_intended_nodes(&opts.target_nodes, &opts.non_validating_node_ids)?;
+
+    // ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetcurrentvalidators>
+    let now = Utc::now().timestamp() as u64;
+    let subnet_result = avalancheup_aws::install_subnet_chain::call_json_rpc(
+        &opts.chain_rpc_url,
+        "/ext/bc/P",
+        "platform.getCurrentValidators",
+        serde_json::json!({ "subnetID": subnet_id.to_string() }),
+        &[],
+    )
+    .await?;
+    let subnet_validators = subnet_validators::parse_validators(&subnet_result, now)?;
+
+    let primary_result = avalancheup_aws::install_subnet_chain::call_json_rpc(
+        &opts.chain_rpc_url,
+        "/ext/bc/P",
+        "platform.getCurrentValidators",
+        serde_json::json!({}),
+        &[],
+    )
+    .await?;
+    let primary_validators = subnet_validators::parse_validators(&primary_result, now)?;
+
+    let report = build_report(
+        &subnet_id.to_string(),
+        &intended,
+        &primary_validators,
+        &subnet_validators,
+    );
+
+    if opts.output == "json" {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print_report(&report)?;
+    }
+
+    Ok(report)
+}
+
+impl DeployReport {
+    /// Returns the number of intended nodes that are not as intended.
+    pub fn mismatched_nodes(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|n| n.status != NodeReportStatus::Ok)
+            .count()
+    }
+}
+
+/// Intended node: region, machine Id, and whether it validates the subnet.
+struct IntendedNode {
+    region: String,
+    machine_id: String,
+    validator: bool,
+}
+
+/// Returns the intended nodes keyed by the normalized node Id.
+fn parse_intended_nodes(
+    target_nodes: &HashMap<String, avalanche_ops::aws::spec::RegionMachineId>,
+    non_validating_node_ids: &[String],
+) -> io::Result<BTreeMap<String, IntendedNode>> {
+    if target_nodes.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "--target-nodes is empty",
+        ));
+    }
+    let normalize = |node_id: &str| -> io::Result<String> {
+        node::Id::from_str(node_id)
+            .map(|id| id.to_string())
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("invalid node Id '{node_id}' ({})", e),
+                )
+            })
+    };
+
+    let mut intended = BTreeMap::new();
+    for (node_id, region_machine_id) in target_nodes.iter() {
+        intended.insert(
+            normalize(node_id)?,
+            IntendedNode {
+                region: region_machine_id.region.clone(),
+                machine_id: region_machine_id.machine_id.clone(),
+                validator: true,
+            },
+        );
+    }
+    for node_id in non_validating_node_ids.iter() {
+        match intended.get_mut(&normalize(node_id)?) {
+            Some(node) => node.validator = false,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("non-validating node id '{node_id}' not found in --target-nodes"),
+                ))
+            }
+        }
+    }
+    Ok(intended)
+}
+
+fn build_report(
+    subnet_id: &str,
+    intended: &BTreeMap<String, IntendedNode>,
+    primary_validators: &[SubnetValidator],
+    subnet_validators: &[SubnetValidator],
+) -> DeployReport {
+    let nodes: Vec<NodeReport> = intended
+        .iter()
+        .map(|(node_id, node)| {
+            let primary_validator = primary_validators.iter().any(|v| v.node_id == *node_id);
+            let subnet_end_time = subnet_validators
+                .iter()
+                .find(|v| v.node_id == *node_id)
+                .map(|v| v.end_time);
+            let status = match (node.validator, primary_validator, subnet_end_time.is_some()) {
+                (true, false, _) => NodeReportStatus::NotPrimaryValidator,
+                (true, true, false) => NodeReportStatus::NotSubnetValidator,
+                (false, _, true) => NodeReportStatus::UnexpectedSubnetValidator,
+                _ => NodeReportStatus::Ok,
+            };
+            NodeReport {
+                node_id: node_id.clone(),
+                region: node.region.clone(),
+                machine_id: node.machine_id.clone(),
+                validator: node.validator,
+                primary_validator,
+                subnet_end_time,
+                status,
+            }
+        })
+        .collect();
+
+    let unexpected_validators: Vec<SubnetValidator> = subnet_validators
+        .iter()
+        .filter(|v| !intended.contains_key(&v.node_id))
+        .cloned()
+        .collect();
+
+    let in_sync =
+        unexpected_validators.is_empty() && nodes.iter().all(|n| n.status == NodeReportStatus::Ok);
+    DeployReport {
+        subnet_id: subnet_id.to_string(),
+        nodes,
+        unexpected_validators,
+        in_sync,
+    }
+}
+
+fn print_report(report: &DeployReport) -> io::Result<()> {
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Green),
+        Print(format!(
+            "\nDEPLOY REPORT ({}, {} intended node(s))\n",
+            report.subnet_id,
+            report.nodes.len()
+        )),
+        ResetColor
+    )?;
+    println!("NODE ID\tREGION\tMACHINE ID\tINTENT\tPRIMARY\tSUBNET\tSTATUS");
+    for n in report.nodes.iter() {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{:?}",
+            n.node_id,
+            if n.region.is_empty() { "-" } else { &n.region },
+            n.machine_id,
+            if n.validator {
+                "validator"
+            } else {
+                "non-validating"
+            },
+            if n.primary_validator { "yes" } else { "no" },
+            if n.subnet_end_time.is_some() {
+                "yes"
+            } else {
+                "no"
+            },
+            n.status
+        );
+    }
+
+    if !report.unexpected_validators.is_empty() {
+        execute!(
+            stdout(),
+            SetForegroundColor(Color::Yellow),
+            Print(format!(
+                "\nUNEXPECTED SUBNET VALIDATORS ({})\n",
+                report.unexpected_validators.len()
+            )),
+            ResetColor
+        )?;
+        for v in report.unexpected_validators.iter() {
+            println!("{}\tweight {}", v.node_id, v.weight);
+        }
+    }
+
+    let (color, summary) = if report.in_sync {
+        (
+            Color::Green,
+            "\nIN SYNC: the validators match the intended nodes\n",
+        )
+    } else {
+        (
+            Color::Red,
+            "\nOUT OF SYNC: the validators do not match the intended nodes\n",
+        )
+    };
+    execute!(
+        stdout(),
+        SetForegroundColor(color),
+        Print(summary),
+        ResetColor
+    )?;
+    Ok(())
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --bin avalancheup-aws -- deploy_report::test_parse_intended_nodes --exact --show-output
+#[test]
+fn test_parse_intended_nodes() {
+    let region_machine_id = |machine_id: &str| avalanche_ops::aws::spec::RegionMachineId {
+        region: String::from("us-west-2"),
+        machine_id: machine_id.to_string(),
+    };
+    let target_nodes = HashMap::from([
+        (
+            String::from("NodeID-7Xhw2mDxuDS44j42TCB6U5579esbSt3Lg"),
+            region_machine_id("i-a"),
+        ),
+        (
+            String::from("NodeID-MFrZFVCXPv5iCn6M9K6XduxGTYp891xXZ"),
+            region_machine_id("i-b"),
+        ),
+    ]);
+
+    let intended = parse_intended_nodes(
+        &target_nodes,
+        &[String::from("NodeID-MFrZFVCXPv5iCn6M9K6XduxGTYp891xXZ")],
+    )
+    .unwrap();
+    assert_eq!(intended.len(), 2);
+    let node = &intended["NodeID-7Xhw2mDxuDS44j42TCB6U5579esbSt3Lg"];
+    assert_eq!(node.region, "us-west-2");
+    assert_eq!(node.machine_id, "i-a");
+    assert!(node.validator);
+    assert!(!intended["NodeID-MFrZFVCXPv5iCn6M9K6XduxGTYp891xXZ"].validator);
+
+    assert!(parse_intended_nodes(&HashMap::new(), &[]).is_err());
+    // not in --target-nodes
+    assert!(parse_intended_nodes(
+        &target_nodes,
+        &[String::from("NodeID-NFBbbJ4qCmNaCzeW7sxErhvWqvEQMnYcN")]
+    )
+    .is_err());
+    assert!(parse_intended_nodes(&target_nodes, &[String::from("NodeID-invalid")]).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --bin avalancheup-aws -- deploy_report::test_build_report --exact --show-output
+#[test]
+fn test_build_report() {
+    let intended_node = |validator: bool| IntendedNode {
+        region: String::from("us-west-2"),
+        machine_id: String::from("i-a"),
+        validator,
+    };
+    let validator = |node_id: &str| SubnetValidator {
+        node_id: node_id.to_string(),
+        weight: 100,
+        start_time: 1,
+        end_time: 1000,
+        remaining_secs: 999,
+    };
+    let intended = BTreeMap::from([
+        (String::from("NodeID-A"), intended_node(true)),
+        (String::from("NodeID-B"), intended_node(true)),
+        (String::from("NodeID-C"), intended_node(true)),
+        (String::from("NodeID-D"), intended_node(false)),
+        (String::from("NodeID-E"), intended_node(false)),
+    ]);
+    let primary_validators = vec![
+        validator("NodeID-A"),
+        validator("NodeID-C"),
+        validator("NodeID-D"),
+        validator("NodeID-E"),
+    ];
+
+    let report = build_report(
+        "subnet",
+        &intended,
+        &primary_validators,
+        &[validator("NodeID-A"), validator("NodeID-E")],
+    );
+    let statuses: Vec<(&str, NodeReportStatus)> = report
+        .nodes
+        .iter()
+        .map(|n| (n.node_id.as_str(), n.status))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ("NodeID-A", NodeReportStatus::Ok),
+            ("NodeID-B", NodeReportStatus::NotPrimaryValidator),
+            ("NodeID-C", NodeReportStatus::NotSubnetValidator),
+            ("NodeID-D", NodeReportStatus::Ok),
+            ("NodeID-E", NodeReportStatus::UnexpectedSubnetValidator),
+        ]
+    );
+    assert_eq!(report.nodes[0].subnet_end_time, Some(1000));
+    assert_eq!(report.nodes[2].subnet_end_time, None);
+    assert!(report.unexpected_validators.is_empty());
+    assert_eq!(report.mismatched_nodes(), 3);
+    assert!(!report.in_sync);
+
+    // in sync, except for a subnet validator not in the intended nodes
+    let intended = BTreeMap::from([
+        (String::from("NodeID-A"), intended_node(true)),
+        (String::from("NodeID-D"), intended_node(false)),
+    ]);
+    let report = build_report(
+        "subnet",
+        &intended,
+        &primary_validators,
+        &[validator("NodeID-A"), validator("NodeID-F")],
+    );
+    assert_eq!(report.mismatched_nodes(), 0);
+    assert_eq!(report.unexpected_validators, vec![validator("NodeID-F")]);
+    assert!(!report.in_sync);
+
+    let report = build_report(
+        "subnet",
+        &intended,
+        &primary_validators,
+        &[validator("NodeID-A")],
+    );
+    assert!(report.in_sync);
+}
@@ -0,0 +1,221 @@
+use std::io::{self, stdout, Error, ErrorKind};
+
+use avalanche_types::{constants, jsonrpc::client::info as json_client_info};
+use clap::{value_parser, Arg, Command};
+use crossterm::{
+    execute,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+
+pub const NAME: &str = "estimate-cost";
+
+/// Defines "estimate-cost" option.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Flags {
+    pub log_level: String,
+
+    pub network_id: u32,
+    pub chain_rpc_url: String,
+
+    pub node_count: u64,
+    pub staking_amount_in_avax: u64,
+    pub stake_asset_symbol: String,
+    pub staking_decimals: u32,
+    pub primary_network_validate_period_in_days: u64,
+    pub subnet_validate_period_in_days: u64,
+}
+
+/// Transaction fees in nAVAX (base units of the staking asset) used for the estimate.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Fees {
+    pub create_subnet_tx_fee: u64,
+    pub create_blockchain_tx_fee: u64,
+    pub add_primary_network_validator_fee: u64,
+    pub add_subnet_validator_fee: u64,
+}
+
+impl Fees {
+    /// Returns the default fees of the public networks (mainnet and fuji),
+    /// if the network is not public, returns None.
+    /// ref. <https://docs.avax.network/quickstart/transaction-fees>
+    pub fn default_for(network_id: u32) -> Option<Self> {
+        match constants::NETWORK_ID_TO_NETWORK_NAME.get(&network_id) {
+            Some(&"mainnet") | Some(&"fuji") => {}
+            _ => return None,
+        }
+        Some(Self {
+            create_subnet_tx_fee: 1_000_000_000,
+            create_blockchain_tx_fee: 1_000_000_000,
+            add_primary_network_validator_fee: 0,
+            add_subnet_validator_fee: 1_000_000,
+        })
+    }
+}
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Estimates the total AVAX required for 'install-subnet-chain' (no key required)")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("NETWORK_ID")
+                .long("network-id")
+                .help("Sets the network Id (fees of mainnet and fuji are built-in, other networks require --chain-rpc-url)")
+                .required(true)
+                .num_args(1)
+                .value_parser(value_parser!(u32)),
+        )
+        .arg(
+            Arg::new("CHAIN_RPC_URL")
+                .long("chain-rpc-url")
+                .help("Sets the Avalanche RPC endpoint to fetch the current fees from (if empty, uses the built-in fees)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("NODE_COUNT")
+                .long("node-count")
+                .help("Sets the number of nodes to validate the primary network and the subnet")
+                .required(true)
+                .num_args(1)
+                .value_parser(value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("STAKING_AMOUNT_IN_AVAX")
+                .long("staking-amount-in-avax")
+                .help(
+                    "Sets the staking amount in P-chain AVAX (not in nAVAX) for primary network validator",
+                )
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("2000"),
+        )
+        .args(avalancheup_aws::install_subnet_chain::staking_asset_args())
+        .arg(
+            Arg::new("PRIMARY_NETWORK_VALIDATE_PERIOD_IN_DAYS")
+                .long("primary-network-validate-period-in-days")
+                .help("Sets the number of days to validate primary network (stakes are locked for the period)")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("16"),
+        )
+        .arg(
+            Arg::new("SUBNET_VALIDATE_PERIOD_IN_DAYS")
+                .long("subnet-validate-period-in-days")
+                .help("Sets the number of days to validate/stake the subnet")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("14"),
+        )
+}
+
+pub async fn execute(opts: Flags) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, opts.log_level),
+    );
+
+    if opts.subnet_validate_period_in_days > opts.primary_network_validate_period_in_days {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "subnet validate period {} days exceeds primary network validate period {} days",
+                opts.subnet_validate_period_in_days, opts.primary_network_validate_period_in_days
+            ),
+        ));
+    }
+
+    let fees = if opts.chain_rpc_url.is_empty() {
+        Fees::default_for(opts.network_id).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "no built-in fees for network id {}, set --chain-rpc-url to fetch the current fees",
+                    opts.network_id
+                ),
+            )
+        })?
+    } else {
+        let resp = json_client_info::get_tx_fee(&opts.chain_rpc_url)
+            .await
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!("failed get_tx_fee '{}' ({})", opts.chain_rpc_url, e),
+                )
+            })?;
+        let fee = resp.result.ok_or_else(|| {
+            Error::new(
+                ErrorKind::Other,
+                format!("no tx fee result from '{}'", opts.chain_rpc_url),
+            )
+        })?;
+        Fees {
+            create_subnet_tx_fee: fee.create_subnet_tx_fee,
+            create_blockchain_tx_fee: fee.create_blockchain_tx_fee,
+            add_primary_network_validator_fee: fee.add_primary_network_validator_fee,
+            add_subnet_validator_fee: fee.add_subnet_validator_fee,
+        }
+    };
+    log::info!("using fees {:?}", fees);
+
+    let staking_asset = avalancheup_aws::install_subnet_chain::StakingAsset::new(
+        "",
+        &opts.stake_asset_symbol,
+        opts.staking_decimals,
+    )?;
+    let (unit, base_unit) = (staking_asset.symbol(), staking_asset.base_symbol());
+
+    let node_count = U256::from(opts.node_count);
+    let stakes = staking_asset
+        .to_base_units(opts.staking_amount_in_avax)
+        .checked_mul(node_count)
+        .unwrap();
+    let add_primary_network_validator_fees = U256::from(fees.add_primary_network_validator_fee)
+        .checked_mul(node_count)
+        .unwrap();
+    let add_subnet_validator_fees = U256::from(fees.add_subnet_validator_fee)
+        .checked_mul(node_count)
+        .unwrap();
+    let create_subnet_tx_fee = U256::from(fees.create_subnet_tx_fee);
+    let create_blockchain_tx_fee = U256::from(fees.create_blockchain_tx_fee);
+
+    let burned = add_primary_network_validator_fees
+        .checked_add(add_subnet_validator_fees)
+        .and_then(|v| v.checked_add(create_subnet_tx_fee))
+        .and_then(|v| v.checked_add(create_blockchain_tx_fee))
+        .unwrap();
+    let total = stakes.checked_add(burned).unwrap();
+
+    println!();
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Blue),
+        Print(format!(
+            "\n\n\nESTIMATED COST (network id {}, {} node(s))\nstakes (locked for {} days, returned after): {stakes} {base_unit} ({} {unit})\nadd primary network validator fees: {add_primary_network_validator_fees} {base_unit}\nadd subnet validator fees ({} days): {add_subnet_validator_fees} {base_unit}\ncreate subnet fee: {create_subnet_tx_fee} {base_unit}\ncreate blockchain fee: {create_blockchain_tx_fee} {base_unit}\ntotal fees (burned): {burned} {base_unit} ({} {unit})\ntotal required: {total} {base_unit} ({} {unit})\n\n",
+            opts.network_id,
+            opts.node_count,
+            opts.primary_network_validate_period_in_days,
+            staking_asset.to_display_units(stakes),
+            opts.subnet_validate_period_in_days,
+            staking_asset.to_display_units(burned),
+            staking_asset.to_display_units(total),
+        )),
+        ResetColor
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,415 @@
+use std::{
+    fs,
+    io::{self, Error, ErrorKind},
+};
+
+use serde_json::Value;
+
+/// Expected JSON value type of a known config key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Kind {
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+    /// Accepts any value (e.g., durations that can be either numbers or strings).
+    Any,
+}
+
+impl Kind {
+    fn matches(&self, v: &Value) -> bool {
+        match self {
+            Kind::Bool => v.is_boolean(),
+            Kind::Number => v.is_number(),
+            Kind::String => v.is_string(),
+            Kind::Array => v.is_array(),
+            Kind::Object => v.is_object(),
+            Kind::Any => true,
+        }
+    }
+}
+
+/// Known keys of the avalanchego subnet config.
+/// ref. <https://github.com/ava-labs/avalanchego/blob/master/subnets/config.go>
+pub const SUBNET_CONFIG_SCHEMA: &[(&str, Kind)] = &[
+    ("validatorOnly", Kind::Bool),
+    ("allowedNodes", Kind::Array),
+    ("consensusParameters", Kind::Object),
+    ("proposerMinBlockDelay", Kind::Number),
+    ("proposerNumHistoricalBlocks", Kind::Number),
+    ("gossipAcceptedFrontierValidatorSize", Kind::Number),
+    ("gossipAcceptedFrontierNonValidatorSize", Kind::Number),
+    ("gossipAcceptedFrontierPeerSize", Kind::Number),
+    ("gossipOnAcceptValidatorSize", Kind::Number),
+    ("gossipOnAcceptNonValidatorSize", Kind::Number),
+    ("gossipOnAcceptPeerSize", Kind::Number),
+    ("appGossipValidatorSize", Kind::Number),
+    ("appGossipNonValidatorSize", Kind::Number),
+    ("appGossipPeerSize", Kind::Number),
+];
+
+/// Known keys of the subnet-evm chain config.
+/// ref. <https://github.com/ava-labs/subnet-evm/blob/master/plugin/evm/config.go>
+pub const CHAIN_CONFIG_SCHEMA: &[(&str, Kind)] = &[
+    ("snowman-api-enabled", Kind::Bool),
+    ("admin-api-enabled", Kind::Bool),
+    ("admin-api-dir", Kind::String),
+    ("warp-api-enabled", Kind::Bool),
+    ("validators-api-enabled", Kind::Bool),
+    ("eth-apis", Kind::Array),
+    ("continuous-profiler-dir", Kind::String),
+    ("continuous-profiler-frequency", Kind::Any),
+    ("continuous-profiler-max-files", Kind::Number),
+    ("rpc-gas-cap", Kind::Number),
+    ("rpc-tx-fee-cap", Kind::Number),
+    ("api-max-duration", Kind::Any),
+    ("ws-cpu-refill-rate", Kind::Any),
+    ("ws-cpu-max-stored", Kind::Any),
+    ("api-max-blocks-per-request", Kind::Number),
+    ("allow-unfinalized-queries", Kind::Bool),
+    ("allow-unprotected-txs", Kind::Bool),
+    ("allow-unprotected-tx-hashes", Kind::Array),
+    ("keystore-directory", Kind::String),
+    ("keystore-external-signer", Kind::String),
+    ("keystore-insecure-unlock-allowed", Kind::Bool),
+    ("pruning-enabled", Kind::Bool),
+    ("commit-interval", Kind::Number),
+    ("accepted-cache-size", Kind::Number),
+    ("acceptor-queue-limit", Kind::Number),
+    ("trie-clean-cache", Kind::Number),
+    ("trie-clean-journal", Kind::String),
+    ("trie-clean-rejournal", Kind::Any),
+    ("trie-dirty-cache", Kind::Number),
+    ("trie-dirty-commit-target", Kind::Number),
+    ("snapshot-cache", Kind::Number),
+    ("snapshot-async", Kind::Bool),
+    ("snapshot-verification-enabled", Kind::Bool),
+    ("preimages-enabled", Kind::Bool),
+    ("populate-missing-tries", Kind::Number),
+    ("populate-missing-tries-parallelism", Kind::Number),
+    ("allow-missing-tries", Kind::Bool),
+    ("offline-pruning-enabled", Kind::Bool),
+    ("offline-pruning-bloom-filter-size", Kind::Number),
+    ("offline-pruning-data-directory", Kind::String),
+    ("metrics-expensive-enabled", Kind::Bool),
+    ("local-txs-enabled", Kind::Bool),
+    ("tx-pool-journal", Kind::String),
+    ("tx-pool-rejournal", Kind::Any),
+    ("tx-pool-price-limit", Kind::Number),
+    ("tx-pool-price-bump", Kind::Number),
+    ("tx-pool-account-slots", Kind::Number),
+    ("tx-pool-global-slots", Kind::Number),
+    ("tx-pool-account-queue", Kind::Number),
+    ("tx-pool-global-queue", Kind::Number),
+    ("tx-pool-lifetime", Kind::Any),
+    ("remote-gossip-only-enabled", Kind::Bool),
+    ("regossip-frequency", Kind::Any),
+    ("regossip-max-txs", Kind::Number),
+    ("regossip-txs-per-address", Kind::Number),
+    ("priority-regossip-frequency", Kind::Any),
+    ("priority-regossip-max-txs", Kind::Number),
+    ("priority-regossip-txs-per-address", Kind::Number),
+    ("priority-regossip-addresses", Kind::Array),
+    ("log-level", Kind::String),
+    ("log-json-format", Kind::Bool),
+    ("max-outbound-active-requests", Kind::Number),
+    ("max-outbound-active-cross-chain-requests", Kind::Number),
+    ("state-sync-enabled", Kind::Bool),
+    ("state-sync-skip-resume", Kind::Bool),
+    ("state-sync-server-trie-cache", Kind::Number),
+    ("state-sync-ids", Kind::String),
+    ("state-sync-commit-interval", Kind::Number),
+    ("state-sync-min-blocks", Kind::Number),
+    ("state-sync-request-size", Kind::Number),
+    ("tx-lookup-limit", Kind::Number),
+    ("skip-tx-indexing", Kind::Bool),
+    ("skip-upgrade-check", Kind::Bool),
+    ("inspect-database", Kind::Bool),
+    ("feeRecipient", Kind::String),
+    ("airdrop", Kind::String),
+];
+
+/// Known "eth-apis" of subnet-evm (the node fails to start on other names).
+/// ref. <https://github.com/ava-labs/subnet-evm/blob/master/plugin/evm/service.go>
+pub const SUBNET_EVM_ETH_APIS: &[&str] = &[
+    "eth",
+    "eth-filter",
+    "net",
+    "web3",
+    "internal-eth",
+    "internal-blockchain",
+    "internal-transaction",
+    "internal-tx-pool",
+    "internal-account",
+    "internal-personal",
+    "debug-tracer",
+    "debug-file-tracer",
+    "debug-handler",
+    "internal-debug",
+];
+
+/// Unknown keys within this edit distance of a known key are reported as typos.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Validates the JSON config file against the schema.
+/// Returns an error for mistyped values of known keys and for unknown keys
+/// that closely match a known key (likely misspelled, thus silently ignored).
+/// Other unknown keys are only logged, since VMs may define their own keys.
+pub fn validate_file(file_path: &str, kind: &str, schema: &[(&str, Kind)]) -> io::Result<()> {
+    let d = fs::read_to_string(file_path).map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to read {kind} '{file_path}' ({})", e),
+        )
+    })?;
+    let problems = validate(&d, schema).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid {kind} '{file_path}' ({})", e),
+        )
+    })?;
+    if problems.is_empty() {
+        log::info!("validated {kind} '{file_path}'");
+        return Ok(());
+    }
+
+    Err(Error::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "invalid {kind} '{file_path}' (use --skip-config-validation to ignore):\n{}",
+            problems.join("\n")
+        ),
+    ))
+}
+
+/// Validates the JSON config against the schema, returning the list of problems.
+pub fn validate(d: &str, schema: &[(&str, Kind)]) -> io::Result<Vec<String>> {
+    let v: Value = serde_json::from_str(d).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("failed to parse JSON ({})", e),
+        )
+    })?;
+    let obj = v
+        .as_object()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "expected a JSON object"))?;
+
+    let mut problems = Vec::new();
+    for (k, v) in obj.iter() {
+        if let Some((_, expected)) = schema.iter().find(|(known, _)| *known == k.as_str()) {
+            if !expected.matches(v) {
+                problems.push(format!(
+                    "- key '{k}' expects {:?} value, found '{v}'",
+                    expected
+                ));
+            }
+            continue;
+        }
+
+        match suggest(k, schema) {
+            Some(known) => problems.push(format!("- unknown key '{k}' (did you mean '{known}'?)")),
+            None => log::warn!("unknown key '{k}' (not in the known schema, skipping)"),
+        }
+    }
+    Ok(problems)
+}
+
+/// Checks the subnet-evm chain config for values the node rejects on start
+/// (returned as errors) and for suspicious combinations (returned as warnings).
+pub fn check_subnet_evm_chain_config(d: &str) -> io::Result<(Vec<String>, Vec<String>)> {
+    let v: Value = serde_json::from_str(d).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("failed to parse JSON ({})", e),
+        )
+    })?;
+    let enabled = |k: &str| v[k].as_bool();
+
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    if let Some(apis) = v["eth-apis"].as_array() {
+        for api in apis.iter() {
+            match api.as_str() {
+                Some(name) if SUBNET_EVM_ETH_APIS.contains(&name) => {}
+                Some(name) => errors.push(format!("- unknown 'eth-apis' entry '{name}'")),
+                None => errors.push(format!("- 'eth-apis' entry '{api}' is not a string")),
+            }
+        }
+    }
+    // subnet-evm defaults "pruning-enabled" to true
+    let archival = enabled("pruning-enabled") == Some(false);
+    if enabled("offline-pruning-enabled") == Some(true) {
+        if archival {
+            errors.push(String::from(
+                "- 'offline-pruning-enabled' requires 'pruning-enabled' (archival node)",
+            ));
+        }
+        if v["offline-pruning-data-directory"]
+            .as_str()
+            .unwrap_or_default()
+            .is_empty()
+        {
+            errors.push(String::from(
+                "- 'offline-pruning-enabled' requires 'offline-pruning-data-directory'",
+            ));
+        }
+    }
+    if v["commit-interval"].as_u64() == Some(0) {
+        errors.push(String::from("- 'commit-interval' must be non-zero"));
+    }
+
+    let state_sync = enabled("state-sync-enabled") == Some(true);
+    if state_sync && archival {
+        warnings.push(String::from(
+            "'state-sync-enabled' with 'pruning-enabled' false: the node state syncs from a recent summary, thus never has the historical state of an archival node",
+        ));
+    }
+    if !state_sync && !v["state-sync-ids"].as_str().unwrap_or_default().is_empty() {
+        warnings.push(String::from(
+            "'state-sync-ids' is set but 'state-sync-enabled' is not true, thus ignored",
+        ));
+    }
+    if enabled("warp-api-enabled") == Some(true) && v["eth-apis"].is_array() {
+        let apis = v["eth-apis"].as_array().unwrap();
+        if !apis.iter().any(|api| api.as_str() == Some("eth")) {
+            warnings.push(String::from(
+                "'warp-api-enabled' without 'eth' in 'eth-apis': warp messages cannot be queried via the eth API",
+            ));
+        }
+    }
+    if enabled("allow-missing-tries") == Some(true) && !archival {
+        warnings.push(String::from(
+            "'allow-missing-tries' only has an effect with 'pruning-enabled' false",
+        ));
+    }
+    Ok((errors, warnings))
+}
+
+/// Runs "check_subnet_evm_chain_config" on the file, logging the warnings.
+pub fn check_subnet_evm_chain_config_file(file_path: &str) -> io::Result<()> {
+    let d = fs::read_to_string(file_path).map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to read chain config '{file_path}' ({})", e),
+        )
+    })?;
+    let (errors, warnings) = check_subnet_evm_chain_config(&d)?;
+    for w in warnings.iter() {
+        log::warn!("chain config '{file_path}': {w}");
+    }
+    if errors.is_empty() {
+        log::info!("checked subnet-evm chain config '{file_path}'");
+        return Ok(());
+    }
+    Err(Error::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "inconsistent subnet-evm chain config '{file_path}' (use --skip-chain-config-validation to ignore):\n{}",
+            errors.join("\n")
+        ),
+    ))
+}
+
+/// Returns the closest known key, if any is within the suggestion distance.
+fn suggest<'a>(key: &str, schema: &[(&'a str, Kind)]) -> Option<&'a str> {
+    let lower = key.to_lowercase();
+    schema
+        .iter()
+        .map(|(known, _)| (*known, edit_distance(&lower, &known.to_lowercase())))
+        .filter(|(_, d)| *d <= MAX_SUGGESTION_DISTANCE)
+        .min_by_key(|(_, d)| *d)
+        .map(|(known, _)| known)
+}
+
+/// Levenshtein distance between two strings.
+pub(super) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::config_schema::test_validate --exact --show-output
+#[test]
+fn test_validate() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    assert!(validate(
+        r#"{"validatorOnly":true,"proposerMinBlockDelay":1000000000}"#,
+        SUBNET_CONFIG_SCHEMA
+    )
+    .unwrap()
+    .is_empty());
+
+    // mistyped value
+    let problems = validate(r#"{"validatorOnly":"true"}"#, SUBNET_CONFIG_SCHEMA).unwrap();
+    assert_eq!(problems.len(), 1);
+
+    // misspelled key
+    let problems = validate(r#"{"validatorsOnly":true}"#, SUBNET_CONFIG_SCHEMA).unwrap();
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].contains("validatorOnly"));
+
+    let problems = validate(r#"{"state-sync-enable":true}"#, CHAIN_CONFIG_SCHEMA).unwrap();
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].contains("state-sync-enabled"));
+
+    // unrelated unknown key is only logged
+    assert!(validate(r#"{"my-vm-key":1}"#, CHAIN_CONFIG_SCHEMA)
+        .unwrap()
+        .is_empty());
+
+    assert!(validate("[]", CHAIN_CONFIG_SCHEMA).is_err());
+    assert!(validate("{", CHAIN_CONFIG_SCHEMA).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::config_schema::test_check_subnet_evm_chain_config --exact --show-output
+#[test]
+fn test_check_subnet_evm_chain_config() {
+    let (errors, warnings) = check_subnet_evm_chain_config(
+        r#"{"eth-apis":["eth","eth-filter","net","web3"],"warp-api-enabled":true,"state-sync-enabled":true}"#,
+    )
+    .unwrap();
+    assert!(errors.is_empty());
+    assert!(warnings.is_empty());
+
+    let (errors, _) =
+        check_subnet_evm_chain_config(r#"{"eth-apis":["eth","eth-fliter",1]}"#).unwrap();
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].contains("eth-fliter"));
+
+    let (errors, _) = check_subnet_evm_chain_config(
+        r#"{"pruning-enabled":false,"offline-pruning-enabled":true}"#,
+    )
+    .unwrap();
+    assert_eq!(errors.len(), 2);
+
+    let (errors, _) = check_subnet_evm_chain_config(r#"{"commit-interval":0}"#).unwrap();
+    assert_eq!(errors.len(), 1);
+
+    // state sync on an archival node
+    let (errors, warnings) =
+        check_subnet_evm_chain_config(r#"{"pruning-enabled":false,"state-sync-enabled":true}"#)
+            .unwrap();
+    assert!(errors.is_empty());
+    assert_eq!(warnings.len(), 1);
+
+    let (_, warnings) = check_subnet_evm_chain_config(
+        r#"{"state-sync-ids":"abc","warp-api-enabled":true,"eth-apis":["net"]}"#,
+    )
+    .unwrap();
+    assert_eq!(warnings.len(), 2);
+
+    assert!(check_subnet_evm_chain_config("{").is_err());
+}
@@ -0,0 +1,570 @@
+//! Backends that apply the avalanched commands (track the subnet, write the
+//! chain config, alias the chain) on the target nodes: EC2 instances via SSM,
+//! or the nodes of a local avalanche-network-runner (ANR) via local processes.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    io::{self, Error, ErrorKind},
+    pin::Pin,
+};
+
+use aws_credential_types::provider::SharedCredentialsProvider;
+use aws_manager::ssm;
+use crossterm::style::Color;
+use tokio::time::{timeout, Duration};
+
+use super::{
+    check_avalanchego_configs, check_ssm_docs, load_aws_config, send_ssm_commands, telemetry,
+    validation, Flags, InstallError, Ui,
+};
+
+pub const BACKEND_EC2: &str = "ec2";
+pub const BACKEND_ANR: &str = "anr";
+
+/// Maximum duration of the local process per ANR node (same as the SSM poll timeout).
+const ANR_EXEC_TIMEOUT: Duration = Duration::from_secs(300);
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Id of each command sent with its target machine Ids.
+pub type SentCommands = Vec<(String, Vec<String>)>;
+
+/// Runs the avalanched commands on the target nodes, where the node "machine Id"
+/// is the EC2 instance Id or the ANR node name.
+pub trait Deployer: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Checks that every target node can be reached, before any on-chain operation.
+    fn prepare<'a>(
+        &'a mut self,
+        region_to_instance_ids: &'a HashMap<String, Vec<String>>,
+    ) -> BoxFuture<'a, io::Result<()>>;
+
+    /// Runs the avalanched args on all target nodes, and returns the commands
+    /// sent with the result: on failure, the commands sent before it are still
+    /// returned, to be recorded in the output.
+    fn run<'a>(
+        &'a self,
+        region_to_instance_ids: &'a HashMap<String, Vec<String>>,
+        avalanched_args: &'a str,
+    ) -> BoxFuture<'a, (SentCommands, io::Result<()>)>;
+
+    /// Same as "run" for the chain config update.
+    fn run_chain_config<'a>(
+        &'a self,
+        region_to_instance_ids: &'a HashMap<String, Vec<String>>,
+        avalanched_args: &'a str,
+    ) -> BoxFuture<'a, (SentCommands, io::Result<()>)> {
+        self.run(region_to_instance_ids, avalanched_args)
+    }
+}
+
+/// Sends the SSM documents to the EC2 instances.
+pub struct Ec2SsmDeployer {
+    opts: Flags,
+    ui: Ui,
+    ssm_managers: HashMap<String, ssm::Manager>,
+    credentials_provider: Option<SharedCredentialsProvider>,
+    region_to_ssm_doc: HashMap<String, String>,
+    ssm_doc_version: Option<String>,
+}
+
+impl Ec2SsmDeployer {
+    pub(super) fn new(
+        opts: &Flags,
+        ui: Ui,
+        ssm_managers: HashMap<String, ssm::Manager>,
+        credentials_provider: Option<SharedCredentialsProvider>,
+        region_to_ssm_doc: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            opts: opts.clone(),
+            ui,
+            ssm_managers,
+            credentials_provider,
+            region_to_ssm_doc,
+            ssm_doc_version: if opts.ssm_doc_version.is_empty() {
+                None
+            } else {
+                Some(opts.ssm_doc_version.clone())
+            },
+        }
+    }
+}
+
+impl Deployer for Ec2SsmDeployer {
+    fn name(&self) -> &'static str {
+        BACKEND_EC2
+    }
+
+    /// Fails if a region cannot be reached via SSM, and loads the SSM manager
+    /// of each region not injected via "AwsClients". Then, checks the avalanchego
+    /// config on every instance (unless "--skip-avalanchego-config-check", or
+    /// "--print-ssm-args" as no command is sent).
+    fn prepare<'a>(
+        &'a mut self,
+        region_to_instance_ids: &'a HashMap<String, Vec<String>>,
+    ) -> BoxFuture<'a, io::Result<()>> {
+        Box::pin(async move {
+            check_ssm_docs(region_to_instance_ids, &self.region_to_ssm_doc).map_err(validation)?;
+            for region in regions_without_ssm_manager(&self.ssm_managers, region_to_instance_ids) {
+                log::info!("loading SSM manager for the region '{region}'");
+                let shared_config = load_aws_config(
+                    &region,
+                    &self.opts.profile_name,
+                    Duration::from_secs(30),
+                    &self.credentials_provider,
+                )
+                .await;
+                self.ssm_managers
+                    .insert(region, ssm::Manager::new(&shared_config));
+            }
+
+            if self.opts.skip_avalanchego_config_check || self.opts.print_ssm_args {
+                return Ok(());
+            }
+            self.ui.print(
+                Color::Green,
+                format!(
+                    "\n\n\nSTEP: checking the avalanchego config '{}' on the target instances\n\n",
+                    self.opts.avalanchego_config_remote_path
+                ),
+            )?;
+            check_avalanchego_configs(
+                &self.ssm_managers,
+                region_to_instance_ids,
+                &self.opts.avalanchego_config_remote_path,
+            )
+            .await
+        })
+    }
+
+    fn run<'a>(
+        &'a self,
+        region_to_instance_ids: &'a HashMap<String, Vec<String>>,
+        avalanched_args: &'a str,
+    ) -> BoxFuture<'a, (SentCommands, io::Result<()>)> {
+        Box::pin(async move {
+            let mut sent_commands = Vec::new();
+            let ret = send_ssm_commands(
+                &self.opts,
+                &self.ssm_managers,
+                &self.credentials_provider,
+                self.ui,
+                region_to_instance_ids,
+                &self.region_to_ssm_doc,
+                &self.ssm_doc_version,
+                avalanched_args,
+                &mut sent_commands,
+            )
+            .await;
+            (sent_commands, ret)
+        })
+    }
+
+    /// Sends the chain config update with the documents of "--ssm-docs".
+    fn run_chain_config<'a>(
+        &'a self,
+        region_to_instance_ids: &'a HashMap<String, Vec<String>>,
+        avalanched_args: &'a str,
+    ) -> BoxFuture<'a, (SentCommands, io::Result<()>)> {
+        Box::pin(async move {
+            let mut sent_commands = Vec::new();
+            let ret = send_ssm_commands(
+                &self.opts,
+                &self.ssm_managers,
+                &self.credentials_provider,
+                self.ui,
+                region_to_instance_ids,
+                &self.opts.ssm_docs,
+                &self.ssm_doc_version,
+                avalanched_args,
+                &mut sent_commands,
+            )
+            .await;
+            (sent_commands, ret)
+        })
+    }
+}
+
+/// Runs "--anr-node-exec" locally once per ANR node with the avalanched args,
+/// and the node info from the ANR status in the environment
+/// (ANR_ENDPOINT, ANR_NODE_NAME, ANR_NODE_URI, ANR_NODE_DB_DIR, ANR_NODE_LOG_DIR,
+/// ANR_NODE_PLUGIN_DIR), so that the executable can update the node and restart
+/// it via the ANR API.
+pub struct AnrDeployer {
+    endpoint: String,
+    node_exec: String,
+    print_only: bool,
+    ui: Ui,
+    node_infos: HashMap<String, serde_json::Value>,
+}
+
+impl AnrDeployer {
+    pub(super) fn new(opts: &Flags, ui: Ui) -> io::Result<Self> {
+        if opts.anr_node_exec.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "--backend anr requires --anr-node-exec",
+            ));
+        }
+        Ok(Self {
+            endpoint: opts.anr_endpoint.trim_end_matches('/').to_string(),
+            node_exec: opts.anr_node_exec.clone(),
+            print_only: opts.print_ssm_args,
+            ui,
+            node_infos: HashMap::new(),
+        })
+    }
+
+    /// Runs "--anr-node-exec" for each target node in the node name order,
+    /// recording each node run in "sent_commands" until the first failure.
+    async fn exec_nodes(
+        &self,
+        region_to_instance_ids: &HashMap<String, Vec<String>>,
+        avalanched_args: &str,
+        sent_commands: &mut SentCommands,
+    ) -> io::Result<()> {
+        let mut node_names: Vec<&String> = region_to_instance_ids.values().flatten().collect();
+        node_names.sort();
+
+        for node_name in node_names {
+            if self.print_only {
+                self.ui.print(
+                    Color::Magenta,
+                    format!(
+                        "\n\n\nANR NODE COMMAND (not run)\nnode name: {node_name}\ncommand: {} {avalanched_args}\n\n",
+                        self.node_exec
+                    ),
+                )?;
+                continue;
+            }
+
+            let _span = telemetry::span("anr exec", &[("node_name", node_name.clone())]);
+            log::info!(
+                "running '{}' for the ANR node '{node_name}'",
+                self.node_exec
+            );
+
+            // split like the shell that runs the SSM document, so quoted args stay whole
+            let args = shell_words::split(avalanched_args).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "failed to split the avalanched args '{avalanched_args}' ({})",
+                        e
+                    ),
+                )
+            })?;
+            let info = self.node_infos.get(node_name).cloned().unwrap_or_default();
+            let field = |k: &str| info[k].as_str().unwrap_or_default().to_string();
+            let mut cmd = tokio::process::Command::new(&self.node_exec);
+            cmd.args(args)
+                .env("ANR_AVALANCHED_ARGS", avalanched_args)
+                .env("ANR_ENDPOINT", &self.endpoint)
+                .env("ANR_NODE_NAME", node_name)
+                .env("ANR_NODE_URI", field("uri"))
+                .env("ANR_NODE_DB_DIR", field("dbDir"))
+                .env("ANR_NODE_LOG_DIR", field("logDir"))
+                .env("ANR_NODE_PLUGIN_DIR", field("pluginDir"))
+                .kill_on_drop(true);
+            let out = timeout(ANR_EXEC_TIMEOUT, cmd.output())
+                .await
+                .map_err(|_| {
+                    InstallError::SsmTimeout.error(
+                        ErrorKind::TimedOut,
+                        format!(
+                            "'{}' for the ANR node '{node_name}' did not exit in {:?}",
+                            self.node_exec, ANR_EXEC_TIMEOUT
+                        ),
+                    )
+                })?
+                .map_err(|e| {
+                    Error::new(
+                        ErrorKind::Other,
+                        format!("failed to run '{}' ({})", self.node_exec, e),
+                    )
+                })?;
+            if !out.status.success() {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    format!(
+                        "'{}' failed for the ANR node '{node_name}' ({}, stderr '{}')",
+                        self.node_exec,
+                        out.status,
+                        String::from_utf8_lossy(&out.stderr).trim()
+                    ),
+                ));
+            }
+            log::info!(
+                "'{}' succeeded for the ANR node '{node_name}' (stdout '{}')",
+                self.node_exec,
+                String::from_utf8_lossy(&out.stdout).trim()
+            );
+            sent_commands.push((format!("anr-{node_name}"), vec![node_name.clone()]));
+        }
+        Ok(())
+    }
+}
+
+impl Deployer for AnrDeployer {
+    fn name(&self) -> &'static str {
+        BACKEND_ANR
+    }
+
+    /// Fetches the ANR cluster status, and fails if a target node is not in the network.
+    fn prepare<'a>(
+        &'a mut self,
+        region_to_instance_ids: &'a HashMap<String, Vec<String>>,
+    ) -> BoxFuture<'a, io::Result<()>> {
+        Box::pin(async move {
+            log::info!("fetching the ANR status from '{}'", self.endpoint);
+            // ref. <https://github.com/ava-labs/avalanche-network-runner#using-avalanche-network-runner-remotely>
+            let resp = reqwest::Client::new()
+                .post(format!("{}/v1/control/status", self.endpoint))
+                .json(&serde_json::json!({}))
+                .timeout(Duration::from_secs(30))
+                .send()
+                .await
+                .map_err(|e| {
+                    Error::new(
+                        ErrorKind::Other,
+                        format!("failed to reach ANR at '{}' ({})", self.endpoint, e),
+                    )
+                })?;
+            let status: serde_json::Value = resp.json().await.map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("failed to parse the ANR status ({})", e),
+                )
+            })?;
+            self.node_infos = parse_anr_node_infos(&status)?;
+
+            let mut missing: Vec<&String> = region_to_instance_ids
+                .values()
+                .flatten()
+                .filter(|node_name| !self.node_infos.contains_key(*node_name))
+                .collect();
+            if !missing.is_empty() {
+                missing.sort();
+                let mut node_names: Vec<&String> = self.node_infos.keys().collect();
+                node_names.sort();
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "target nodes {:?} are not in the ANR network (node names {:?})",
+                        missing, node_names
+                    ),
+                ));
+            }
+            Ok(())
+        })
+    }
+
+    fn run<'a>(
+        &'a self,
+        region_to_instance_ids: &'a HashMap<String, Vec<String>>,
+        avalanched_args: &'a str,
+    ) -> BoxFuture<'a, (SentCommands, io::Result<()>)> {
+        Box::pin(async move {
+            let mut sent_commands = Vec::new();
+            let ret = self
+                .exec_nodes(region_to_instance_ids, avalanched_args, &mut sent_commands)
+                .await;
+            (sent_commands, ret)
+        })
+    }
+}
+
+/// Parses the node infos by node name from the ANR "/v1/control/status" response.
+pub fn parse_anr_node_infos(
+    status: &serde_json::Value,
+) -> io::Result<HashMap<String, serde_json::Value>> {
+    let node_infos = status["clusterInfo"]["nodeInfos"]
+        .as_object()
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("ANR status has no 'clusterInfo.nodeInfos' ({status})"),
+            )
+        })?;
+    Ok(node_infos
+        .iter()
+        .map(|(name, info)| (name.clone(), info.clone()))
+        .collect())
+}
+
+/// Returns the regions of the target nodes without an injected SSM manager, sorted.
+fn regions_without_ssm_manager(
+    ssm_managers: &HashMap<String, ssm::Manager>,
+    region_to_instance_ids: &HashMap<String, Vec<String>>,
+) -> Vec<String> {
+    let mut regions: Vec<String> = region_to_instance_ids
+        .keys()
+        .filter(|region| !ssm_managers.contains_key(*region))
+        .cloned()
+        .collect();
+    regions.sort();
+    regions
+}
+
+/// Returns the deployer for "--backend".
+pub(super) fn new_deployer(
+    opts: &Flags,
+    ui: Ui,
+    ssm_managers: HashMap<String, ssm::Manager>,
+    credentials_provider: Option<SharedCredentialsProvider>,
+    region_to_ssm_doc: HashMap<String, String>,
+) -> io::Result<Box<dyn Deployer>> {
+    match opts.backend.as_str() {
+        BACKEND_EC2 => Ok(Box::new(Ec2SsmDeployer::new(
+            opts,
+            ui,
+            ssm_managers,
+            credentials_provider,
+            region_to_ssm_doc,
+        ))),
+        BACKEND_ANR => Ok(Box::new(AnrDeployer::new(opts, ui)?)),
+        backend => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("unknown --backend '{backend}' (expected '{BACKEND_EC2}' or '{BACKEND_ANR}')"),
+        )),
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::deployer::test_parse_anr_node_infos --exact --show-output
+#[test]
+fn test_parse_anr_node_infos() {
+    let status = serde_json::json!({
+        "clusterInfo": {
+            "nodeNames": ["node1", "node2"],
+            "nodeInfos": {
+                "node1": {"name": "node1", "uri": "http://127.0.0.1:9650", "dbDir": "/tmp/node1/db"},
+                "node2": {"name": "node2", "uri": "http://127.0.0.1:9652", "dbDir": "/tmp/node2/db"}
+            },
+            "healthy": true
+        }
+    });
+    let node_infos = parse_anr_node_infos(&status).unwrap();
+    assert_eq!(node_infos.len(), 2);
+    assert_eq!(node_infos["node2"]["uri"], "http://127.0.0.1:9652");
+
+    let err = parse_anr_node_infos(&serde_json::json!({})).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::deployer::test_regions_without_ssm_manager --exact --show-output
+#[tokio::test]
+async fn test_regions_without_ssm_manager() {
+    // the clients do not call AWS until the first request, so they can be injected offline
+    let mut clients =
+        super::AwsClients::load("us-west-2", "default", Duration::from_secs(30)).await;
+    let shared_config = aws_manager::load_config(
+        Some(String::from("us-west-2")),
+        Some(String::from("default")),
+        Some(Duration::from_secs(30)),
+    )
+    .await;
+    clients
+        .ssm_managers
+        .insert(String::from("us-west-2"), ssm::Manager::new(&shared_config));
+
+    let region_to_instance_ids = HashMap::from([
+        (String::from("us-west-2"), vec![String::from("i-1")]),
+        (String::from("us-east-1"), vec![String::from("i-2")]),
+        (String::from("eu-west-1"), vec![String::from("i-3")]),
+    ]);
+    assert_eq!(
+        regions_without_ssm_manager(&clients.ssm_managers, &region_to_instance_ids),
+        vec![String::from("eu-west-1"), String::from("us-east-1")]
+    );
+
+    clients.ssm_managers.clear();
+    assert_eq!(
+        regions_without_ssm_manager(&clients.ssm_managers, &region_to_instance_ids).len(),
+        3
+    );
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::deployer::test_anr_deployer --exact --show-output
+#[cfg(unix)]
+#[tokio::test]
+async fn test_anr_deployer() {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // serves the ANR "/v1/control/status" of a network with the node "node1"
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = vec![0u8; 4096];
+            let _ = stream.read(&mut buf).await;
+            let body = r#"{"clusterInfo":{"nodeInfos":{"node1":{"uri":"http://127.0.0.1:9650","dbDir":"/tmp/node1/db"}}}}"#;
+            let resp = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(resp.as_bytes()).await;
+        }
+    });
+
+    // records one arg per line, then the node info from the environment
+    let dir = tempfile::tempdir().unwrap();
+    let recorded_path = dir.path().join("recorded");
+    let node_exec = dir.path().join("node-exec.sh");
+    std::fs::write(
+        &node_exec,
+        format!(
+            "#!/bin/sh\nprintf '%s\\n' \"$@\" \"$ANR_NODE_NAME\" \"$ANR_NODE_DB_DIR\" > '{}'\n",
+            recorded_path.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&node_exec, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut deployer = AnrDeployer {
+        endpoint,
+        node_exec: node_exec.display().to_string(),
+        print_only: false,
+        ui: Ui {
+            terminal: false,
+            color: false,
+        },
+        node_infos: HashMap::new(),
+    };
+
+    let missing = HashMap::from([(String::from("local"), vec![String::from("node2")])]);
+    let err = deployer.prepare(&missing).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let region_to_node_names =
+        HashMap::from([(String::from("local"), vec![String::from("node1")])]);
+    deployer.prepare(&region_to_node_names).await.unwrap();
+    let (sent_commands, ret) = deployer
+        .run(
+            &region_to_node_names,
+            "alias-chain --chain-alias 'my chain' --chain-aliases-path /tmp/aliases.json",
+        )
+        .await;
+    ret.unwrap();
+    assert_eq!(
+        sent_commands,
+        vec![(String::from("anr-node1"), vec![String::from("node1")])]
+    );
+
+    let recorded = std::fs::read_to_string(&recorded_path).unwrap();
+    assert_eq!(
+        recorded.lines().collect::<Vec<_>>(),
+        vec![
+            "alias-chain",
+            "--chain-alias",
+            "my chain",
+            "--chain-aliases-path",
+            "/tmp/aliases.json",
+            "node1",
+            "/tmp/node1/db",
+        ]
+    );
+}
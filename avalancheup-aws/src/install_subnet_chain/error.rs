@@ -0,0 +1,138 @@
+//! Failure classes of the install, mapped to distinct process exit codes so
+//! that wrapper scripts can retry, alert, or abort without parsing the logs.
+
+use std::{
+    fmt,
+    io::{self, ErrorKind},
+};
+
+/// Exit code of the failures that do not match any class.
+pub const EXIT_CODE_OTHER: i32 = 1;
+
+/// Class of the install failure, attached to the returned "io::Error".
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum InstallError {
+    /// Invalid flags, inputs, or configs (exit code 2).
+    Validation,
+    /// P-chain balance too low for the staking and the reserve (exit code 3).
+    InsufficientBalance,
+    /// SSM commands (or ANR node commands) that did not complete in time (exit code 4).
+    SsmTimeout,
+    /// RPC endpoint unreachable after the retries (exit code 5).
+    RpcUnreachable,
+    /// Interrupted by a signal before the install completed (exit code 6).
+    Interrupted,
+}
+
+impl InstallError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            InstallError::Validation => 2,
+            InstallError::InsufficientBalance => 3,
+            InstallError::SsmTimeout => 4,
+            InstallError::RpcUnreachable => 5,
+            InstallError::Interrupted => 6,
+        }
+    }
+
+    /// Returns the error with the message, tagged with this class.
+    pub fn error(self, kind: ErrorKind, msg: impl Into<String>) -> io::Error {
+        io::Error::new(
+            kind,
+            Tagged {
+                class: self,
+                msg: msg.into(),
+            },
+        )
+    }
+
+    /// Returns the tagged class of the error (None if untagged, since the
+    /// error kinds are shared with the RPC, S3, and parse failures).
+    pub fn classify(e: &io::Error) -> Option<Self> {
+        e.get_ref()
+            .and_then(|inner| inner.downcast_ref::<Tagged>())
+            .map(|tagged| tagged.class)
+    }
+}
+
+/// Tags the untagged invalid input or data error as a validation error,
+/// for the checks of the flags, inputs, and configs.
+pub fn validation(e: io::Error) -> io::Error {
+    if InstallError::classify(&e).is_none()
+        && matches!(e.kind(), ErrorKind::InvalidInput | ErrorKind::InvalidData)
+    {
+        return InstallError::Validation.error(e.kind(), e.to_string());
+    }
+    e
+}
+
+/// Returns the process exit code for the install error.
+pub fn exit_code(e: &io::Error) -> i32 {
+    InstallError::classify(e).map_or(EXIT_CODE_OTHER, |class| class.exit_code())
+}
+
+#[derive(Debug)]
+struct Tagged {
+    class: InstallError,
+    msg: String,
+}
+
+impl fmt::Display for Tagged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.msg)
+    }
+}
+
+impl std::error::Error for Tagged {}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::error::test_exit_code --exact --show-output
+#[test]
+fn test_exit_code() {
+    let e = InstallError::InsufficientBalance.error(ErrorKind::InvalidInput, "only has 1 nAVAX");
+    assert_eq!(e.to_string(), "only has 1 nAVAX");
+    assert_eq!(e.kind(), ErrorKind::InvalidInput);
+    // the tag takes precedence over the kind
+    assert_eq!(exit_code(&e), 3);
+
+    assert_eq!(
+        exit_code(&InstallError::SsmTimeout.error(ErrorKind::TimedOut, "")),
+        4
+    );
+    assert_eq!(
+        exit_code(&InstallError::RpcUnreachable.error(ErrorKind::Other, "")),
+        5
+    );
+    // untagged errors are not classified by the kind
+    assert_eq!(
+        exit_code(&io::Error::new(ErrorKind::InvalidInput, "")),
+        EXIT_CODE_OTHER
+    );
+    assert_eq!(
+        exit_code(&io::Error::new(ErrorKind::InvalidData, "")),
+        EXIT_CODE_OTHER
+    );
+    assert_eq!(
+        exit_code(&io::Error::new(ErrorKind::Interrupted, "")),
+        EXIT_CODE_OTHER
+    );
+
+    let e = validation(io::Error::new(ErrorKind::InvalidData, "bad genesis"));
+    assert_eq!(e.to_string(), "bad genesis");
+    assert_eq!(e.kind(), ErrorKind::InvalidData);
+    assert_eq!(exit_code(&e), 2);
+    // already tagged or other kinds are unchanged
+    assert_eq!(
+        exit_code(&validation(
+            InstallError::InsufficientBalance.error(ErrorKind::InvalidInput, "")
+        )),
+        3
+    );
+    assert_eq!(
+        exit_code(&validation(io::Error::new(ErrorKind::Other, ""))),
+        EXIT_CODE_OTHER
+    );
+    assert_eq!(
+        exit_code(&io::Error::new(ErrorKind::Other, "")),
+        EXIT_CODE_OTHER
+    );
+}
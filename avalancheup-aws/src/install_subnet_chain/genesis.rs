@@ -0,0 +1,184 @@
+//! Chain genesis helpers: parsing, fetching the genesis of a deployed
+//! blockchain, and diffing it against a baseline.
+
+use std::{
+    collections::BTreeSet,
+    io::{self, Error, ErrorKind},
+};
+
+use avalanche_types::ids;
+
+use super::{call_json_rpc, ApiPaths};
+
+pub fn parse_genesis_json(d: &[u8], name: &str) -> io::Result<serde_json::Value> {
+    serde_json::from_slice(d).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("{name} is not valid JSON, cannot diff ({})", e),
+        )
+    })
+}
+
+/// Fetches the genesis data of the blockchain from its "CreateChainTx" on the P-chain.
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgettx>
+pub async fn fetch_genesis_data(
+    p_chain_rpc_url: &str,
+    blockchain_id: &ids::Id,
+    rpc_headers: &[(String, String)],
+    api_paths: &ApiPaths,
+) -> io::Result<Vec<u8>> {
+    let result = call_json_rpc(
+        p_chain_rpc_url,
+        &api_paths.p_chain,
+        "platform.getTx",
+        serde_json::json!({
+            "txID": blockchain_id.to_string(),
+            "encoding": "json",
+        }),
+        rpc_headers,
+    )
+    .await?;
+    let genesis_data = result["tx"]["unsignedTx"]["genesisData"]
+        .as_str()
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("tx '{blockchain_id}' has no genesis data, not a CreateChainTx"),
+            )
+        })?;
+    decode_genesis_data(genesis_data)
+}
+
+/// Decodes the "genesisData" of the JSON-encoded "CreateChainTx",
+/// either hex ("0x" prefixed) or base64 (Go's JSON encoding of bytes).
+pub fn decode_genesis_data(genesis_data: &str) -> io::Result<Vec<u8>> {
+    use base64::Engine;
+    if let Some(h) = genesis_data.strip_prefix("0x") {
+        return hex::decode(h).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("failed to decode hex genesis data ({})", e),
+            )
+        });
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(genesis_data)
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("failed to decode base64 genesis data ({})", e),
+            )
+        })
+}
+
+/// Returns the structured diff of the intended genesis against the baseline,
+/// grouped by the top-level section (e.g., "config", "alloc"), with the
+/// changed paths in each section. Unchanged sections are omitted.
+/// The "alloc" addresses are compared case-insensitively, with or without "0x".
+pub fn diff_genesis(
+    baseline: &serde_json::Value,
+    intended: &serde_json::Value,
+) -> serde_json::Value {
+    let normalize = |v: &serde_json::Value| {
+        let mut v = v.clone();
+        if let Some(alloc) = v.get_mut("alloc").and_then(|a| a.as_object_mut()) {
+            *alloc = std::mem::take(alloc)
+                .into_iter()
+                .map(|(addr, account)| (addr.trim_start_matches("0x").to_lowercase(), account))
+                .collect();
+        }
+        v
+    };
+    let mut changes = serde_json::Map::new();
+    diff_json_values(
+        "",
+        Some(&normalize(baseline)),
+        Some(&normalize(intended)),
+        &mut changes,
+    );
+
+    let mut diff = serde_json::Map::new();
+    for (path, change) in changes {
+        let section = match path.trim_start_matches('/').split('/').next() {
+            Some(section) if !section.is_empty() => section.to_string(),
+            _ => String::from("(root)"),
+        };
+        if let serde_json::Value::Object(section_changes) = diff
+            .entry(section)
+            .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()))
+        {
+            section_changes.insert(path, change);
+        }
+    }
+    serde_json::Value::Object(diff)
+}
+
+/// Records the changed leaves under the path (e.g., "/feeConfig/minBaseFee")
+/// with the "baseline" and "intended" values (omitted if absent).
+fn diff_json_values(
+    path: &str,
+    baseline: Option<&serde_json::Value>,
+    intended: Option<&serde_json::Value>,
+    changes: &mut serde_json::Map<String, serde_json::Value>,
+) {
+    if let (Some(serde_json::Value::Object(b)), Some(serde_json::Value::Object(i))) =
+        (baseline, intended)
+    {
+        let keys: BTreeSet<&String> = b.keys().chain(i.keys()).collect();
+        for key in keys {
+            diff_json_values(&format!("{path}/{key}"), b.get(key), i.get(key), changes);
+        }
+        return;
+    }
+    if baseline == intended {
+        return;
+    }
+    let mut change = serde_json::Map::new();
+    if let Some(b) = baseline {
+        change.insert(String::from("baseline"), b.clone());
+    }
+    if let Some(i) = intended {
+        change.insert(String::from("intended"), i.clone());
+    }
+    let path = if path.is_empty() { "/" } else { path };
+    changes.insert(path.to_string(), serde_json::Value::Object(change));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::genesis::test_diff_genesis --exact --show-output
+#[test]
+fn test_diff_genesis() {
+    let baseline = serde_json::json!({
+        "config": {"chainId": 99999, "feeConfig": {"gasLimit": 8000000, "minBaseFee": 25000000000_u64}},
+        "alloc": {"0x8db97C7cEcE249c2b98bDC0226Cc4C2A57BF52FC": {"balance": "0x52B7D2DCC80CD2E4000000"}},
+        "gasLimit": "0x7A1200",
+        "timestamp": "0x0"
+    });
+    assert_eq!(diff_genesis(&baseline, &baseline), serde_json::json!({}));
+
+    let intended = serde_json::json!({
+        "config": {"chainId": 99999, "feeConfig": {"gasLimit": 15000000, "minBaseFee": 25000000000_u64}},
+        "alloc": {
+            "8db97c7cece249c2b98bdc0226cc4c2a57bf52fc": {"balance": "0x52B7D2DCC80CD2E4000000"},
+            "0x613040a239BDd6A67D6d0B8c0Ba6E3E6cB3fD2eE": {"balance": "0x1"}
+        },
+        "gasLimit": "0x7A1200"
+    });
+    assert_eq!(
+        diff_genesis(&baseline, &intended),
+        serde_json::json!({
+            "alloc": {
+                "/alloc/613040a239bdd6a67d6d0b8c0ba6e3e6cb3fd2ee": {"intended": {"balance": "0x1"}}
+            },
+            "config": {
+                "/config/feeConfig/gasLimit": {"baseline": 8000000, "intended": 15000000}
+            },
+            "timestamp": {
+                "/timestamp": {"baseline": "0x0"}
+            }
+        })
+    );
+
+    assert_eq!(decode_genesis_data("0x7b7d").unwrap(), b"{}".to_vec());
+    assert_eq!(decode_genesis_data("e30=").unwrap(), b"{}".to_vec());
+    assert!(decode_genesis_data("0xzz").is_err());
+}
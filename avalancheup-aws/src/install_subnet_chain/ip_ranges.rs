@@ -0,0 +1,192 @@
+//! Infers the AWS region of an RPC endpoint from its hostname, or from the
+//! published AWS IP address ranges.
+
+use std::io::{self, Error, ErrorKind};
+
+use tokio::time::timeout;
+
+use super::{rpc_host_port, RPC_PREFLIGHT_TIMEOUT};
+
+/// AWS public IP address ranges, with the region of each prefix.
+/// ref. <https://docs.aws.amazon.com/vpc/latest/userguide/aws-ip-ranges.html>
+const AWS_IP_RANGES_URL: &str = "https://ip-ranges.amazonaws.com/ip-ranges.json";
+
+/// Infers the AWS region of the RPC endpoint, from its hostname if it has the
+/// region (e.g., EC2 public DNS names, load balancers), or else by resolving
+/// the host and looking up its address in the AWS IP ranges.
+pub async fn infer_region_from_rpc(rpc_url: &str) -> io::Result<String> {
+    let (host, port) = rpc_host_port(rpc_url)?;
+    if let Some(region) = region_from_hostname(&host) {
+        return Ok(region);
+    }
+
+    let addrs: Vec<std::net::IpAddr> = timeout(
+        RPC_PREFLIGHT_TIMEOUT,
+        tokio::net::lookup_host((host.as_str(), port)),
+    )
+    .await
+    .map_err(|_| {
+        Error::new(
+            ErrorKind::TimedOut,
+            format!("timed out resolving host '{host}' of RPC '{rpc_url}' to infer the region"),
+        )
+    })??
+    .map(|addr| addr.ip())
+    .collect();
+
+    let resp = reqwest::get(AWS_IP_RANGES_URL).await.map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to fetch '{AWS_IP_RANGES_URL}' ({})", e),
+        )
+    })?;
+    let d = resp.text().await.map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to read '{AWS_IP_RANGES_URL}' ({})", e),
+        )
+    })?;
+    let ip_ranges: serde_json::Value = serde_json::from_str(&d).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("failed to parse '{AWS_IP_RANGES_URL}' ({})", e),
+        )
+    })?;
+    addrs
+        .iter()
+        .find_map(|ip| region_from_ip_ranges(&ip_ranges, ip))
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("RPC '{rpc_url}' ({:?}) is not in the AWS IP ranges, cannot infer the region (set the region of each target node)", addrs),
+            )
+        })
+}
+
+/// Returns the AWS region in the hostname, if any
+/// (e.g., "ec2-54-1-2-3.us-west-2.compute.amazonaws.com", "my-lb.elb.us-west-2.amazonaws.com").
+pub fn region_from_hostname(host: &str) -> Option<String> {
+    if !host.ends_with(".amazonaws.com") {
+        return None;
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    // us-east-1 EC2 public DNS names have no region (e.g., "ec2-54-1-2-3.compute-1.amazonaws.com")
+    if labels.contains(&"compute-1") {
+        return Some(String::from("us-east-1"));
+    }
+    // the first label is the resource name (e.g., a load balancer "my-lb-123")
+    labels
+        .into_iter()
+        .skip(1)
+        .find(|label| is_region_name(label))
+        .map(String::from)
+}
+
+/// Returns true if the name looks like an AWS region (e.g., "us-west-2", "us-gov-west-1").
+fn is_region_name(name: &str) -> bool {
+    let parts: Vec<&str> = name.split('-').collect();
+    if parts.len() < 3 || parts[0].len() != 2 || !parts[0].chars().all(|c| c.is_ascii_lowercase()) {
+        return false;
+    }
+    let last = parts[parts.len() - 1];
+    !last.is_empty()
+        && last.chars().all(|c| c.is_ascii_digit())
+        && parts[1..parts.len() - 1]
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_lowercase()))
+}
+
+/// Returns the region of the longest AWS IP range prefix that contains the address
+/// (ignoring the "GLOBAL" ranges), from the "ip-ranges.json" document.
+pub fn region_from_ip_ranges(
+    ip_ranges: &serde_json::Value,
+    ip: &std::net::IpAddr,
+) -> Option<String> {
+    let (prefixes, prefix_key) = match ip {
+        std::net::IpAddr::V4(_) => (&ip_ranges["prefixes"], "ip_prefix"),
+        std::net::IpAddr::V6(_) => (&ip_ranges["ipv6_prefixes"], "ipv6_prefix"),
+    };
+    let ip_bits = match ip {
+        std::net::IpAddr::V4(v4) => u128::from(u32::from(*v4)) << 96,
+        std::net::IpAddr::V6(v6) => u128::from(*v6),
+    };
+
+    let mut matched: Option<(u32, String)> = None;
+    for prefix in prefixes.as_array()?.iter() {
+        let (Some(cidr), Some(region)) = (prefix[prefix_key].as_str(), prefix["region"].as_str())
+        else {
+            continue;
+        };
+        if region == "GLOBAL" {
+            continue;
+        }
+        let Some((network, len)) = cidr.split_once('/') else {
+            continue;
+        };
+        let (Ok(network), Ok(len)) = (network.parse::<std::net::IpAddr>(), len.parse::<u32>())
+        else {
+            continue;
+        };
+        let (network_bits, len) = match network {
+            std::net::IpAddr::V4(v4) => (u128::from(u32::from(v4)) << 96, len),
+            std::net::IpAddr::V6(v6) => (u128::from(v6), len),
+        };
+        if len > 128 || network.is_ipv4() != ip.is_ipv4() {
+            continue;
+        }
+        let mask = if len == 0 {
+            0
+        } else {
+            u128::MAX << (128 - len)
+        };
+        if ip_bits & mask == network_bits & mask
+            && matched.as_ref().map(|(l, _)| len > *l).unwrap_or(true)
+        {
+            matched = Some((len, region.to_string()));
+        }
+    }
+    matched.map(|(_, region)| region)
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::ip_ranges::test_infer_region --exact --show-output
+#[test]
+fn test_infer_region() {
+    assert_eq!(
+        region_from_hostname("ec2-54-1-2-3.us-west-2.compute.amazonaws.com"),
+        Some(String::from("us-west-2"))
+    );
+    assert_eq!(
+        region_from_hostname("my-lb-123.elb.eu-central-1.amazonaws.com"),
+        Some(String::from("eu-central-1"))
+    );
+    assert_eq!(
+        region_from_hostname("ec2-54-1-2-3.compute-1.amazonaws.com"),
+        Some(String::from("us-east-1"))
+    );
+    assert_eq!(
+        region_from_hostname("vpce-1.ssm.us-gov-west-1.vpce.amazonaws.com"),
+        Some(String::from("us-gov-west-1"))
+    );
+    assert_eq!(region_from_hostname("api.avax.network"), None);
+    assert_eq!(region_from_hostname("s3.amazonaws.com"), None);
+
+    let ip_ranges = serde_json::json!({
+        "prefixes": [
+            {"ip_prefix": "3.0.0.0/8", "region": "GLOBAL", "service": "AMAZON"},
+            {"ip_prefix": "3.5.0.0/16", "region": "us-east-1", "service": "AMAZON"},
+            {"ip_prefix": "3.5.140.0/22", "region": "ap-northeast-2", "service": "EC2"},
+            {"ip_prefix": "54.0.0.0/12", "region": "us-west-2", "service": "EC2"}
+        ],
+        "ipv6_prefixes": [
+            {"ipv6_prefix": "2600:1f14::/35", "region": "us-west-2", "service": "EC2"}
+        ]
+    });
+    let region = |ip: &str| region_from_ip_ranges(&ip_ranges, &ip.parse().unwrap());
+    assert_eq!(region("3.5.141.1"), Some(String::from("ap-northeast-2")));
+    assert_eq!(region("3.5.1.1"), Some(String::from("us-east-1")));
+    assert_eq!(region("54.1.2.3"), Some(String::from("us-west-2")));
+    assert_eq!(region("3.6.1.1"), None);
+    assert_eq!(region("8.8.8.8"), None);
+    assert_eq!(region("2600:1f14::1"), Some(String::from("us-west-2")));
+    assert_eq!(region("2600:1f18::1"), None);
+}
@@ -1,27 +1,65 @@
 use std::{
-    collections::HashMap,
-    fs::File,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs::{self, File},
     io::{self, stdout, BufReader, Error, ErrorKind, Read},
     path::Path,
+    process::Command as ProcessCommand,
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, Mutex},
+    time::Instant,
 };
 
 use avalanche_types::{
+    constants,
     ids::{self, node},
-    jsonrpc::client::info as json_client_info,
-    key, subnet, units, wallet,
+    jsonrpc::client::{health as json_client_health, info as json_client_info, p as json_client_p},
+    key, subnet,
+    subnet_evm::genesis as subnet_evm_genesis,
+    wallet,
 };
+use aws_config::{sts::AssumeRoleProvider, timeout::TimeoutConfig, SdkConfig};
+use aws_credential_types::provider::SharedCredentialsProvider;
 use aws_manager::{self, s3, ssm, sts};
+use aws_sdk_s3::{
+    primitives::ByteStream,
+    types::{Tag, Tagging},
+};
 use aws_sdk_ssm::types::CommandInvocationStatus;
-use clap::{value_parser, Arg, Command};
+use chrono::{DateTime, TimeZone, Utc};
+use clap::{value_parser, Arg, ArgAction, Command};
 use crossterm::{
     execute,
     style::{Color, Print, ResetColor, SetForegroundColor},
 };
-use dialoguer::{theme::ColorfulTheme, Select};
+use dialoguer::{
+    theme::{ColorfulTheme, SimpleTheme, Theme},
+    Input, Select,
+};
 use serde::{Deserialize, Serialize};
-use tokio::time::{sleep, Duration};
+use tokio::{
+    sync::Semaphore,
+    task::JoinSet,
+    time::{sleep, timeout, Duration},
+};
+use zeroize::Zeroizing;
+
+mod config_schema;
+mod deployer;
+mod error;
+mod genesis;
+mod ip_ranges;
+mod permissions;
+mod telemetry;
+mod utxo;
+mod vm_binary;
+
+pub use error::{exit_code, validation, InstallError};
+use genesis::{diff_genesis, fetch_genesis_data, parse_genesis_json};
+use ip_ranges::infer_region_from_rpc;
+use permissions::{check_permissions, required_permissions};
+use utxo::{
+    fetch_p_chain_balance_from_utxos, fetch_p_chain_utxos, p_chain_balance_from_utxos, UtxoSnapshot,
+};
 
 pub const NAME: &str = "install-subnet-chain";
 
@@ -29,39 +67,130 @@ pub const NAME: &str = "install-subnet-chain";
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct Flags {
     pub log_level: String,
+    pub log_file: String,
+    pub no_color: bool,
 
     pub skip_prompt: bool,
+    pub confirm_phrase: String,
     pub spec_file_path: String,
 
     pub s3_region: String,
     pub s3_bucket: String,
     pub s3_key_prefix: String,
     pub s3_upload_timeout: u64,
+    pub s3_object_tags: HashMap<String, String>,
 
     pub chain_rpc_url: String,
-    pub key: String,
+    pub p_chain_rpc_url: String,
+    pub tx_submit_rpc_url: String,
+    pub balance_utxo_snapshot: String,
+    pub refresh_balance_utxos: bool,
+    pub save_balance_utxo_snapshot: String,
+    pub node_rpc_urls: HashMap<String, String>,
+    pub query_rpc_headers: Vec<String>,
+    pub p_chain_api_path: String,
+    pub info_api_path: String,
+    pub evm_api_path: String,
+    /// Hotkey in hex format, scrubbed from memory on drop.
+    pub key: Zeroizing<String>,
+    /// Other subnet control keys in hex format that co-sign the subnet auth, scrubbed from memory on drop.
+    pub subnet_auth_keys: Vec<Zeroizing<String>>,
 
     pub primary_network_validate_period_in_days: u64,
     pub subnet_validate_period_in_days: u64,
+    pub subnet_validation_end_at: String,
     pub staking_amount_in_avax: u64,
+    pub min_balance_reserve_avax: u64,
+    pub staking_asset_id: String,
+    pub staking_decimals: u32,
+    pub stake_asset_symbol: String,
 
     pub subnet_config_local_path: String,
     pub subnet_config_remote_dir: String,
+    pub subnet_config_overrides: String,
+    pub validator_only: bool,
+    pub allowed_nodes: Vec<String>,
 
     pub vm_binary_local_path: String,
     pub vm_binary_remote_dir: String,
+    pub skip_vm_binary_upload: bool,
+    pub vm_binary_s3_key: String,
     pub vm_id: String,
+    pub vm_id_derivation: String,
+    pub expected_vm_version: String,
+    pub verify_vm_id_in_binary: bool,
+    pub vm_binary_sha256: String,
+    pub vm_binary_sig: String,
+    pub vm_binary_pubkey: String,
     pub chain_name: String,
     pub chain_genesis_path: String,
+    pub dry_run_genesis_diff: bool,
+    pub baseline_genesis_path: String,
+    pub baseline_blockchain_id: String,
+
+    pub generate_subnet_evm_genesis: bool,
+    pub subnet_evm_chain_id: u64,
+    pub subnet_evm_gas_limit: u64,
+    pub subnet_evm_fee_config: String,
+    pub subnet_evm_allocs: Vec<String>,
+    pub output_dir: String,
 
     pub chain_config_local_path: String,
     pub chain_config_remote_dir: String,
+    pub chain_config_remote_filename: String,
+    pub skip_config_validation: bool,
+    pub skip_chain_config_validation: bool,
+
+    pub chain_alias: String,
+    pub chain_aliases_remote_path: String,
 
     pub avalanchego_config_remote_path: String,
+    pub skip_avalanchego_config_check: bool,
+    pub track_subnets_mode: String,
+    pub emit_node_config: String,
+    pub post_install_hook: String,
+    pub fail_on_hook_error: bool,
+
+    pub backend: String,
+    pub anr_endpoint: String,
+    pub anr_node_exec: String,
 
     pub ssm_docs: HashMap<String, String>,
+    pub ssm_doc_version: String,
+    pub ssm_max_attempts: u32,
+    pub region_concurrency: u32,
+    pub max_validators: u32,
+    pub otel_endpoint: String,
+    pub print_ssm_args: bool,
+    pub print_env: bool,
+    pub plan_output: String,
+    pub check_permissions: bool,
+    pub region_from_rpc: bool,
+    pub verbose_timing: bool,
+    pub wait_for_bootstrap_minutes: u64,
     pub target_nodes: HashMap<String, avalanche_ops::aws::spec::RegionMachineId>,
+    pub bls_signer_per_node: HashMap<String, key::bls::ProofOfPossession>,
+    pub non_validating_node_ids: Vec<String>,
+    pub stake_unhealthy: bool,
+    pub continue_on_existing_subnet_validator: bool,
+    pub batch_validator_adds: bool,
     pub profile_name: String,
+    pub assume_role_arn: String,
+    pub external_id: String,
+    pub session_name: String,
+
+    pub overall_timeout_minutes: u64,
+
+    pub subnet_id: String,
+    /// P-chain addresses of the new subnet's control keys (defaults to the "--key" address).
+    pub subnet_control_keys: Vec<String>,
+    pub subnet_threshold: u32,
+    pub exit_after_create_subnet: bool,
+    pub strict_id_prediction: bool,
+    pub no_check_acceptance: bool,
+    pub tx_status_poll_interval_ms: u64,
+    /// Maximum seconds to wait for the acceptance of the issued transactions.
+    pub acceptance_timeout_seconds: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -111,6 +240,72 @@ impl clap::builder::TypedValueParser for HashMapStringToRegionInstanceIdParser {
     }
 }
 
+/// Parses "--target-nodes" like [`HashMapStringToRegionInstanceIdParser`],
+/// except that the region may be omitted (empty, to be inferred from the RPC endpoint).
+#[derive(Clone, Debug)]
+pub struct TargetNodesParser;
+
+#[derive(Deserialize)]
+struct TargetNode {
+    #[serde(default)]
+    region: String,
+    machine_id: String,
+}
+
+impl clap::builder::TypedValueParser for TargetNodesParser {
+    type Value = HashMap<String, avalanche_ops::aws::spec::RegionMachineId>;
+
+    fn parse_ref(
+        &self,
+        _cmd: &Command,
+        _arg: Option<&Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        let s = value.to_str().unwrap_or_default();
+        let m: HashMap<String, TargetNode> = serde_json::from_str(s).map_err(|e| {
+            clap::Error::raw(
+                clap::error::ErrorKind::InvalidValue,
+                format!("HashMap parsing '{}' failed ({})", s, e),
+            )
+        })?;
+        Ok(m.into_iter()
+            .map(|(node_id, n)| {
+                (
+                    node_id,
+                    avalanche_ops::aws::spec::RegionMachineId {
+                        region: n.region,
+                        machine_id: n.machine_id,
+                    },
+                )
+            })
+            .collect())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HashMapStringToProofOfPossessionParser;
+
+impl clap::builder::TypedValueParser for HashMapStringToProofOfPossessionParser {
+    type Value = HashMap<String, key::bls::ProofOfPossession>;
+
+    fn parse_ref(
+        &self,
+        _cmd: &Command,
+        _arg: Option<&Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        let s = value.to_str().unwrap_or_default();
+        let m: HashMap<String, key::bls::ProofOfPossession> =
+            serde_json::from_str(s).map_err(|e| {
+                clap::Error::raw(
+                    clap::error::ErrorKind::InvalidValue,
+                    format!("HashMap parsing '{}' failed ({})", s, e),
+                )
+            })?;
+        Ok(m)
+    }
+}
+
 pub fn command() -> Command {
     Command::new(NAME)
         .about("Installs subnet and chain to target nodes")
@@ -124,6 +319,20 @@ pub fn command() -> Command {
                 .value_parser(["debug", "info"])
                 .default_value("info"),
         )
+        .arg(
+            Arg::new("LOG_FILE")
+                .long("log-file")
+                .help("Sets the file to also write the logs at 'debug' level (suffixed with the Unix timestamp of the run)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("NO_COLOR")
+                .long("no-color")
+                .help("Disables the colored output (also disabled if NO_COLOR is set or stdout is not a terminal)")
+                .required(false)
+                .num_args(0),
+        )
         .arg(
             Arg::new("SKIP_PROMPT")
                 .long("skip-prompt")
@@ -131,6 +340,13 @@ pub fn command() -> Command {
                 .required(false)
                 .num_args(0),
         )
+        .arg(
+            Arg::new("CONFIRM_PHRASE")
+                .long("confirm-phrase")
+                .help("Sets the phrase (e.g., chain name) that must be typed to confirm the install (ignored with --skip-prompt)")
+                .required(false)
+                .num_args(1),
+        )
         .arg(
             Arg::new("SPEC_FILE_PATH")
                 .long("spec-file-path")
@@ -170,6 +386,14 @@ pub fn command() -> Command {
                 .value_parser(value_parser!(u64))
                 .default_value("30")
         )
+        .arg(
+            Arg::new("S3_OBJECT_TAGS")
+                .long("s3-object-tags")
+                .help("Sets the hash map of tags in JSON format for the uploaded S3 objects (e.g., for lifecycle rules and cost allocation), in addition to the run Id and chain name tags; requires 's3:PutObjectTagging', otherwise the default tags are best-effort")
+                .required(false)
+                .value_parser(HashMapStringToStringParser {})
+                .num_args(1),
+        )
         .arg(
             Arg::new("CHAIN_RPC_URL")
                 .long("chain-rpc-url")
@@ -177,6 +401,81 @@ pub fn command() -> Command {
                 .required(true)
                 .num_args(1),
         )
+        .arg(
+            Arg::new("P_CHAIN_RPC_URL")
+                .long("p-chain-rpc-url")
+                .help("Sets the Avalanche RPC endpoint for the wallet and P-chain queries (if empty, defaults to --chain-rpc-url, see --tx-submit-rpc-url to issue transactions elsewhere, the wallet does not support per-chain endpoints, so X/C-chain use the same endpoint)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("TX_SUBMIT_RPC_URL")
+                .long("tx-submit-rpc-url")
+                .help("Sets the Avalanche RPC endpoint to issue the P-chain transactions (if empty, defaults to --p-chain-rpc-url, balance and subnet queries still use --p-chain-rpc-url)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("BALANCE_UTXO_SNAPSHOT")
+                .long("balance-utxo-snapshot")
+                .help("Sets the P-chain UTXO snapshot file (saved by --save-balance-utxo-snapshot) for the pre-flight balance check only, instead of fetching the UTXO set (may be stale if the key spent since; the wallet still fetches the live UTXOs for every transaction)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("REFRESH_BALANCE_UTXOS")
+                .long("refresh-balance-utxos")
+                .help("Sets to fetch the live UTXO set even with --balance-utxo-snapshot, and to update the snapshot file")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("SAVE_BALANCE_UTXO_SNAPSHOT")
+                .long("save-balance-utxo-snapshot")
+                .help("Sets the file to save the fetched P-chain UTXO snapshot to, for --balance-utxo-snapshot in later runs")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("QUERY_RPC_HEADER")
+                .long("query-rpc-header")
+                .help("Sets the 'Name: Value' header for the JSON-RPC calls sent directly by this command (repeatable, values redacted in logs), only with --dry-run-genesis-diff since the wallet cannot send the headers")
+                .required(false)
+                .num_args(1)
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("P_CHAIN_API_PATH")
+                .long("p-chain-api-path")
+                .help("Sets the P-chain API path of the RPC endpoints (e.g., for API gateways that route by a path prefix), the standard path behind the same prefix as --info-api-path")
+                .required(false)
+                .num_args(1)
+                .default_value(DEFAULT_P_CHAIN_API_PATH),
+        )
+        .arg(
+            Arg::new("INFO_API_PATH")
+                .long("info-api-path")
+                .help("Sets the info API path of the RPC endpoints (e.g., for API gateways that route by a path prefix)")
+                .required(false)
+                .num_args(1)
+                .default_value(DEFAULT_INFO_API_PATH),
+        )
+        .arg(
+            Arg::new("EVM_API_PATH")
+                .long("evm-api-path")
+                .help("Sets the EVM RPC path of the new blockchain on the node RPC endpoints, '{blockchain_id}' is replaced with the blockchain Id")
+                .required(false)
+                .num_args(1)
+                .default_value(DEFAULT_EVM_API_PATH),
+        )
+        .arg(
+            Arg::new("NODE_RPC_URLS")
+                .long("node-rpc-urls")
+                .help("Sets the hash map of node Id to its own RPC endpoint in JSON format for per-node verification (nodes not found skip the pre-stake check and are reported as unknown in the final verification)")
+                .required(false)
+                .value_parser(HashMapStringToStringParser {})
+                .num_args(1),
+        )
         .arg(
             Arg::new("KEY")
                 .long("key")
@@ -184,6 +483,13 @@ pub fn command() -> Command {
                 .required(true)
                 .num_args(1),
         )
+        .arg(
+            Arg::new("SUBNET_AUTH_KEYS")
+                .long("subnet-auth-keys")
+                .help("Sets the comma-separated private keys in hex format of the other subnet control keys that co-sign the subnet-authorized transactions (add subnet validator, create chain) with --key, for an existing --subnet-id whose threshold is greater than 1")
+                .required(false)
+                .num_args(1),
+        )
         .arg(
             Arg::new("PRIMARY_NETWORK_VALIDATE_PERIOD_IN_DAYS") 
                 .long("primary-network-validate-period-in-days")
@@ -202,6 +508,13 @@ pub fn command() -> Command {
                 .value_parser(value_parser!(u64))
                 .default_value("14"),
         )
+        .arg(
+            Arg::new("SUBNET_VALIDATION_END_AT")
+                .long("subnet-validation-end-at")
+                .help("Sets the absolute end time of the subnet validation for all nodes, in Unix seconds or RFC 3339 (e.g., '2023-07-01T00:00:00Z', overrides --subnet-validate-period-in-days, must be before the primary network validation of every node ends, checked against the P-chain)")
+                .required(false)
+                .num_args(1),
+        )
         .arg(
             Arg::new("STAKING_AMOUNT_IN_AVAX")
                 .long("staking-amount-in-avax")
@@ -213,6 +526,23 @@ pub fn command() -> Command {
                 .value_parser(value_parser!(u64))
                 .default_value("2000"),
         )
+        .arg(
+            Arg::new("STAKING_ASSET_ID")
+                .long("staking-asset-id")
+                .help("Sets the staking asset Id for custom networks (must match the network's staking asset, empty then AVAX)")
+                .required(false)
+                .num_args(1),
+        )
+        .args(staking_asset_args())
+        .arg(
+            Arg::new("MIN_BALANCE_RESERVE_AVAX")
+                .long("min-balance-reserve-avax")
+                .help("Sets the minimum P-chain balance in AVAX (not in nAVAX) to keep in the wallet after staking (rejects the run if it would leave less)")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("0"),
+        )
         .arg(
             Arg::new("SUBNET_CONFIG_LOCAL_PATH")
                 .long("subnet-config-local-path")
@@ -235,17 +565,45 @@ pub fn command() -> Command {
                 .required(false)
                 .num_args(1),
         )
+        .arg(
+            Arg::new("SUBNET_CONFIG_OVERRIDES")
+                .long("subnet-config-overrides")
+                .help("Sets the JSON to deep-merge into the subnet config local file before upload (e.g., '{\"proposerMinBlockDelay\":0}')")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("VALIDATOR_ONLY")
+                .long("validator-only")
+                .help("Sets to make the subnet private ('validatorOnly' in the subnet config, generated if --subnet-config-local-path is empty)")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("ALLOWED_NODES")
+                .long("allowed-nodes")
+                .help("Sets the comma-separated node Ids allowed to connect to the private subnet ('allowedNodes' in the subnet config, requires --validator-only)")
+                .required(false)
+                .num_args(1),
+        )
         .arg(
             Arg::new("VM_BINARY_LOCAL_PATH")
                 .long("vm-binary-local-path")
-                .help("VM binary local file path")
-                .required(true)
+                .help("VM binary local file path (optional with --skip-vm-binary-upload)")
+                .required(false)
                 .num_args(1),
         )
+        .arg(
+            Arg::new("SKIP_VM_BINARY_UPLOAD")
+                .long("skip-vm-binary-upload")
+                .help("Sets to skip uploading/downloading the VM binary, when it is already installed in --vm-binary-remote-dir (e.g., baked into the AMI)")
+                .required(false)
+                .num_args(0),
+        )
         .arg(
             Arg::new("VM_BINARY_S3_KEY")
                 .long("vm-binary-s3-key")
-                .help("Sets the S3 key for the Vm binary (if empty, default to local file name)")
+                .help("Sets the S3 key for the Vm binary (if empty, default to the S3 key prefix and Vm Id; if set without --vm-binary-local-path, reuses the existing S3 object)")
                 .required(false)
                 .num_args(1),
         )
@@ -259,7 +617,51 @@ pub fn command() -> Command {
         .arg(
             Arg::new("VM_ID")
                 .long("vm-id")
-                .help("Sets the 32-byte Vm Id for the Vm binary (if empty, converts chain name to Id)")
+                .help("Sets the 32-byte Vm Id for the Vm binary (if empty, derives the Id with --vm-id-derivation)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("VM_ID_DERIVATION")
+                .long("vm-id-derivation")
+                .help("Sets how to derive the Vm Id if --vm-id is empty: 'name' converts the chain name to Id, 'sha256-binary' uses the SHA-256 digest of the Vm binary")
+                .required(false)
+                .num_args(1)
+                .value_parser([VM_ID_DERIVATION_NAME, VM_ID_DERIVATION_SHA256_BINARY])
+                .default_value(VM_ID_DERIVATION_NAME),
+        )
+        .arg(
+            Arg::new("EXPECTED_VM_VERSION")
+                .long("expected-vm-version")
+                .help("Sets the expected VM version, to be matched against the local VM binary '--version' output before upload")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("VERIFY_VM_ID_IN_BINARY")
+                .long("verify-vm-id-in-binary")
+                .help("Sets to check that the local VM binary embeds the VM Id (as the CB58 string or the raw 32 bytes) before any transaction, for VMs that embed their own Id (e.g., subnet-evm), since the nodes refuse to load a plugin whose Id does not match")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("VM_BINARY_SHA256")
+                .long("vm-binary-sha256")
+                .help("Sets the expected hex-encoded SHA-256 of the local VM binary, verified before upload")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("VM_BINARY_SIG")
+                .long("vm-binary-sig")
+                .help("Sets the detached secp256k1 ECDSA signature file (DER or 64-byte r || s) over the SHA-256 of the local VM binary (requires --vm-binary-pubkey)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("VM_BINARY_PUBKEY")
+                .long("vm-binary-pubkey")
+                .help("Sets the hex-encoded SEC1 secp256k1 public key to verify --vm-binary-sig")
                 .required(false)
                 .num_args(1),
         )
@@ -273,10 +675,78 @@ pub fn command() -> Command {
         .arg(
             Arg::new("CHAIN_GENESIS_PATH")
                 .long("chain-genesis-path")
-                .help("Chain genesis file path")
-                .required(true)
+                .help("Chain genesis file path (not required with --generate-subnet-evm-genesis)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("DRY_RUN_GENESIS_DIFF")
+                .long("dry-run-genesis-diff")
+                .help("Sets to print the JSON diff of the intended genesis against --baseline-genesis-path or --baseline-blockchain-id, and exit without any change")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("BASELINE_GENESIS_PATH")
+                .long("baseline-genesis-path")
+                .help("Sets the previously deployed genesis file path to diff against (with --dry-run-genesis-diff)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("BASELINE_BLOCKCHAIN_ID")
+                .long("baseline-blockchain-id")
+                .help("Sets the previously deployed blockchain Id to fetch the genesis from the P-chain and diff against (with --dry-run-genesis-diff)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("GENERATE_SUBNET_EVM_GENESIS")
+                .long("generate-subnet-evm-genesis")
+                .help("Sets to generate the subnet-evm genesis from --chain-id, --gas-limit, --fee-config, and --alloc (in place of --chain-genesis-path)")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("CHAIN_ID")
+                .long("chain-id")
+                .help("Sets the subnet-evm chain Id for the generated genesis (zero then use defaults)")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("GAS_LIMIT")
+                .long("gas-limit")
+                .help("Sets the subnet-evm gas limit for the generated genesis (zero then use defaults)")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("FEE_CONFIG")
+                .long("fee-config")
+                .help("Sets the subnet-evm fee config JSON for the generated genesis (e.g., '{\"targetBlockRate\":2,\"minBaseFee\":25000000000}')")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("ALLOC")
+                .long("alloc")
+                .help("Sets the comma-separated initial allocations for the generated genesis in 'address:balance' format (balance in wei)")
+                .required(false)
                 .num_args(1),
         )
+        .arg(
+            Arg::new("OUTPUT_DIR")
+                .long("output-dir")
+                .help("Sets the local directory to write the generated genesis, the subnet Id (with --exit-after-create-subnet), and the run output JSON (with the step timings) to")
+                .required(false)
+                .num_args(1)
+                .default_value("."),
+        )
         .arg(
             Arg::new("CHAIN_CONFIG_LOCAL_PATH")
                 .long("chain-config-local-path")
@@ -299,6 +769,42 @@ pub fn command() -> Command {
                 .required(false)
                 .num_args(1),
         )
+        .arg(
+            Arg::new("CHAIN_CONFIG_REMOTE_FILENAME")
+                .long("chain-config-remote-filename")
+                .help("Sets the chain configuration file name in '{chain-config-remote-dir}/{blockchain Id}/' (for VMs not reading 'config.json', the subnet-evm chain config checks only run for '.json' names)")
+                .required(false)
+                .num_args(1)
+                .default_value("config.json"),
+        )
+        .arg(
+            Arg::new("SKIP_CONFIG_VALIDATION")
+                .long("skip-config-validation")
+                .help("Sets to skip validating the subnet and chain config files against the known keys")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("SKIP_CHAIN_CONFIG_VALIDATION")
+                .long("skip-chain-config-validation")
+                .help("Sets to skip checking the subnet-evm chain config values for consistency (e.g., eth-apis, pruning, state sync)")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("CHAIN_ALIAS")
+                .long("chain-alias")
+                .help("Sets the human-readable alias for the new blockchain (e.g., /ext/bc/[ALIAS]/rpc), written to --chain-aliases-remote-path and registered via 'admin.aliasChain' on the nodes in --node-rpc-urls")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("CHAIN_ALIASES_REMOTE_PATH")
+                .long("chain-aliases-remote-path")
+                .help("avalanchego chain aliases file remote path (required with --chain-alias, see avalanchego '--chain-aliases-file')")
+                .required(false)
+                .num_args(1),
+        )
         .arg(
             Arg::new("AVALANCHEGO_CONFIG_REMOTE_PATH")
                 .long("avalanchego-config-remote-path")
@@ -306,740 +812,6591 @@ pub fn command() -> Command {
                 .required(true)
                 .num_args(1),
         )
+        .arg(
+            Arg::new("SKIP_AVALANCHEGO_CONFIG_CHECK")
+                .long("skip-avalanchego-config-check")
+                .help("Skips checking that --avalanchego-config-remote-path exists and is valid JSON on every target EC2 instance (via the 'AWS-RunShellScript' SSM document) before any on-chain operation")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("TRACK_SUBNETS_MODE")
+                .long("track-subnets-mode")
+                .help("Sets whether the nodes merge the new subnet Id into their existing track-subnets or replace them")
+                .required(false)
+                .num_args(1)
+                .value_parser(["merge", "replace"])
+                .default_value("merge"),
+        )
+        .arg(
+            Arg::new("BACKEND")
+                .long("backend")
+                .help("Sets the backend to run the avalanched commands on the target nodes with: 'ec2' for SSM documents on EC2 instances, 'anr' for local processes on the nodes of a local avalanche-network-runner (the target node machine Id is the ANR node name)")
+                .required(false)
+                .num_args(1)
+                .value_parser(["ec2", "anr"])
+                .default_value("ec2"),
+        )
+        .arg(
+            Arg::new("ANR_ENDPOINT")
+                .long("anr-endpoint")
+                .help("Sets the avalanche-network-runner gRPC gateway endpoint for '--backend anr'")
+                .required(false)
+                .num_args(1)
+                .default_value("http://127.0.0.1:8081"),
+        )
+        .arg(
+            Arg::new("ANR_NODE_EXEC")
+                .long("anr-node-exec")
+                .help("Sets the local executable to run per ANR node with the avalanched args for '--backend anr' (with ANR_ENDPOINT, ANR_NODE_NAME, ANR_NODE_URI, ANR_NODE_DB_DIR, ANR_NODE_LOG_DIR, and ANR_NODE_PLUGIN_DIR set)")
+                .required(false)
+                .required_if_eq("BACKEND", "anr")
+                .num_args(1),
+        )
         .arg(
             Arg::new("SSM_DOCS")
                 .long("ssm-docs")
-                .help("Sets the hash map of AWS region to SSM document name for subnet and chain install (see avalanche-ops/src/aws/cfn-templates/ssm_install_subnet_chain.yaml)")
+                .help("Sets the hash map of AWS region to SSM document name for subnet and chain install (see avalanche-ops/src/aws/cfn-templates/ssm_install_subnet_chain.yaml, or create one with 'create-ssm-doc')")
                 .required(false)
                 .value_parser(HashMapStringToStringParser {})
                 .num_args(1),
         )
         .arg(
-            Arg::new("TARGET_NODES")
-                .long("target-nodes")
-                .help("Sets the hash map of node Id to the corresponding EC2 region, and instance Id in JSON format")
+            Arg::new("SSM_DOC_VERSION")
+                .long("ssm-doc-version")
+                .help("Sets the SSM document version to run (e.g., '3', '$LATEST', if empty, runs '$DEFAULT')")
                 .required(false)
-                .value_parser(HashMapStringToRegionInstanceIdParser {})
                 .num_args(1),
         )
         .arg(
-            Arg::new("PROFILE_NAME")
-                .long("profile-name")
-                .help("Sets the AWS credential profile name for API calls/endpoints")
+            Arg::new("SSM_MAX_ATTEMPTS")
+                .long("ssm-max-attempts")
+                .help("Sets the maximum number of times to send an SSM command, re-sending to the instances whose invocation failed transiently (e.g., agent restart, undeliverable) but not on script errors")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u32).range(1..))
+                .default_value("1"),
+        )
+        .arg(
+            Arg::new("REGION_CONCURRENCY")
+                .long("region-concurrency")
+                .visible_alias("parallel-regions")
+                .help("Sets the maximum number of regions to send the SSM commands to at once (1 to process the regions one by one), to avoid the account-wide API throttling with many regions")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u32).range(1..))
+                .default_value("1"),
+        )
+        .arg(
+            Arg::new("MAX_VALIDATORS")
+                .long("max-validators")
+                .help("Sets the maximum number of validating nodes to stake, aborting if the target nodes exceed it (set higher explicitly for large deployments)")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u32).range(1..))
+                .default_value("50"),
+        )
+        .arg(
+            Arg::new("OTEL_ENDPOINT")
+                .long("otel-endpoint")
+                .help("Sets the OTLP (gRPC) collector endpoint to export the OpenTelemetry spans of the run, steps, SSM polls, and validator transactions to (requires the 'otel' cargo feature)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("PRINT_ENV")
+                .long("print-env")
+                .help("Sets to print the resolved configuration and credentials source (secrets redacted), and exit without any change")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("PLAN_OUTPUT")
+                .long("plan-output")
+                .help("Sets the file path to write the install plan to as a Mermaid flowchart (nodes, primary validators, subnet, track, subnet validators, chain, chain config), and exit without any change (dry run)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("PRINT_SSM_ARGS")
+                .long("print-ssm-args")
+                .help("Prints the resolved SSM document parameters and target instance Ids instead of sending SSM commands (for running the document manually)")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("CHECK_PERMISSIONS")
+                .long("check-permissions")
+                .help("Checks that the AWS identity has the S3 and SSM permissions for the run via the IAM policy simulator before any change (requires 'iam:SimulatePrincipalPolicy')")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("VERBOSE_TIMING")
+                .long("verbose-timing")
+                .help("Logs the start and elapsed time of each step at info level (to find which step dominates a slow install)")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("WAIT_FOR_BOOTSTRAP_MINUTES")
+                .long("wait-for-bootstrap-minutes")
+                .help("Sets the minutes to wait for all nodes to bootstrap the new blockchain in the final verification (polled concurrently, reports each node's last known status on timeout, 0 to skip)")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("REGION_FROM_RPC")
+                .long("region-from-rpc")
+                .help("Sets to infer the AWS region from the --chain-rpc-url host (hostname, or the AWS IP ranges) for the target nodes without a region, and to warn on the nodes in other regions")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("TARGET_NODES")
+                .long("target-nodes")
+                .help("Sets the hash map of node Id to the corresponding EC2 region, and instance Id in JSON format (nodes may span multiple regions, each with its --ssm-docs entry, the region may be omitted with --region-from-rpc)")
+                .required(false)
+                .value_parser(TargetNodesParser {})
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("BLS_SIGNER_PER_NODE")
+                .long("bls-signer-per-node")
+                .help("Sets the hash map of node Id to its BLS public key and proof of possession in JSON format (e.g., '{\"NodeID-...\":{\"publicKey\":\"0x...\",\"proofOfPossession\":\"0x...\"}}', if not found, fetched from --node-rpc-urls)")
+                .required(false)
+                .value_parser(HashMapStringToProofOfPossessionParser {})
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("NON_VALIDATING_NODE_IDS")
+                .long("non-validating-node-ids")
+                .help("Sets the comma-separated node Ids that only track the subnet (receive the SSM doc, but not staked as primary network or subnet validators)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("STAKE_UNHEALTHY")
+                .long("stake-unhealthy")
+                .help("Sets to stake the nodes even if they are not healthy or bootstrapped on the primary network (requires --node-rpc-urls to check)")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("CONTINUE_ON_EXISTING_SUBNET_VALIDATOR")
+                .long("continue-on-existing-subnet-validator")
+                .help("Sets to skip (rather than fail on) the nodes that already validate the subnet (e.g., re-runs)")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(bool))
+                .default_value("true"),
+        )
+        .arg(
+            Arg::new("BATCH_VALIDATOR_ADDS")
+                .long("batch-validator-adds")
+                .help("Sets to batch the validator adds into fewer transactions where the protocol allows (falls back to one transaction per node otherwise, which is the case for all P-chain validator transactions for now)")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("PROFILE_NAME")
+                .long("profile-name")
+                .visible_alias("aws-profile")
+                .help("Sets the AWS credential profile name for API calls/endpoints")
                 .required(false)
                 .default_value("default")
                 .num_args(1),
         )
+        .arg(
+            Arg::new("ASSUME_ROLE_ARN")
+                .long("assume-role-arn")
+                .help("Sets the IAM role to assume (with the --profile-name credentials) before any AWS call, all AWS calls then run as the role (the temporary credentials are refreshed before they expire)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("EXTERNAL_ID")
+                .long("external-id")
+                .help("Sets the external Id required by the trust policy of --assume-role-arn")
+                .required(false)
+                .requires("ASSUME_ROLE_ARN")
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("SESSION_NAME")
+                .long("session-name")
+                .help("Sets the role session name of --assume-role-arn, recorded in CloudTrail for the AWS calls (default 'avalancheup-aws-[UNIX_TIMESTAMP]')")
+                .required(false)
+                .requires("ASSUME_ROLE_ARN")
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("SUBNET_ID")
+                .long("subnet-id")
+                .help("Sets the existing subnet Id (created by --exit-after-create-subnet) to skip staking and subnet creation (the VM binary upload and subnet tracking still run), and create the blockchain")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("SUBNET_CONTROL_KEYS")
+                .long("subnet-control-keys")
+                .help("Sets the comma-separated P-chain addresses of the new subnet's control keys, which must include the --key address (defaults to the --key address alone)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("SUBNET_THRESHOLD")
+                .long("subnet-threshold")
+                .help("Sets the number of the new subnet's control keys required to authorize the subnet transactions (sign the others with --subnet-auth-keys)")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u32).range(1..))
+                .default_value("1"),
+        )
+        .arg(
+            Arg::new("EXIT_AFTER_CREATE_SUBNET")
+                .long("exit-after-create-subnet")
+                .help("Sets to exit after the subnet is created and validators added, saving the subnet Id to --output-dir")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("STRICT_ID_PREDICTION")
+                .long("strict-id-prediction")
+                .help("Sets to abort if the created subnet or blockchain Id differs from the dry-run Id (e.g., the wallet UTXOs changed by a concurrent spend), instead of warning")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("NO_CHECK_ACCEPTANCE")
+                .long("no-check-acceptance")
+                .help("Sets to issue the transactions without waiting for each acceptance, then polls all tx Ids at once before verifying the nodes (faster on local test networks, keep unset on real networks)")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("TX_STATUS_POLL_INTERVAL_MS")
+                .long("tx-status-poll-interval-ms")
+                .help("Sets the interval in milliseconds to poll 'platform.getTxStatus' for the acceptance of the issued transactions")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("1000"),
+        )
+        .arg(
+            Arg::new("ACCEPTANCE_TIMEOUT_SECONDS")
+                .long("acceptance-timeout-seconds")
+                .help("Sets the maximum seconds to wait for the acceptance of the issued transactions ('platform.getTxStatus' errors are retried until then)")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("120"),
+        )
+        .arg(
+            Arg::new("EMIT_NODE_CONFIG")
+                .long("emit-node-config")
+                .help("Sets the path to write the avalanchego config snippet (track-subnets, config dirs, aliases) for the created subnet/chain ('.json' for JSON, otherwise YAML)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("POST_INSTALL_HOOK")
+                .long("post-install-hook")
+                .help("Sets the path of the program to run after a successful install, with the results in the environment (AVAX_SUBNET_ID, AVAX_BLOCKCHAIN_ID, AVAX_VM_ID, AVAX_NETWORK_ID, and AVAX_RESULT_JSON)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("FAIL_ON_HOOK_ERROR")
+                .long("fail-on-hook-error")
+                .help("Sets to fail the run if the post-install hook exits non-zero (otherwise, only logs a warning)")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("OVERALL_TIMEOUT_MINUTES")
+                .long("overall-timeout-minutes")
+                .help("Sets the upper bound of the whole run in minutes (0 for unlimited), on timeout the partial output is written to --output-dir")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("0"),
+        )
+}
+
+pub async fn execute(opts: Flags) -> io::Result<()> {
+    avalanche_ops::logger::init(&opts.log_level, &opts.log_file)?;
+
+    let credentials_provider = if !opts.assume_role_arn.is_empty() {
+        Some(
+            assume_role(
+                &opts.s3_region,
+                &opts.profile_name,
+                &opts.assume_role_arn,
+                &opts.external_id,
+                &opts.session_name,
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    // artifacts bucket may be in a different region than the target nodes
+    // (SSM commands are sent with the per-node region configs)
+    let clients = AwsClients::load_with_credentials(
+        &opts.s3_region,
+        &opts.profile_name,
+        Duration::from_secs(opts.s3_upload_timeout),
+        credentials_provider,
+    )
+    .await;
+    execute_with_clients(opts, clients).await
+}
+
+/// Checks the role session name against the "AssumeRole" constraints.
+/// ref. <https://docs.aws.amazon.com/STS/latest/APIReference/API_AssumeRole.html>
+pub fn check_role_session_name(session_name: &str) -> io::Result<()> {
+    if !(2..=64).contains(&session_name.len()) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid --session-name '{session_name}' (2 to 64 characters)"),
+        ));
+    }
+    if let Some(c) = session_name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || "_+=,.@-".contains(*c)))
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid --session-name '{session_name}' (character '{c}', expected alphanumerics or '_+=,.@-')"),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the credentials provider that assumes the IAM role with the profile
+/// credentials, and refreshes the temporary credentials before they expire.
+/// Fails if the role cannot be assumed. The session name is generated if empty.
+pub async fn assume_role(
+    region: &str,
+    profile_name: &str,
+    role_arn: &str,
+    external_id: &str,
+    session_name: &str,
+) -> io::Result<SharedCredentialsProvider> {
+    if !role_arn.starts_with("arn:") || !role_arn.contains(":role/") {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            format!("invalid --assume-role-arn '{role_arn}' (expected 'arn:aws:iam::<account>:role/<name>')"),
+        ));
+    }
+    let session_name = if session_name.is_empty() {
+        format!("avalancheup-aws-{}", unix_now())
+    } else {
+        check_role_session_name(session_name).map_err(validation)?;
+        session_name.to_string()
+    };
+    log::info!("assuming the role '{role_arn}' with the profile '{profile_name}' (session '{session_name}')");
+
+    let shared_config = aws_manager::load_config(
+        Some(region.to_string()),
+        Some(profile_name.to_string()),
+        Some(Duration::from_secs(30)),
+    )
+    .await;
+    let profile_provider = shared_config
+        .credentials_provider()
+        .cloned()
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("no credentials for the profile '{profile_name}' to assume the role with"),
+            )
+        })?;
+    let mut builder = AssumeRoleProvider::builder(role_arn)
+        .session_name(&session_name)
+        .region(aws_sdk_s3::config::Region::new(region.to_string()));
+    if !external_id.is_empty() {
+        builder = builder.external_id(external_id);
+    }
+    let credentials_provider = SharedCredentialsProvider::new(builder.build(profile_provider));
+
+    // the provider assumes the role lazily, so fail here on the trust policy
+    let shared_config = load_aws_config(
+        region,
+        profile_name,
+        Duration::from_secs(30),
+        &Some(credentials_provider.clone()),
+    )
+    .await;
+    let identity = sts::Manager::new(&shared_config)
+        .get_identity()
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::PermissionDenied,
+                format!(
+                    "failed to assume the role '{role_arn}', does its trust policy allow the profile '{profile_name}' (and the external Id)? ({})",
+                    e
+                ),
+            )
+        })?;
+    log::info!(
+        "assumed the role '{role_arn}', effective AWS identity {:?}",
+        identity
+    );
+    Ok(credentials_provider)
+}
+
+/// Loads the AWS config like "aws_manager::load_config", with the credentials
+/// provider if any (e.g., from "assume_role"), so that every client of the run
+/// uses the same credentials without changing the process environment.
+pub async fn load_aws_config(
+    region: &str,
+    profile_name: &str,
+    operation_timeout: Duration,
+    credentials_provider: &Option<SharedCredentialsProvider>,
+) -> SdkConfig {
+    let Some(credentials_provider) = credentials_provider else {
+        return aws_manager::load_config(
+            Some(region.to_string()),
+            Some(profile_name.to_string()),
+            Some(operation_timeout),
+        )
+        .await;
+    };
+    aws_config::from_env()
+        .region(aws_sdk_s3::config::Region::new(region.to_string()))
+        .profile_name(profile_name)
+        .timeout_config(
+            TimeoutConfig::builder()
+                .operation_timeout(operation_timeout)
+                .build(),
+        )
+        .credentials_provider(credentials_provider.clone())
+        .load()
+        .await
+}
+
+/// Same as "execute" with the injected AWS clients (e.g., pointed at a local
+/// AWS emulator in tests), without initializing the logger.
+pub async fn execute_with_clients(opts: Flags, clients: AwsClients) -> io::Result<()> {
+    let ui = Ui {
+        terminal: true,
+        color: avalanche_ops::color::enabled(opts.no_color),
+    };
+    run(opts, clients, ui, true).await.map(|_| ())
+}
+
+/// Installs the subnet and chain for other Rust programs (and tests), returning
+/// the created Ids. Unlike "execute", it does not initialize the logger, prompt,
+/// or write to the terminal (progress goes to the "log" facade), and reuses
+/// the caller's AWS clients.
+pub async fn install(mut opts: Flags, clients: &AwsClients) -> io::Result<InstallOutput> {
+    if !opts.confirm_phrase.is_empty() {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            "confirm_phrase requires the interactive CLI",
+        ));
+    }
+    opts.skip_prompt = true;
+    let ui = Ui {
+        terminal: false,
+        color: false,
+    };
+    run(opts, clients.clone(), ui, false).await
+}
+
+/// AWS clients for the artifacts bucket and the per-region SSM commands,
+/// to be injected via "execute_with_clients" or "install".
+#[derive(Debug, Clone)]
+pub struct AwsClients {
+    pub sts_manager: sts::Manager,
+    pub s3_manager: s3::Manager,
+    /// SSM managers by the region of the target nodes,
+    /// loaded with the profile for regions not in the map.
+    pub ssm_managers: HashMap<String, ssm::Manager>,
+    /// Credentials for the clients loaded during the run (e.g., the SSM
+    /// managers of the other regions), the profile credentials if None.
+    pub credentials_provider: Option<SharedCredentialsProvider>,
+}
+
+impl AwsClients {
+    /// Loads the clients for the artifacts bucket region (the bucket may be in a
+    /// different region than the target nodes).
+    pub async fn load(s3_region: &str, profile_name: &str, s3_upload_timeout: Duration) -> Self {
+        Self::load_with_credentials(s3_region, profile_name, s3_upload_timeout, None).await
+    }
+
+    /// Same as "load" with the credentials provider for every client
+    /// (e.g., from "assume_role").
+    pub async fn load_with_credentials(
+        s3_region: &str,
+        profile_name: &str,
+        s3_upload_timeout: Duration,
+        credentials_provider: Option<SharedCredentialsProvider>,
+    ) -> Self {
+        let s3_shared_config = load_aws_config(
+            s3_region,
+            profile_name,
+            s3_upload_timeout,
+            &credentials_provider,
+        )
+        .await;
+        Self {
+            sts_manager: sts::Manager::new(&s3_shared_config),
+            s3_manager: s3::Manager::new(&s3_shared_config),
+            ssm_managers: HashMap::new(),
+            credentials_provider,
+        }
+    }
+}
+
+/// Ids produced by the install (empty if the run stopped early,
+/// e.g., with "print_env" or "exit_after_create_subnet").
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Default)]
+pub struct InstallOutput {
+    /// Unique Id of the run, tagged on the uploaded S3 objects.
+    pub run_id: String,
+    pub subnet_id: Option<ids::Id>,
+    pub blockchain_id: Option<ids::Id>,
+    pub vm_id: Option<ids::Id>,
+    pub network_id: Option<u32>,
+    pub ssm_command_ids: Vec<String>,
+    /// Wall-clock duration of each completed step, in order.
+    pub step_timings: Vec<StepTiming>,
+    /// Per-node results, sorted by the node Id.
+    pub nodes: Vec<NodeResult>,
+    /// Genesis diff against the baseline, only set with "--dry-run-genesis-diff".
+    pub genesis_diff: Option<serde_json::Value>,
+}
+
+impl InstallOutput {
+    /// Records the SSM commands for the run and for the nodes of their target instances.
+    fn add_ssm_commands(&mut self, sent_commands: deployer::SentCommands) {
+        for (command_id, instance_ids) in sent_commands {
+            for node in self
+                .nodes
+                .iter_mut()
+                .filter(|n| instance_ids.contains(&n.instance_id))
+            {
+                node.ssm_command_ids.push(command_id.clone());
+            }
+            self.ssm_command_ids.push(command_id);
+        }
+    }
+
+    fn node_mut(&mut self, node_id: &str) -> Option<&mut NodeResult> {
+        self.nodes.iter_mut().find(|n| n.node_id == node_id)
+    }
+
+    /// Returns the transactions issued in the run with their labels (the subnet
+    /// and blockchain Ids are the Ids of their creation transactions).
+    fn issued_tx_ids(&self, created_subnet: bool) -> Vec<(String, ids::Id)> {
+        let mut tx_ids = Vec::new();
+        for node in self.nodes.iter() {
+            if let Some(tx_id) = node.primary_validator_tx {
+                tx_ids.push((format!("primary network validator {}", node.node_id), tx_id));
+            }
+        }
+        if let (true, Some(subnet_id)) = (created_subnet, self.subnet_id) {
+            tx_ids.push((String::from("create subnet"), subnet_id));
+        }
+        for node in self.nodes.iter() {
+            if let Some(tx_id) = node.subnet_validator_tx {
+                tx_ids.push((format!("subnet validator {}", node.node_id), tx_id));
+            }
+        }
+        if let Some(blockchain_id) = self.blockchain_id {
+            tx_ids.push((String::from("create chain"), blockchain_id));
+        }
+        tx_ids
+    }
+
+    /// Marks the nodes that are not yet done as installed, once the run succeeds.
+    fn complete_nodes(&mut self) {
+        for node in self.nodes.iter_mut() {
+            if node.status == NodeStatus::Pending {
+                node.status = NodeStatus::Installed;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct NodeResult {
+    pub node_id: String,
+    pub region: String,
+    pub instance_id: String,
+    /// False if the node only tracks the subnet (non-validating).
+    pub validator: bool,
+    /// None if the node was already a primary network validator (or not added in this run).
+    pub primary_validator_tx: Option<ids::Id>,
+    /// None if the node was already a subnet validator (or not added in this run).
+    pub subnet_validator_tx: Option<ids::Id>,
+    pub ssm_command_ids: Vec<String>,
+    pub status: NodeStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeStatus {
+    /// The run stopped before completing the node (e.g., "print_env").
+    Pending,
+    /// The node tracks the subnet (and validates it, if a validator).
+    Installed,
+    /// The node was already a subnet validator, thus skipped.
+    AlreadyValidating,
+}
+
+/// Major steps of the install, in the order of the run.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    PreflightChecks,
+    UploadSubnetConfig,
+    UploadVmBinary,
+    UploadChainConfig,
+    AddPrimaryNetworkValidators,
+    CreateSubnet,
+    TrackSubnet,
+    AddSubnetValidators,
+    CreateBlockchain,
+    UpdateChainConfigAndAlias,
+    VerifyNodes,
+}
+
+impl Step {
+    /// Returns the label for the logs and the timing breakdown.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Step::PreflightChecks => "pre-flight checks",
+            Step::UploadSubnetConfig => "uploading subnet config",
+            Step::UploadVmBinary => "uploading VM binary",
+            Step::UploadChainConfig => "uploading subnet chain config",
+            Step::AddPrimaryNetworkValidators => "adding primary network validators",
+            Step::CreateSubnet => "creating a subnet",
+            Step::TrackSubnet => "sending SSM doc to track the subnet",
+            Step::AddSubnetValidators => "adding subnet validators",
+            Step::CreateBlockchain => "creating a blockchain",
+            Step::UpdateChainConfigAndAlias => "sending SSM docs to update chain config and alias",
+            Step::VerifyNodes => "verifying nodes",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct StepTiming {
+    pub step: Step,
+    pub duration_ms: u64,
+    /// True if the step had nothing to do (e.g., no local file to upload).
+    #[serde(default)]
+    pub skipped: bool,
+}
+
+/// Output of the steps, colored on the terminal for the CLI or logged for the library.
+#[derive(Debug, Clone, Copy)]
+struct Ui {
+    terminal: bool,
+    color: bool,
+}
+
+impl Ui {
+    fn print<T: std::fmt::Display>(&self, color: Color, msg: T) -> io::Result<()> {
+        if self.terminal && self.color {
+            execute!(stdout(), SetForegroundColor(color), Print(msg), ResetColor)?;
+        } else if self.terminal {
+            execute!(stdout(), Print(msg))?;
+        } else {
+            log::info!("{}", msg.to_string().trim());
+        }
+        Ok(())
+    }
+
+    fn print_line(&self, line: &str) {
+        if self.terminal {
+            println!("{line}");
+        } else if !line.is_empty() {
+            log::info!("{line}");
+        }
+    }
+
+    fn theme(&self) -> Box<dyn Theme> {
+        if self.color {
+            Box::<ColorfulTheme>::default()
+        } else {
+            Box::new(SimpleTheme)
+        }
+    }
+}
+
+/// Runs the select prompt on the blocking pool, so that the overall timeout
+/// (and SIGINT) still fire while waiting for the user. Defaults to the first item.
+async fn prompt_select(ui: Ui, prompt: String, items: Vec<String>) -> io::Result<usize> {
+    tokio::task::spawn_blocking(move || {
+        Select::with_theme(ui.theme().as_ref())
+            .with_prompt(prompt)
+            .items(&items)
+            .default(0)
+            .interact()
+    })
+    .await
+    .map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to join the prompt ({})", e),
+        )
+    })?
+}
+
+/// Same as "prompt_select" for the text input (empty allowed).
+async fn prompt_input(ui: Ui, prompt: String) -> io::Result<String> {
+    tokio::task::spawn_blocking(move || {
+        Input::<String>::with_theme(ui.theme().as_ref())
+            .with_prompt(prompt)
+            .allow_empty(true)
+            .interact_text()
+    })
+    .await
+    .map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to join the prompt ({})", e),
+        )
+    })?
+}
+
+/// Runs the install; with "handle_sigint", stops on SIGINT and writes the
+/// output as of the last checkpoint (the library "install" leaves the signals
+/// to the caller).
+async fn run(
+    opts: Flags,
+    clients: AwsClients,
+    ui: Ui,
+    handle_sigint: bool,
+) -> io::Result<InstallOutput> {
+    telemetry::init(&opts.otel_endpoint)?;
+    let ret = run_with_timeout(opts, clients, ui, handle_sigint).await;
+    telemetry::finish(ret.as_ref().err());
+    ret
+}
+
+async fn run_with_timeout(
+    opts: Flags,
+    clients: AwsClients,
+    ui: Ui,
+    handle_sigint: bool,
+) -> io::Result<InstallOutput> {
+    let overall_timeout_minutes = opts.overall_timeout_minutes;
+    let output_dir = opts.output_dir.clone();
+    let progress = Arc::new(Mutex::new(Progress::new(opts.verbose_timing)));
+
+    let overall_timeout = async {
+        if overall_timeout_minutes == 0 {
+            std::future::pending::<()>().await;
+        }
+        sleep(Duration::from_secs(overall_timeout_minutes * 60)).await
+    };
+    let sigint = async {
+        // never fires if disabled or if the handler cannot be installed
+        if !handle_sigint || tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+
+    tokio::select! {
+        ret = execute_steps(opts, clients, ui, progress.clone()) => {
+            // keeps the issued transactions and the sent commands of the failed run
+            if ret.is_err() && progress.lock().unwrap().has_completed_step() {
+                let (last_completed_step, written) = write_partial_output(&progress, &output_dir);
+                log::warn!("'{NAME}' failed (last completed step: {last_completed_step}, {written})");
+            }
+            ret
+        }
+        _ = overall_timeout => {
+            let (last_completed_step, written) = write_partial_output(&progress, &output_dir);
+            Err(Error::new(
+                ErrorKind::TimedOut,
+                format!(
+                    "'{NAME}' timed out after {overall_timeout_minutes} minute(s) (last completed step: {last_completed_step}, {written})"
+                ),
+            ))
+        }
+        _ = sigint => {
+            let (last_completed_step, written) = write_partial_output(&progress, &output_dir);
+            Err(InstallError::Interrupted.error(
+                ErrorKind::Interrupted,
+                format!(
+                    "'{NAME}' interrupted by SIGINT (last completed step: {last_completed_step}, {written})"
+                ),
+            ))
+        }
+    }
+}
+
+/// Writes the output as of the last checkpoint for the unfinished run, and
+/// returns the last completed step with the write result to report.
+fn write_partial_output(progress: &Mutex<Progress>, output_dir: &str) -> (String, String) {
+    let (last_completed_step, partial_output) = {
+        let progress = progress.lock().unwrap();
+        (progress.last_completed_step(), progress.partial_output())
+    };
+    let written = match write_output(output_dir, &partial_output, "partial-output") {
+        Ok(path) => format!("partial output written to '{path}'"),
+        Err(e) => format!("failed to write the partial output ({e})"),
+    };
+    (last_completed_step, written)
+}
+
+/// Writes the output (e.g., the issued transactions and the step timings) to
+/// "{output_dir}/{run_id}-{suffix}.json", and returns the file path: "output"
+/// on success, or "partial-output" for the unfinished run.
+fn write_output(output_dir: &str, output: &InstallOutput, suffix: &str) -> io::Result<String> {
+    fs::create_dir_all(output_dir)?;
+    let path = Path::new(output_dir)
+        .join(format!("{}-{suffix}.json", output.run_id))
+        .display()
+        .to_string();
+    fs::write(&path, serde_json::to_string_pretty(output).unwrap())?;
+    Ok(path)
+}
+
+/// Completed steps with their durations, to report the last completed step
+/// on the failure, overall timeout, or SIGINT, and the timing breakdown on success.
+#[derive(Debug)]
+struct Progress {
+    verbose_timing: bool,
+    step_started_at: Instant,
+    step_skipped: bool,
+    step_timings: Vec<StepTiming>,
+    /// Output as of the last checkpoint, written out if the run fails (after
+    /// the preflight checks), times out, or is interrupted.
+    output: InstallOutput,
+}
+
+impl Progress {
+    fn new(verbose_timing: bool) -> Self {
+        Self {
+            verbose_timing,
+            step_started_at: Instant::now(),
+            step_skipped: false,
+            step_timings: Vec::new(),
+            output: InstallOutput::default(),
+        }
+    }
+
+    /// Returns the output as of the last checkpoint, with the step timings so far.
+    fn partial_output(&self) -> InstallOutput {
+        let mut output = self.output.clone();
+        output.step_timings = self.step_timings.clone();
+        output
+    }
+
+    fn has_completed_step(&self) -> bool {
+        self.step_timings.iter().any(|t| !t.skipped)
+    }
+
+    fn last_completed_step(&self) -> String {
+        self.step_timings
+            .iter()
+            .rev()
+            .find(|t| !t.skipped)
+            .map(|t| t.step.as_str().to_string())
+            .unwrap_or_else(|| String::from("none"))
+    }
+}
+
+/// Marks the start of the step (otherwise, timed from the previous step completion).
+fn start_step(progress: &Arc<Mutex<Progress>>, step: Step) {
+    telemetry::start_step(step.as_str());
+    let mut progress = progress.lock().unwrap();
+    progress.step_started_at = Instant::now();
+    progress.step_skipped = false;
+    if progress.verbose_timing {
+        log::info!("STEP '{}' started", step.as_str());
+    }
+}
+
+/// Marks the current step as skipped, so that it is not reported as completed.
+fn skip_step(progress: &Arc<Mutex<Progress>>, reason: &str) {
+    log::info!("skipping the step ({reason})");
+    progress.lock().unwrap().step_skipped = true;
+}
+
+/// Saves the output so far, to be written out if the run fails, times out,
+/// or is interrupted.
+fn checkpoint(progress: &Arc<Mutex<Progress>>, output: &InstallOutput) {
+    progress.lock().unwrap().output = output.clone();
+}
+
+/// Records the commands sent by the deployer, and checkpoints the output
+/// before returning the failure if any, so that the commands sent before the
+/// failure are in the partial output.
+fn record_ssm_commands(
+    progress: &Arc<Mutex<Progress>>,
+    output: &mut InstallOutput,
+    (sent_commands, ret): (deployer::SentCommands, io::Result<()>),
+) -> io::Result<()> {
+    output.add_ssm_commands(sent_commands);
+    checkpoint(progress, output);
+    ret
+}
+
+/// Records the completed (or skipped) step with the time elapsed since its start,
+/// and checkpoints the output.
+fn complete_step(progress: &Arc<Mutex<Progress>>, output: &InstallOutput, step: Step) {
+    telemetry::end_step();
+    let mut progress = progress.lock().unwrap();
+    let now = Instant::now();
+    let elapsed = now.duration_since(progress.step_started_at);
+    progress.step_started_at = now;
+    let state = if progress.step_skipped {
+        "skipped"
+    } else {
+        "completed"
+    };
+    if progress.verbose_timing {
+        log::info!(
+            "STEP '{}' {state} in {:.1}s",
+            step.as_str(),
+            elapsed.as_secs_f64()
+        );
+    } else {
+        log::debug!(
+            "{state} step '{}' in {:.1}s",
+            step.as_str(),
+            elapsed.as_secs_f64()
+        );
+    }
+    let skipped = progress.step_skipped;
+    progress.step_timings.push(StepTiming {
+        step,
+        duration_ms: elapsed.as_millis() as u64,
+        skipped,
+    });
+    progress.step_skipped = false;
+    progress.output = output.clone();
+}
+
+/// Prints the per-node results, one node per line.
+fn print_node_results(ui: Ui, nodes: &[NodeResult]) -> io::Result<()> {
+    ui.print(Color::Blue, format!("nodes ({}):\n", nodes.len()))?;
+    for node in nodes {
+        ui.print_line(&format_node_result(node));
+    }
+    let regions = format_region_results(nodes);
+    ui.print(Color::Blue, format!("\nregions ({}):\n", regions.len()))?;
+    for region in regions.iter() {
+        ui.print_line(region);
+    }
+    ui.print_line("");
+    Ok(())
+}
+
+/// Formats the node count and the SSM commands of each region (sorted by the
+/// region) as tab-separated lines.
+pub fn format_region_results(nodes: &[NodeResult]) -> Vec<String> {
+    let mut regions: BTreeMap<&str, (usize, BTreeSet<&str>)> = BTreeMap::new();
+    for node in nodes {
+        let (count, command_ids) = regions.entry(node.region.as_str()).or_default();
+        *count += 1;
+        command_ids.extend(node.ssm_command_ids.iter().map(|id| id.as_str()));
+    }
+    regions
+        .into_iter()
+        .map(|(region, (count, command_ids))| {
+            format!(
+                "{region}\t{count} node(s)\tSSM commands {}",
+                if command_ids.is_empty() {
+                    String::from("-")
+                } else {
+                    command_ids.into_iter().collect::<Vec<_>>().join(",")
+                }
+            )
+        })
+        .collect()
+}
+
+/// Formats the node result as a tab-separated line.
+pub fn format_node_result(node: &NodeResult) -> String {
+    let tx = |tx_id: &Option<ids::Id>| {
+        tx_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| String::from("-"))
+    };
+    format!(
+        "{}\t{}\t{}\t{:?}\tprimary tx {}\tsubnet tx {}\tSSM commands {}",
+        node.node_id,
+        node.region,
+        node.instance_id,
+        node.status,
+        tx(&node.primary_validator_tx),
+        if node.validator {
+            tx(&node.subnet_validator_tx)
+        } else {
+            String::from("(non-validating)")
+        },
+        if node.ssm_command_ids.is_empty() {
+            String::from("-")
+        } else {
+            node.ssm_command_ids.join(",")
+        }
+    )
+}
+
+/// Resolved inputs of the install, to render the sequence of steps for review.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct InstallPlan {
+    pub network_name: String,
+    pub target_node_count: usize,
+    pub regions: Vec<String>,
+    pub validator_count: usize,
+    /// Skips staking and subnet creation if set (the nodes still track the subnet).
+    pub existing_subnet_id: Option<String>,
+    pub staking_amount: String,
+    pub primary_network_validate_period_in_days: u64,
+    pub subnet_validate_period_in_days: u64,
+    pub backend: String,
+    pub chain_name: String,
+    pub vm_id: String,
+    pub exit_after_create_subnet: bool,
+    pub chain_config: bool,
+    pub chain_alias: String,
+}
+
+impl InstallPlan {
+    /// Renders the steps that the run will take as a Mermaid flowchart.
+    /// ref. <https://mermaid.js.org/syntax/flowchart.html>
+    pub fn to_mermaid(&self) -> String {
+        // quotes end the Mermaid label
+        let esc = |s: &str| s.replace('"', "#quot;");
+        let via = if self.backend == deployer::BACKEND_ANR {
+            "ANR"
+        } else {
+            "SSM"
+        };
+
+        let mut steps = vec![(
+            "nodes",
+            format!(
+                "{} target node(s) on {}<br/>{} validating, regions {}",
+                self.target_node_count,
+                esc(&self.network_name),
+                self.validator_count,
+                esc(&self.regions.join(", "))
+            ),
+        )];
+        let track = (
+            "track",
+            format!(
+                "track subnet ({via})<br/>{} node(s)",
+                self.target_node_count
+            ),
+        );
+        match &self.existing_subnet_id {
+            Some(subnet_id) => {
+                steps.push((
+                    "subnet",
+                    format!("existing subnet {subnet_id}<br/>skips staking"),
+                ));
+                steps.push(track);
+            }
+            None => {
+                steps.push((
+                    "primary_validators",
+                    format!(
+                        "add {} primary network validator(s)<br/>{}, {} days",
+                        self.validator_count,
+                        esc(&self.staking_amount),
+                        self.primary_network_validate_period_in_days
+                    ),
+                ));
+                steps.push(("subnet", String::from("create subnet")));
+                steps.push(track);
+                steps.push((
+                    "subnet_validators",
+                    format!(
+                        "add {} subnet validator(s)<br/>{} days",
+                        self.validator_count, self.subnet_validate_period_in_days
+                    ),
+                ));
+            }
+        }
+        if !self.exit_after_create_subnet {
+            steps.push((
+                "chain",
+                format!(
+                    "create chain '{}'<br/>VM {}",
+                    esc(&self.chain_name),
+                    self.vm_id
+                ),
+            ));
+            if self.chain_config {
+                steps.push(("chain_config", format!("update chain config ({via})")));
+            }
+            if !self.chain_alias.is_empty() {
+                steps.push((
+                    "chain_alias",
+                    format!("alias chain '{}' ({via})", esc(&self.chain_alias)),
+                ));
+            }
+        }
+
+        let mut lines = vec![String::from("flowchart TD")];
+        for (id, label) in steps.iter() {
+            lines.push(format!("    {id}[\"{label}\"]"));
+        }
+        for pair in steps.windows(2) {
+            lines.push(format!("    {} --> {}", pair[0].0, pair[1].0));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Formats the timing breakdown of the completed steps, with the total.
+pub fn format_step_timings(step_timings: &[StepTiming]) -> String {
+    let width = step_timings
+        .iter()
+        .map(|t| t.step.as_str().len())
+        .max()
+        .unwrap_or(0)
+        .max("total".len());
+    let mut lines = Vec::new();
+    let mut total_ms = 0;
+    for t in step_timings {
+        total_ms += t.duration_ms;
+        lines.push(format!(
+            "{:<width$}  {:>8.1}s{}",
+            t.step.as_str(),
+            t.duration_ms as f64 / 1000.0,
+            if t.skipped { "  (skipped)" } else { "" }
+        ));
+    }
+    lines.push(format!(
+        "{:<width$}  {:>8.1}s",
+        "total",
+        total_ms as f64 / 1000.0
+    ));
+    lines.join("\n")
+}
+
+async fn execute_steps(
+    opts: Flags,
+    clients: AwsClients,
+    ui: Ui,
+    progress: Arc<Mutex<Progress>>,
+) -> io::Result<InstallOutput> {
+    let mut output = InstallOutput {
+        run_id: format!("{}-{:08x}", unix_now(), random_manager::u64() as u32),
+        ..Default::default()
+    };
+    log::info!("run Id '{}'", output.run_id);
+    checkpoint(&progress, &output);
+    start_step(&progress, Step::PreflightChecks);
+
+    let Some(ctx) = preflight_checks(opts, clients, ui, &mut output).await? else {
+        return Ok(output);
+    };
+
+    complete_step(&progress, &output, Step::PreflightChecks);
+    start_step(&progress, Step::UploadSubnetConfig);
+    upload_subnet_config(&ctx, &progress).await?;
+
+    complete_step(&progress, &output, Step::UploadSubnetConfig);
+    start_step(&progress, Step::UploadVmBinary);
+    let vm_binary_s3_key = upload_vm_binary(&ctx, &progress).await?;
+
+    complete_step(&progress, &output, Step::UploadVmBinary);
+    start_step(&progress, Step::UploadChainConfig);
+    upload_chain_config(&ctx, &progress).await?;
+
+    complete_step(&progress, &output, Step::UploadChainConfig);
+    start_step(&progress, Step::AddPrimaryNetworkValidators);
+    add_primary_network_validators(&ctx, &progress, &mut output).await?;
+
+    complete_step(&progress, &output, Step::AddPrimaryNetworkValidators);
+    start_step(&progress, Step::CreateSubnet);
+    let created_subnet_id = create_subnet(&ctx, &progress, &mut output).await?;
+
+    complete_step(&progress, &output, Step::CreateSubnet);
+    start_step(&progress, Step::TrackSubnet);
+    track_subnet(
+        &ctx,
+        &progress,
+        &mut output,
+        created_subnet_id,
+        &vm_binary_s3_key,
+    )
+    .await?;
+
+    complete_step(&progress, &output, Step::TrackSubnet);
+    start_step(&progress, Step::AddSubnetValidators);
+    add_subnet_validators(&ctx, &progress, &mut output, created_subnet_id).await?;
+
+    complete_step(&progress, &output, Step::AddSubnetValidators);
+    start_step(&progress, Step::CreateBlockchain);
+    if ctx.opts.exit_after_create_subnet {
+        save_created_subnet(&ctx, &mut output, created_subnet_id).await?;
+        return Ok(output);
+    }
+    let blockchain_id = create_blockchain(&ctx, &progress, &mut output, created_subnet_id).await?;
+
+    complete_step(&progress, &output, Step::CreateBlockchain);
+    start_step(&progress, Step::UpdateChainConfigAndAlias);
+    let registered_alias_node_ids =
+        update_chain_config_and_alias(&ctx, &progress, &mut output, blockchain_id).await?;
+
+    complete_step(&progress, &output, Step::UpdateChainConfigAndAlias);
+    start_step(&progress, Step::VerifyNodes);
+    verify_nodes(&ctx, &mut output, blockchain_id).await?;
+
+    complete_step(&progress, &output, Step::VerifyNodes);
+
+    let InstallContext {
+        opts, target_nodes, ..
+    } = &ctx;
+    if !opts.emit_node_config.is_empty() {
+        let node_config = build_node_config(
+            &created_subnet_id,
+            &blockchain_id,
+            &opts.subnet_config_remote_dir,
+            &opts.chain_config_remote_dir,
+            &opts.chain_config_remote_filename,
+            &opts.chain_aliases_remote_path,
+            &opts.chain_alias,
+        );
+        write_node_config(&node_config, &opts.emit_node_config)?;
+        log::info!(
+            "wrote avalanchego config snippet to '{}'",
+            opts.emit_node_config
+        );
+    }
+
+    ui.print_line("");
+    ui.print(Color::Blue, format!(
+            "\n\n\nSUCCESS!\nsubnet Id: {created_subnet_id}\nblockchain Id: {blockchain_id}\nblockchain alias: {}\n\n",
+            format_chain_alias_status(
+                &opts.chain_alias,
+                &opts.chain_aliases_remote_path,
+                registered_alias_node_ids.len(),
+                target_nodes.len()
+            )
+        ))?;
+
+    output.complete_nodes();
+    print_node_results(ui, &output.nodes)?;
+
+    output.step_timings = progress.lock().unwrap().step_timings.clone();
+    ui.print(
+        Color::Blue,
+        format!(
+            "timing breakdown:\n{}\n\n",
+            format_step_timings(&output.step_timings)
+        ),
+    )?;
+    let output_path = write_output(&opts.output_dir, &output, "output")?;
+    ui.print(
+        Color::Blue,
+        format!("output (with the step timings) written to '{output_path}'\n\n"),
+    )?;
+
+    if !opts.post_install_hook.is_empty() {
+        ui.print(
+            Color::Green,
+            format!(
+                "\n\n\nSTEP: running the post-install hook '{}'\n\n",
+                opts.post_install_hook
+            ),
+        )?;
+        run_post_install_hook(&opts.post_install_hook, &output, opts.fail_on_hook_error).await?;
+    }
+
+    Ok(output)
+}
+
+/// The targets, the wallet and the parameters resolved by the pre-flight checks,
+/// shared by the install steps.
+struct InstallContext {
+    opts: Flags,
+    ui: Ui,
+    clients: AwsClients,
+    target_nodes: HashMap<String, avalanche_ops::aws::spec::RegionMachineId>,
+    region_to_instance_ids: HashMap<String, Vec<String>>,
+    /// Validating node ids (disjoint from the non-validating node ids).
+    all_node_ids: Vec<String>,
+    parsed_node_ids: HashMap<String, ids::node::Id>,
+    node_id_to_pop: HashMap<ids::node::Id, key::bls::ProofOfPossession>,
+    api_paths: ApiPaths,
+    rpc_headers: Vec<(String, String)>,
+    p_chain_rpc_url: String,
+    network_id: u32,
+    priv_key: key::secp256k1::private_key::Key,
+    wallet_to_spend: wallet::Wallet<key::secp256k1::private_key::Key>,
+    staking_asset: StakingAsset,
+    /// None to create a new subnet.
+    existing_subnet_id: Option<ids::Id>,
+    /// The owners of a new subnet with their P-chain addresses.
+    new_subnet_owners: Option<(key::secp256k1::txs::OutputOwners, Vec<String>)>,
+    subnet_validation_end_at: Option<u64>,
+    chain_genesis_bytes: Vec<u8>,
+    vm_id: ids::Id,
+    s3_tags: Vec<(String, String)>,
+    deployer: Box<dyn deployer::Deployer>,
+    tx_status_poller: TxStatusPoller,
+    /// None to issue the transactions without waiting ("--no-check-acceptance").
+    acceptance_poller: Option<TxStatusPoller>,
+}
+
+/// Resolves the target nodes, validates the flags, loads the wallet, and checks
+/// the nodes, the balance and the AWS resources, before any change.
+/// Returns None to exit without any change (e.g., a dry run, or not confirmed).
+async fn preflight_checks(
+    mut opts: Flags,
+    clients: AwsClients,
+    ui: Ui,
+    output: &mut InstallOutput,
+) -> io::Result<Option<InstallContext>> {
+    opts.s3_key_prefix = normalize_s3_key_prefix(&opts.s3_key_prefix).map_err(validation)?;
+    log::info!("normalized S3 key prefix '{}'", opts.s3_key_prefix);
+
+    let mut spec_node_pops = Vec::new();
+    let mut region_to_ssm_doc = HashMap::new();
+    let mut target_nodes = HashMap::new();
+    if !opts.spec_file_path.is_empty() {
+        let spec = avalanche_ops::aws::spec::Spec::load(&opts.spec_file_path).map_err(|e| {
+            Error::new(
+                e.kind(),
+                format!("failed to load spec '{}' ({e})", opts.spec_file_path),
+            )
+        })?;
+        spec.validate().map_err(validation)?;
+
+        for (region, regional_resource) in spec.resource.regional_resources.iter() {
+            let ssm_doc = regional_resource
+                .cloudformation_ssm_install_subnet_chain
+                .clone()
+                .ok_or_else(|| {
+                    InstallError::Validation.error(
+                        ErrorKind::InvalidInput,
+                        format!("spec has no install subnet chain SSM document in the region '{region}' (run 'apply' first)"),
+                    )
+                })?;
+            region_to_ssm_doc.insert(region.to_string(), ssm_doc);
+        }
+
+        if let Some(created_nodes) = &spec.resource.created_nodes {
+            for node in created_nodes {
+                spec_node_pops.push((node.node_id.clone(), node.proof_of_possession.clone()));
+                target_nodes.insert(
+                    node.node_id.clone(),
+                    avalanche_ops::aws::spec::RegionMachineId {
+                        region: node.region.clone(),
+                        machine_id: node.machine_id.clone(),
+                    },
+                );
+            }
+        }
+    } else {
+        target_nodes = opts.target_nodes.clone();
+        region_to_ssm_doc = opts.ssm_docs.clone();
+    }
+
+    if opts.region_from_rpc {
+        let inferred_region = infer_region_from_rpc(&opts.chain_rpc_url).await?;
+        log::info!(
+            "inferred region '{inferred_region}' from RPC '{}'",
+            opts.chain_rpc_url
+        );
+        for (node_id, region_machine_id) in target_nodes.iter_mut() {
+            if region_machine_id.region.is_empty() {
+                region_machine_id.region = inferred_region.clone();
+            } else if region_machine_id.region != inferred_region {
+                log::warn!(
+                    "node '{node_id}' is in the region '{}', but the RPC '{}' is in the region '{inferred_region}'",
+                    region_machine_id.region,
+                    opts.chain_rpc_url
+                );
+            }
+        }
+    }
+    let mut missing_region_node_ids: Vec<&String> = target_nodes
+        .iter()
+        .filter(|(_, region_machine_id)| region_machine_id.region.is_empty())
+        .map(|(node_id, _)| node_id)
+        .collect();
+    if !missing_region_node_ids.is_empty() {
+        missing_region_node_ids.sort();
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            format!(
+                "target nodes {:?} have no region (set the region, or --region-from-rpc)",
+                missing_region_node_ids
+            ),
+        ));
+    }
+
+    // validate all node ids in one pass before any work, rather than failing mid-run
+    let parsed_node_ids = parse_node_ids(
+        target_nodes
+            .keys()
+            .map(|node_id| ("target nodes", node_id.as_str()))
+            .chain(
+                opts.bls_signer_per_node
+                    .keys()
+                    .map(|node_id| ("--bls-signer-per-node", node_id.as_str())),
+            )
+            .chain(
+                opts.node_rpc_urls
+                    .keys()
+                    .map(|node_id| ("--node-rpc-urls", node_id.as_str())),
+            )
+            .chain(
+                opts.non_validating_node_ids
+                    .iter()
+                    .map(|node_id| ("--non-validating-node-ids", node_id.as_str())),
+            )
+            .chain(
+                opts.allowed_nodes
+                    .iter()
+                    .map(|node_id| ("--allowed-nodes", node_id.as_str())),
+            ),
+    )
+    .map_err(validation)?;
+
+    let non_validating_node_ids: HashSet<String> =
+        opts.non_validating_node_ids.iter().cloned().collect();
+    for node_id in non_validating_node_ids.iter() {
+        if !target_nodes.contains_key(node_id) {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                format!("non-validating node id '{node_id}' not found in target nodes"),
+            ));
+        }
+    }
+
+    let mut node_id_to_pop = HashMap::new();
+    for (node_id, pop) in spec_node_pops {
+        node_id_to_pop.insert(parsed_node_ids[&node_id], pop);
+    }
+    // post-Durango, primary network validators must register the BLS signer
+    for (node_id, pop) in opts.bls_signer_per_node.iter() {
+        node_id_to_pop.insert(parsed_node_ids[node_id], pop.clone());
+    }
+    // the wallet and avalanche-types clients take the base URL with the path prefix
+    let api_paths = ApiPaths::new(
+        &opts.p_chain_api_path,
+        &opts.info_api_path,
+        &opts.evm_api_path,
+    )
+    .map_err(validation)?;
+    if api_paths != ApiPaths::default() {
+        log::info!("using API paths {:?}", api_paths);
+    }
+    for (node_id, node_rpc_url) in opts.node_rpc_urls.iter() {
+        let parsed_node_id = parsed_node_ids[node_id];
+        // non-validating nodes are not staked, thus need no BLS signer
+        if node_id_to_pop.contains_key(&parsed_node_id) || non_validating_node_ids.contains(node_id)
+        {
+            continue;
+        }
+        if let Some(pop) =
+            fetch_node_pop(&parsed_node_id, &api_paths.base_url(node_rpc_url)).await?
+        {
+            log::info!("fetched BLS proof of possession for '{node_id}' from '{node_rpc_url}'");
+            node_id_to_pop.insert(parsed_node_id, pop);
+        }
+    }
+
+    check_local_inputs(&mut opts)?;
+    let s3_tags = s3_object_tags(&opts.s3_object_tags, &output.run_id, &opts.chain_name)
+        .map_err(validation)?;
+
+    // two-phase deployments: the first run exits after the subnet is created,
+    // and the second run with the subnet Id creates the blockchain
+    let existing_subnet_id = if opts.subnet_id.is_empty() {
+        None
+    } else {
+        if !opts.subnet_control_keys.is_empty() || opts.subnet_threshold != 1 {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                "--subnet-control-keys and --subnet-threshold only apply to a new subnet, not to --subnet-id",
+            ));
+        }
+        if opts.exit_after_create_subnet {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                "--subnet-id and --exit-after-create-subnet are mutually exclusive",
+            ));
+        }
+        Some(ids::Id::from_str(&opts.subnet_id)?)
+    };
+    if !opts.chain_alias.is_empty() {
+        validate_chain_alias(&opts.chain_alias).map_err(validation)?;
+        if opts.chain_aliases_remote_path.is_empty() {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                "chain_alias not empty but chain_aliases_remote_path empty",
+            ));
+        }
+    }
+
+    let chain_genesis_bytes = if opts.generate_subnet_evm_genesis {
+        if !opts.chain_genesis_path.is_empty() {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                "--chain-genesis-path and --generate-subnet-evm-genesis are mutually exclusive",
+            ));
+        }
+        let genesis = generate_subnet_evm_genesis(
+            opts.subnet_evm_chain_id,
+            opts.subnet_evm_gas_limit,
+            &opts.subnet_evm_fee_config,
+            &opts.subnet_evm_allocs,
+        )
+        .map_err(validation)?;
+
+        fs::create_dir_all(&opts.output_dir)?;
+        let genesis_path = Path::new(&opts.output_dir).join(format!(
+            "{}-subnet-evm-genesis.json",
+            opts.chain_name.replace(' ', "-")
+        ));
+        let genesis_path = genesis_path.display().to_string();
+        genesis.sync(&genesis_path)?;
+
+        ui.print(
+            Color::Blue,
+            format!("\nSaved generated genesis to '{genesis_path}'\n"),
+        )?;
+        opts.chain_genesis_path = genesis_path;
+
+        genesis
+            .encode_json()
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!("failed to encode generated genesis ({})", e),
+                )
+            })?
+            .into_bytes()
+    } else {
+        if opts.chain_genesis_path.is_empty() {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                "--chain-genesis-path is required unless --generate-subnet-evm-genesis is set",
+            ));
+        }
+        read_genesis_file(&opts.chain_genesis_path, "chain genesis").map_err(validation)?
+    };
+
+    let vm_id = if opts.vm_id.is_empty() {
+        derive_vm_id(
+            &opts.vm_id_derivation,
+            &opts.chain_name,
+            &opts.vm_binary_local_path,
+        )
+        .map_err(validation)?
+    } else {
+        ids::Id::from_str(&opts.vm_id).map_err(validation)?
+    };
+    let vm_id_source = if opts.vm_id.is_empty() {
+        format!("derived with '{}'", opts.vm_id_derivation)
+    } else {
+        String::from("--vm-id")
+    };
+    log::info!("VM ID is {} ({vm_id_source})", vm_id.to_string());
+    output.vm_id = Some(vm_id);
+    if opts.verify_vm_id_in_binary {
+        if opts.vm_binary_local_path.is_empty() {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                "verify_vm_id_in_binary requires vm_binary_local_path to inspect",
+            ));
+        }
+        vm_binary::verify_embedded_id(
+            &opts.vm_binary_local_path,
+            &vm_id.to_string(),
+            &vm_id.to_vec(),
+        )
+        .map_err(validation)?;
+    }
+
+    let rpc_headers = parse_rpc_headers(&opts.query_rpc_headers).map_err(validation)?;
+    if !rpc_headers.is_empty() {
+        log::info!(
+            "using RPC headers {:?}",
+            opts.query_rpc_headers
+                .iter()
+                .map(|h| redact_rpc_header(h))
+                .collect::<Vec<String>>()
+        );
+        // the wallet and avalanche-types RPC clients do not support custom headers,
+        // so fail before the deployment rather than at the wallet calls
+        if !opts.dry_run_genesis_diff {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                "--query-rpc-header only applies to --dry-run-genesis-diff, as the wallet that queries the balance and issues the transactions cannot send the headers",
+            ));
+        }
+    }
+
+    if opts.dry_run_genesis_diff {
+        let baseline_genesis_bytes = match (
+            opts.baseline_genesis_path.is_empty(),
+            opts.baseline_blockchain_id.is_empty(),
+        ) {
+            (false, true) => read_genesis_file(&opts.baseline_genesis_path, "baseline genesis")?,
+            (true, false) => {
+                let blockchain_id = ids::Id::from_str(&opts.baseline_blockchain_id)?;
+                let p_chain_rpc_url = if opts.p_chain_rpc_url.is_empty() {
+                    &opts.chain_rpc_url
+                } else {
+                    &opts.p_chain_rpc_url
+                };
+                fetch_genesis_data(p_chain_rpc_url, &blockchain_id, &rpc_headers, &api_paths)
+                    .await?
+            }
+            _ => {
+                return Err(InstallError::Validation.error(
+                    ErrorKind::InvalidInput,
+                    "--dry-run-genesis-diff requires exactly one of --baseline-genesis-path and --baseline-blockchain-id",
+                ));
+            }
+        };
+        let diff = diff_genesis(
+            &parse_genesis_json(&baseline_genesis_bytes, "baseline genesis")?,
+            &parse_genesis_json(&chain_genesis_bytes, "chain genesis")?,
+        );
+        let changed = diff.as_object().map(|m| m.len()).unwrap_or_default();
+        ui.print(
+            Color::Blue,
+            format!("\n\n\nGENESIS DIFF ({changed} changed section(s), no change made)\n\n"),
+        )?;
+        ui.print_line(&serde_json::to_string_pretty(&diff).unwrap());
+        output.genesis_diff = Some(diff);
+        return Ok(None);
+    }
+
+    let network_id = fetch_network_id(&opts.chain_rpc_url, &rpc_headers, &api_paths).await?;
+    let network_name = crate::networks::display_name(network_id);
+    telemetry::set_run_attribute("network_id", network_id.to_string());
+    output.network_id = Some(network_id);
+
+    let priv_key = load_private_key(&opts.key).map_err(validation)?;
+    let mut subnet_auth_keys = Vec::new();
+    for (i, k) in opts.subnet_auth_keys.iter().enumerate() {
+        // never leak the key itself in the error
+        subnet_auth_keys.push(load_private_key(k).map_err(|e| {
+            validation(Error::new(
+                e.kind(),
+                format!("--subnet-auth-keys entry {i}: {e}"),
+            ))
+        })?);
+    }
+    // scrubs the hex keys before "opts" is cloned into the deployer and SSM tasks
+    opts.key = Zeroizing::default();
+    opts.subnet_auth_keys.clear();
+    let p_chain_rpc_url = if opts.p_chain_rpc_url.is_empty() {
+        opts.chain_rpc_url.clone()
+    } else {
+        opts.p_chain_rpc_url.clone()
+    };
+    log::info!("using '{p_chain_rpc_url}' for the wallet and P-chain queries");
+    if p_chain_rpc_url != opts.chain_rpc_url {
+        let p_chain_network_id =
+            fetch_network_id(&p_chain_rpc_url, &rpc_headers, &api_paths).await?;
+        if p_chain_network_id != network_id {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                format!("P-chain RPC '{p_chain_rpc_url}' network Id {p_chain_network_id} does not match chain RPC network Id {network_id}"),
+            ));
+        }
+    }
+    let tx_submit_rpc_url = if opts.tx_submit_rpc_url.is_empty() {
+        p_chain_rpc_url.clone()
+    } else {
+        opts.tx_submit_rpc_url.clone()
+    };
+    // a separate query wallet keeps the balance queries off the submission endpoint
+    let query_wallet = if tx_submit_rpc_url != p_chain_rpc_url {
+        let tx_submit_network_id =
+            fetch_network_id(&tx_submit_rpc_url, &rpc_headers, &api_paths).await?;
+        if tx_submit_network_id != network_id {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                format!("transaction submit RPC '{tx_submit_rpc_url}' network Id {tx_submit_network_id} does not match chain RPC network Id {network_id}"),
+            ));
+        }
+        log::info!("using '{tx_submit_rpc_url}' to issue P-chain transactions");
+        Some(build_wallet(&api_paths.base_url(&p_chain_rpc_url), &priv_key).await?)
+    } else {
+        None
+    };
+    let mut wallet_to_spend =
+        build_wallet(&api_paths.base_url(&tx_submit_rpc_url), &priv_key).await?;
+
+    let subnet_validation_end_at = if opts.subnet_validation_end_at.is_empty() {
+        None
+    } else {
+        let end_at = parse_timestamp(&opts.subnet_validation_end_at).map_err(validation)?;
+        validate_subnet_validation_end_at(
+            end_at,
+            unix_now(),
+            opts.primary_network_validate_period_in_days,
+            network_id,
+        )
+        .map_err(validation)?;
+        log::info!(
+            "all subnet validations will end at {} (overrides the subnet validate period in days)",
+            format_timestamp(end_at)
+        );
+        Some(end_at)
+    };
+
+    // the wallet stakes with the network's staking asset, so the custom asset must match
+    let staking_asset = StakingAsset::new(
+        &opts.staking_asset_id,
+        &opts.stake_asset_symbol,
+        opts.staking_decimals,
+    )
+    .map_err(validation)?;
+    if let Some(asset_id) = &staking_asset.asset_id {
+        if *asset_id != wallet_to_spend.avax_asset_id {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                format!(
+                    "staking asset '{asset_id}' does not match the network's staking asset '{}'",
+                    wallet_to_spend.avax_asset_id
+                ),
+            ));
+        }
+    }
+    let (unit, base_unit) = (staking_asset.symbol(), staking_asset.base_symbol());
+    if staking_asset.to_base_units(opts.staking_amount_in_avax)
+        > primitive_types::U256::from(u64::MAX)
+    {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            format!(
+                "staking amount {} {unit} overflows with {} decimals",
+                opts.staking_amount_in_avax, opts.staking_decimals
+            ),
+        ));
+    }
+
+    let balance_wallet = query_wallet.as_ref().unwrap_or(&wallet_to_spend);
+    let save_utxo_snapshot_path = if !opts.save_balance_utxo_snapshot.is_empty() {
+        opts.save_balance_utxo_snapshot.clone()
+    } else if opts.refresh_balance_utxos {
+        opts.balance_utxo_snapshot.clone()
+    } else {
+        String::new()
+    };
+    let p_chain_balance = if !opts.balance_utxo_snapshot.is_empty() && !opts.refresh_balance_utxos {
+        let snapshot = UtxoSnapshot::load(&opts.balance_utxo_snapshot)?;
+        snapshot
+            .check(network_id, &balance_wallet.p_address, unix_now())
+            .map_err(validation)?;
+        let (balance, utxos) =
+            p_chain_balance_from_utxos(&snapshot.utxos, &balance_wallet.avax_asset_id, unix_now())?;
+        log::info!(
+            "computed P-chain balance {balance} from {utxos} UTXO(s) of the snapshot '{}'",
+            opts.balance_utxo_snapshot
+        );
+        balance
+    } else if !save_utxo_snapshot_path.is_empty() {
+        let utxos = fetch_p_chain_utxos(
+            &p_chain_rpc_url,
+            &balance_wallet.p_address,
+            &rpc_headers,
+            &api_paths,
+        )
+        .await?;
+        let (balance, _) =
+            p_chain_balance_from_utxos(&utxos, &balance_wallet.avax_asset_id, unix_now())?;
+        UtxoSnapshot {
+            network_id,
+            p_address: balance_wallet.p_address.clone(),
+            fetched_at: unix_now(),
+            utxos,
+        }
+        .sync(&save_utxo_snapshot_path)?;
+        ui.print(
+            Color::Blue,
+            format!("\nSaved P-chain UTXO snapshot to '{save_utxo_snapshot_path}'\n"),
+        )?;
+        balance
+    } else {
+        fetch_p_chain_balance(&p_chain_rpc_url, balance_wallet, &rpc_headers, &api_paths).await?
+    };
+    let p_chain_address = priv_key
+        .to_public_key()
+        .to_hrp_address(network_id, "P")
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to derive the P-chain address ({})", e),
+            )
+        })?;
+    log::info!(
+        "loaded wallet '{p_chain_address}', fetched its P-chain balance {} {unit} ({p_chain_balance} {base_unit}, network id {network_id}, {network_name})",
+        staking_asset.to_display_units(primitive_types::U256::from(p_chain_balance))
+    );
+
+    // informational only, to prevent accidental subnet sprawl from repeated runs with the same key
+    let owned_subnet_ids =
+        fetch_owned_subnet_ids(&api_paths.base_url(&p_chain_rpc_url), &priv_key).await?;
+    if let Some(subnet_id) = &existing_subnet_id {
+        if !owned_subnet_ids.contains(subnet_id) {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                format!("subnet '{subnet_id}' is not owned by '{p_chain_address}'"),
+            ));
+        }
+        log::info!("resuming with the existing subnet '{subnet_id}' (skipping staking and subnet creation)");
+    } else if !owned_subnet_ids.is_empty() {
+        log::warn!(
+            "'{p_chain_address}' already owns {} subnet(s) {:?}",
+            owned_subnet_ids.len(),
+            owned_subnet_ids
+        );
+        if !opts.skip_prompt {
+            let selected = prompt_select(
+                ui,
+                format!(
+                    "'{p_chain_address}' already owns {} subnet(s), do you really want to create a new subnet?",
+                    owned_subnet_ids.len()
+                ),
+                vec![
+                    String::from("No, exit!"),
+                    String::from("Yes, create a new subnet"),
+                ],
+            )
+            .await?;
+            if selected == 0 {
+                return Ok(None);
+            }
+        }
+    } else {
+        log::info!("'{p_chain_address}' does not own any subnet yet");
+    }
+
+    // the owners of a new subnet, sorted in the canonical order
+    let new_subnet_owners = if existing_subnet_id.is_none() {
+        let control_keys = if opts.subnet_control_keys.is_empty() {
+            parse_control_keys(std::slice::from_ref(&p_chain_address), network_id)?
+        } else {
+            parse_control_keys(&opts.subnet_control_keys, network_id).map_err(validation)?
+        };
+        let short_addresses: Vec<ids::short::Id> =
+            control_keys.iter().map(|(_, a)| a.clone()).collect();
+        let owners =
+            build_output_owners(&short_addresses, opts.subnet_threshold).map_err(validation)?;
+        let addresses: Vec<String> = owners
+            .addresses
+            .iter()
+            .map(|a| control_keys.iter().find(|(_, k)| k == a).unwrap().0.clone())
+            .collect();
+        Some((owners, addresses))
+    } else {
+        None
+    };
+
+    // fail before any on-chain operation if the signers do not meet the subnet threshold
+    let mut signer_addresses = vec![p_chain_address.clone()];
+    for k in subnet_auth_keys.iter() {
+        signer_addresses.push(
+            k.to_public_key()
+                .to_hrp_address(network_id, "P")
+                .map_err(|e| {
+                    Error::new(
+                        ErrorKind::Other,
+                        format!("failed to derive the --subnet-auth-keys address ({})", e),
+                    )
+                })?,
+        );
+    }
+    let (subnet_label, control_keys, threshold) = if let Some(subnet_id) = &existing_subnet_id {
+        // ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetsubnets>
+        let (control_keys, threshold) = parse_subnet_owners(
+            &call_json_rpc(
+                &p_chain_rpc_url,
+                &api_paths.p_chain,
+                "platform.getSubnets",
+                serde_json::json!({ "ids": [subnet_id.to_string()] }),
+                &rpc_headers,
+            )
+            .await?,
+            subnet_id,
+        )?;
+        (format!("subnet '{subnet_id}'"), control_keys, threshold)
+    } else {
+        let (owners, addresses) = new_subnet_owners.as_ref().unwrap();
+        (
+            String::from("new subnet"),
+            addresses.clone(),
+            owners.threshold,
+        )
+    };
+    let sig_indices =
+        check_subnet_auth(&control_keys, threshold, &signer_addresses).map_err(validation)?;
+    log::info!(
+        "{subnet_label} control keys {:?} (threshold {threshold}), signing with {:?} (signature indices {:?})",
+        control_keys,
+        signer_addresses,
+        sig_indices
+    );
+
+    // the wallet signs the subnet auth with the keychain keys that match the subnet owners
+    if !subnet_auth_keys.is_empty() {
+        let mut keys = vec![priv_key.clone()];
+        keys.append(&mut subnet_auth_keys);
+        wallet_to_spend.keychain = key::secp256k1::keychain::Keychain::new(keys);
+    }
+
+    // validating node ids (disjoint from the non-validating node ids)
+    let mut all_node_ids = Vec::new();
+    let mut region_to_instance_ids: HashMap<String, Vec<String>> = HashMap::new();
+    for (node_id, region_machine_id) in target_nodes.iter() {
+        log::info!(
+            "will send SSM doc to the node '{node_id}' of '{}' in the region '{}'",
+            region_machine_id.machine_id,
+            region_machine_id.region,
+        );
+        if non_validating_node_ids.contains(node_id) {
+            log::info!("node '{node_id}' will only track the subnet (non-validating)");
+        } else {
+            all_node_ids.push(node_id.clone());
+        }
+
+        if let Some(instance_ids) = region_to_instance_ids.get_mut(&region_machine_id.region) {
+            instance_ids.push(region_machine_id.machine_id.clone());
+        } else {
+            region_to_instance_ids.insert(
+                region_machine_id.region.clone(),
+                vec![region_machine_id.machine_id.clone()],
+            );
+        }
+    }
+
+    check_max_validators(all_node_ids.len(), opts.max_validators).map_err(validation)?;
+
+    let mut sorted_node_ids: Vec<&String> = target_nodes.keys().collect();
+    sorted_node_ids.sort();
+    for node_id in sorted_node_ids {
+        let region_machine_id = &target_nodes[node_id];
+        output.nodes.push(NodeResult {
+            node_id: node_id.clone(),
+            region: region_machine_id.region.clone(),
+            instance_id: region_machine_id.machine_id.clone(),
+            validator: !non_validating_node_ids.contains(node_id),
+            primary_validator_tx: None,
+            subnet_validator_tx: None,
+            ssm_command_ids: Vec::new(),
+            status: NodeStatus::Pending,
+        });
+    }
+
+    // fail before any on-chain operation if a node cannot be reached by the backend
+    let mut deployer = deployer::new_deployer(
+        &opts,
+        ui,
+        clients.ssm_managers.clone(),
+        clients.credentials_provider.clone(),
+        region_to_ssm_doc.clone(),
+    )?;
+    log::info!("preparing the '{}' backend", deployer.name());
+    deployer.prepare(&region_to_instance_ids).await?;
+
+    if existing_subnet_id.is_none() {
+        // nodes that are not healthy nor bootstrapped risk low uptime (thus reward loss) right after staking
+        ui.print_line("");
+        ui.print(
+            Color::Green,
+            "\n\n\nSTEP: checking node health and bootstrap status before staking\n\n",
+        )?;
+        let mut unhealthy_node_ids = Vec::new();
+        for node_id in all_node_ids.iter() {
+            let node_rpc_url = if let Some(u) = opts.node_rpc_urls.get(node_id) {
+                u
+            } else {
+                log::warn!(
+                    "no RPC URL for node '{node_id}' in --node-rpc-urls, skipping pre-stake check"
+                );
+                continue;
+            };
+
+            let problems = check_node_ready(node_rpc_url, &rpc_headers, &api_paths).await;
+            if problems.is_empty() {
+                log::info!("node '{node_id}' is healthy and bootstrapped (via '{node_rpc_url}')");
+                continue;
+            }
+            log::warn!(
+                "node '{node_id}' is not ready for staking (via '{node_rpc_url}'): {}",
+                problems.join(", ")
+            );
+            unhealthy_node_ids.push(node_id.clone());
+        }
+        if !unhealthy_node_ids.is_empty() {
+            if opts.stake_unhealthy {
+                log::warn!(
+                    "--stake-unhealthy set, staking unhealthy nodes {:?}",
+                    unhealthy_node_ids
+                );
+            } else {
+                all_node_ids.retain(|node_id| !unhealthy_node_ids.contains(node_id));
+                ui.print(Color::Yellow, format!(
+                        "\nskipping staking for unhealthy nodes (use --stake-unhealthy to override): {:?}\n",
+                        unhealthy_node_ids
+                    ))?;
+            }
+        }
+    }
+
+    // if all nodes need to be staked (none, if resuming with the existing subnet)
+    ui.print_line("");
+    let staked_node_count = if existing_subnet_id.is_some() {
+        0
+    } else {
+        all_node_ids.len()
+    };
+    let estimated_required_avax = staking_asset
+        .to_base_units(opts.staking_amount_in_avax)
+        .checked_mul(primitive_types::U256::from(staked_node_count))
+        .ok_or_else(|| {
+            InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                format!("staking amount for {staked_node_count} node(s) overflows"),
+            )
+        })?;
+    log::info!(
+        "required {unit} to validate all nodes {estimated_required_avax} {base_unit} ({} {unit})",
+        staking_asset.to_display_units(estimated_required_avax)
+    );
+    let min_balance_reserve = staking_asset.to_base_units(opts.min_balance_reserve_avax);
+    if opts.min_balance_reserve_avax > 0 {
+        let required_with_reserve = estimated_required_avax
+            .checked_add(min_balance_reserve)
+            .ok_or_else(|| {
+                InstallError::Validation.error(
+                    ErrorKind::InvalidInput,
+                    "staking amount with the balance reserve overflows",
+                )
+            })?;
+        log::info!(
+            "required {unit} to validate all nodes and keep the reserve {required_with_reserve} {base_unit} ({} {unit})",
+            staking_asset.to_display_units(required_with_reserve)
+        );
+        if primitive_types::U256::from(p_chain_balance) < required_with_reserve {
+            return Err(InstallError::InsufficientBalance.error(
+                ErrorKind::InvalidInput,
+                format!(
+                    "'{p_chain_address}' only has {p_chain_balance} {base_unit}, validating all nodes would leave less than the minimum balance reserve {} {unit} (needs {required_with_reserve} {base_unit})",
+                    opts.min_balance_reserve_avax
+                ),
+            ));
+        }
+    }
+    if primitive_types::U256::from(p_chain_balance) < estimated_required_avax {
+        log::warn!("'{p_chain_address}' only has {p_chain_balance}, not enough to validate all nodes (needs {estimated_required_avax} {base_unit})");
+        // no one to confirm (e.g., "--skip-prompt" or the library API), so fail before any transaction
+        if opts.skip_prompt {
+            return Err(InstallError::InsufficientBalance.error(
+                ErrorKind::InvalidInput,
+                format!(
+                    "'{p_chain_address}' only has {p_chain_balance} {base_unit}, not enough to validate all nodes (needs {estimated_required_avax} {base_unit})"
+                ),
+            ));
+        }
+        let selected = prompt_select(
+            ui,
+            String::from("Should we still proceed?"),
+            vec![String::from("Yes...?"), String::from("No!!!")],
+        )
+        .await?;
+        if selected == 1 {
+            return Ok(None);
+        }
+    }
+
+    ui.print_line("");
+    ui.print(Color::Green, format!(
+            "\nInstalling subnet with network Id '{network_id}' ({network_name}), chain rpc url '{}', S3 bucket '{}', S3 key prefix '{}', S3 upload timeout '{}', subnet config local '{}', subnet config remote dir '{}', VM binary local '{}', VM binary remote dir '{}', VM Id '{}', chain name '{}', chain config local '{}', chain config remote dir '{}', chain genesis file '{}', primary network validate period in days '{}', subnet validate period in days '{}', staking amount in avax '{}', node ids to instance ids '{:?}', non-validating node ids '{:?}'\n",
+            opts.chain_rpc_url,
+            opts.s3_bucket,
+            opts.s3_key_prefix,
+            opts.s3_upload_timeout,
+            opts.subnet_config_local_path,
+            opts.subnet_config_remote_dir,
+            opts.vm_binary_local_path,
+            opts.vm_binary_remote_dir,
+            vm_id,
+            opts.chain_name,
+            opts.chain_config_local_path,
+            opts.chain_config_remote_dir,
+            opts.chain_genesis_path,
+            opts.primary_network_validate_period_in_days,
+            opts.subnet_validate_period_in_days,
+            opts.staking_amount_in_avax,
+            target_nodes,
+            non_validating_node_ids,
+        ))?;
+
+    let sts_manager = &clients.sts_manager;
+    let s3_manager = &clients.s3_manager;
+
+    let current_identity = sts_manager.get_identity().await.map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to get the current AWS identity ({})", e),
+        )
+    })?;
+    log::info!("current AWS identity: {:?}", current_identity);
+
+    if opts.check_permissions {
+        ui.print(
+            Color::Green,
+            "\n\n\nSTEP: checking AWS permissions via the IAM policy simulator\n\n",
+        )?;
+        let required = required_permissions(
+            &current_identity.role_arn,
+            &current_identity.account_id,
+            &opts.s3_bucket,
+            &opts.s3_key_prefix,
+            // the ANR backend does not send SSM commands
+            &if opts.backend == deployer::BACKEND_EC2 {
+                region_to_instance_ids.clone()
+            } else {
+                HashMap::new()
+            },
+            &region_to_ssm_doc,
+            !opts.skip_avalanchego_config_check,
+            !opts.s3_object_tags.is_empty(),
+        );
+        check_permissions(
+            &opts.s3_region,
+            &opts.profile_name,
+            &clients.credentials_provider,
+            &current_identity.role_arn,
+            &required,
+        )
+        .await?;
+    }
+
+    // SSM command outputs are written to the same bucket, so "output_s3_region" must match
+    let bucket_region = fetch_bucket_region(s3_manager, &opts.s3_bucket).await?;
+    if bucket_region != opts.s3_region {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            format!(
+                "S3 bucket '{}' is in the region '{bucket_region}', but --s3-region is '{}'",
+                opts.s3_bucket, opts.s3_region
+            ),
+        ));
+    }
+
+    let ssm_doc_version = if opts.ssm_doc_version.is_empty() {
+        None
+    } else {
+        Some(opts.ssm_doc_version.clone())
+    };
+    log::info!(
+        "resolved SSM document version '{}'",
+        ssm_doc_version.as_deref().unwrap_or("$DEFAULT")
+    );
+
+    if opts.print_env {
+        let resolved = ResolvedEnv {
+            network_id,
+            network_name: network_name.clone(),
+            chain_rpc_url: opts.chain_rpc_url.clone(),
+            p_chain_rpc_url: p_chain_rpc_url.clone(),
+            tx_submit_rpc_url: tx_submit_rpc_url.clone(),
+            s3_region: opts.s3_region.clone(),
+            s3_bucket: opts.s3_bucket.clone(),
+            s3_key_prefix: opts.s3_key_prefix.clone(),
+            ssm_docs: region_to_ssm_doc.clone(),
+            ssm_doc_version: ssm_doc_version
+                .clone()
+                .unwrap_or_else(|| String::from("$DEFAULT")),
+            aws_profile_name: opts.profile_name.clone(),
+            aws_identity: current_identity.clone(),
+            key_source: String::from("hex (--key, redacted)"),
+            p_chain_address: p_chain_address.clone(),
+            vm_id: vm_id.to_string(),
+            vm_id_source: vm_id_source.clone(),
+        };
+        ui.print(Color::Blue, "\n\n\nRESOLVED ENVIRONMENT\n\n")?;
+        ui.print_line(&serde_yaml::to_string(&resolved).unwrap());
+        return Ok(None);
+    }
+
+    if !opts.plan_output.is_empty() {
+        let mut regions: Vec<String> = region_to_instance_ids.keys().cloned().collect();
+        regions.sort();
+        let plan = InstallPlan {
+            network_name: network_name.clone(),
+            target_node_count: target_nodes.len(),
+            regions,
+            validator_count: all_node_ids.len(),
+            existing_subnet_id: existing_subnet_id.as_ref().map(|id| id.to_string()),
+            staking_amount: format!("{} {unit}", opts.staking_amount_in_avax),
+            primary_network_validate_period_in_days: opts.primary_network_validate_period_in_days,
+            subnet_validate_period_in_days: opts.subnet_validate_period_in_days,
+            backend: opts.backend.clone(),
+            chain_name: opts.chain_name.clone(),
+            vm_id: vm_id.to_string(),
+            exit_after_create_subnet: opts.exit_after_create_subnet,
+            chain_config: !opts.chain_config_local_path.is_empty(),
+            chain_alias: opts.chain_alias.clone(),
+        };
+        fs::write(&opts.plan_output, plan.to_mermaid())?;
+        ui.print(
+            Color::Blue,
+            format!(
+                "\n\n\nwrote the install plan to '{}' (dry run, exiting without any change)\n\n",
+                opts.plan_output
+            ),
+        )?;
+        return Ok(None);
+    }
+
+    if !opts.skip_prompt {
+        ui.print_line("");
+        ui.print_line("");
+        let options = &[
+            format!(
+                "No, I am not ready to install a subnet on {network_name} with the wallet {p_chain_address} of balance {} {unit}, {} validator(s), staking amount {} {unit}, minimum balance reserve {} {unit}, primary network staking {} days, subnet staking {} days",
+                    staking_asset.to_display_units(primitive_types::U256::from(p_chain_balance)),
+                    all_node_ids.len(),
+                    opts.staking_amount_in_avax,
+                    opts.min_balance_reserve_avax,
+                    opts.primary_network_validate_period_in_days,
+                    opts.subnet_validate_period_in_days,
+            ),
+            format!(
+                "Yes, let's install a subnet on {network_name} with the wallet {p_chain_address} of balance {} {unit}, {} validator(s), staking amount {} {unit}, minimum balance reserve {} {unit}, primary network staking {} days, subnet staking {} days",
+                    staking_asset.to_display_units(primitive_types::U256::from(p_chain_balance)),
+                    all_node_ids.len(),
+                    opts.staking_amount_in_avax,
+                    opts.min_balance_reserve_avax,
+                    opts.primary_network_validate_period_in_days,
+                    opts.subnet_validate_period_in_days,
+                ),
+        ];
+        let selected = prompt_select(
+            ui,
+            String::from("Select your 'install-subnet-chain' option"),
+            options.to_vec(),
+        )
+        .await?;
+        if selected == 0 {
+            return Ok(None);
+        }
+
+        // deliberate friction for irreversible, funds-spending operations
+        if !opts.confirm_phrase.is_empty() {
+            let typed = prompt_input(
+                ui,
+                format!(
+                    "Type '{}' to confirm the install on {network_name}",
+                    opts.confirm_phrase
+                ),
+            )
+            .await?;
+            if typed.trim() != opts.confirm_phrase {
+                ui.print(Color::Red, "\nconfirm phrase mismatch, aborting\n")?;
+                return Ok(None);
+            }
+        }
+    }
+
+    let tx_status_poller = TxStatusPoller {
+        p_chain_rpc_url: p_chain_rpc_url.clone(),
+        rpc_headers: rpc_headers.clone(),
+        api_paths: api_paths.clone(),
+        interval: Duration::from_millis(opts.tx_status_poll_interval_ms),
+        acceptance_timeout: Duration::from_secs(opts.acceptance_timeout_seconds),
+    };
+    // none to issue the transactions without waiting ("--no-check-acceptance")
+    let acceptance_poller = if opts.no_check_acceptance {
+        None
+    } else {
+        Some(tx_status_poller.clone())
+    };
+
+    Ok(Some(InstallContext {
+        opts,
+        ui,
+        clients,
+        target_nodes,
+        region_to_instance_ids,
+        all_node_ids,
+        parsed_node_ids,
+        node_id_to_pop,
+        api_paths,
+        rpc_headers,
+        p_chain_rpc_url,
+        network_id,
+        priv_key,
+        wallet_to_spend,
+        staking_asset,
+        existing_subnet_id,
+        new_subnet_owners,
+        subnet_validation_end_at,
+        chain_genesis_bytes,
+        vm_id,
+        s3_tags,
+        deployer,
+        tx_status_poller,
+        acceptance_poller,
+    }))
+}
+
+/// Validates the flags and the local files, and merges the subnet config overrides
+/// into the subnet config saved in the output directory.
+fn check_local_inputs(opts: &mut Flags) -> io::Result<()> {
+    if opts.tx_status_poll_interval_ms == 0 {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            "tx_status_poll_interval_ms must be greater than 0",
+        ));
+    }
+    if opts.acceptance_timeout_seconds == 0 {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            "acceptance_timeout_seconds must be greater than 0",
+        ));
+    }
+    if opts.skip_vm_binary_upload && !opts.vm_binary_s3_key.is_empty() {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            "skip_vm_binary_upload and vm_binary_s3_key are mutually exclusive",
+        ));
+    }
+    if opts.vm_binary_local_path.is_empty() {
+        if !opts.skip_vm_binary_upload && opts.vm_binary_s3_key.is_empty() {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                "vm_binary_local_path empty (required unless --skip-vm-binary-upload or --vm-binary-s3-key)",
+            ));
+        }
+        if !opts.expected_vm_version.is_empty() {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                "expected_vm_version requires vm_binary_local_path to verify",
+            ));
+        }
+    } else if !Path::new(&opts.vm_binary_local_path).exists() {
+        return Err(file_not_found_error(
+            "vm binary",
+            &opts.vm_binary_local_path,
+        ));
+    }
+    if opts.post_install_hook.is_empty() {
+        if opts.fail_on_hook_error {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                "--fail-on-hook-error requires --post-install-hook",
+            ));
+        }
+    } else if !Path::new(&opts.post_install_hook).exists() {
+        return Err(file_not_found_error(
+            "post-install hook",
+            &opts.post_install_hook,
+        ));
+    }
+    check_avalanchego_config_remote_path(&opts.avalanchego_config_remote_path)
+        .map_err(validation)?;
+    if opts.vm_binary_sig.is_empty() != opts.vm_binary_pubkey.is_empty() {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            "vm_binary_sig and vm_binary_pubkey must be set together",
+        ));
+    }
+    if !opts.vm_binary_local_path.is_empty() {
+        vm_binary::verify(
+            &opts.vm_binary_local_path,
+            &opts.vm_binary_sha256,
+            &opts.vm_binary_sig,
+            &opts.vm_binary_pubkey,
+        )
+        .map_err(validation)?;
+    } else if !opts.vm_binary_sha256.is_empty() || !opts.vm_binary_sig.is_empty() {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            "vm_binary_sha256 and vm_binary_sig require vm_binary_local_path to verify",
+        ));
+    }
+    if !opts.expected_vm_version.is_empty() {
+        verify_vm_version(&opts.vm_binary_local_path, &opts.expected_vm_version)
+            .map_err(validation)?;
+    }
+
+    if !opts.subnet_config_local_path.is_empty() && opts.subnet_config_remote_dir.is_empty() {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            "subnet_config_local_path not empty but subnet_config_remote_dir empty",
+        ));
+    }
+    if !opts.chain_config_local_path.is_empty() && opts.chain_config_remote_dir.is_empty() {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            "chain_config_local_path not empty but chain_config_remote_dir empty",
+        ));
+    }
+    check_remote_dir("--subnet-config-remote-dir", &opts.subnet_config_remote_dir)
+        .map_err(validation)?;
+    check_remote_dir("--chain-config-remote-dir", &opts.chain_config_remote_dir)
+        .map_err(validation)?;
+    check_remote_filename(
+        "--chain-config-remote-filename",
+        &opts.chain_config_remote_filename,
+    )
+    .map_err(validation)?;
+    if !opts.allowed_nodes.is_empty() && !opts.validator_only {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            "--allowed-nodes is only valid with --validator-only",
+        ));
+    }
+    // private subnet, only the validators (and the allowed nodes) can connect
+    let private_subnet_overrides = if opts.validator_only {
+        let mut overrides = serde_json::json!({ "validatorOnly": true });
+        if !opts.allowed_nodes.is_empty() {
+            overrides["allowedNodes"] = serde_json::json!(opts.allowed_nodes);
+        }
+        Some(overrides.to_string())
+    } else {
+        None
+    };
+
+    if !opts.subnet_config_overrides.is_empty() || private_subnet_overrides.is_some() {
+        if opts.subnet_config_remote_dir.is_empty() {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                "subnet config overrides set but subnet_config_remote_dir empty",
+            ));
+        }
+
+        // keep the file stem, since it is used for the S3 key
+        let (base, file_name) = if opts.subnet_config_local_path.is_empty() {
+            log::info!("no subnet config local file, generating from the default subnet config");
+            let d = subnet::config::Config::default()
+                .encode_json()
+                .map_err(|e| {
+                    Error::new(
+                        ErrorKind::Other,
+                        format!("failed encode_json default subnet config ({})", e),
+                    )
+                })?;
+            (d, String::from("subnet-config.json"))
+        } else {
+            let file_name = Path::new(&opts.subnet_config_local_path)
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string();
+            (
+                fs::read_to_string(&opts.subnet_config_local_path)?,
+                file_name,
+            )
+        };
+        fs::create_dir_all(&opts.output_dir)?;
+        let merged_path = Path::new(&opts.output_dir).join(file_name);
+        if merged_path == Path::new(&opts.subnet_config_local_path) {
+            return Err(InstallError::Validation.error(
+                ErrorKind::InvalidInput,
+                "merged subnet config would overwrite the base subnet config (use a different --output-dir)",
+            ));
+        }
+        let merged_path = merged_path.display().to_string();
+
+        let mut merged = base;
+        if !opts.subnet_config_overrides.is_empty() {
+            merged =
+                merge_json_overrides(&merged, &opts.subnet_config_overrides).map_err(validation)?;
+        }
+        if let Some(overrides) = &private_subnet_overrides {
+            merged = merge_json_overrides(&merged, overrides).map_err(validation)?;
+        }
+        fs::write(&merged_path, merged)?;
+        log::info!("merged subnet config overrides, saved to '{merged_path}'");
+        opts.subnet_config_local_path = merged_path;
+    }
+
+    // the chain config schema and checks are of subnet-evm, which reads the
+    // JSON "config.json", so other VM config formats (e.g., "vm.toml") are not checked
+    let subnet_evm_chain_config = !opts.chain_config_local_path.is_empty()
+        && opts.chain_config_remote_filename.ends_with(".json");
+    if !opts.chain_config_local_path.is_empty() && !subnet_evm_chain_config {
+        log::info!(
+            "skipping subnet-evm chain config checks for the non-JSON '{}'",
+            opts.chain_config_remote_filename
+        );
+    }
+    if opts.skip_config_validation {
+        log::warn!("skipping subnet and chain config validation");
+    } else {
+        if !opts.subnet_config_local_path.is_empty() {
+            config_schema::validate_file(
+                &opts.subnet_config_local_path,
+                "subnet config",
+                config_schema::SUBNET_CONFIG_SCHEMA,
+            )
+            .map_err(validation)?;
+        }
+        if subnet_evm_chain_config {
+            config_schema::validate_file(
+                &opts.chain_config_local_path,
+                "chain config",
+                config_schema::CHAIN_CONFIG_SCHEMA,
+            )
+            .map_err(validation)?;
+        }
+    }
+    if subnet_evm_chain_config {
+        if opts.skip_chain_config_validation {
+            log::warn!("skipping subnet-evm chain config consistency checks");
+        } else {
+            config_schema::check_subnet_evm_chain_config_file(&opts.chain_config_local_path)
+                .map_err(validation)?;
+        }
+    }
+
+    if !opts.vm_id.is_empty() && opts.vm_id_derivation != VM_ID_DERIVATION_NAME {
+        return Err(InstallError::Validation.error(
+            ErrorKind::InvalidInput,
+            "--vm-id and --vm-id-derivation are mutually exclusive",
+        ));
+    }
+    validate_chain_name(
+        &opts.chain_name,
+        opts.vm_id.is_empty() && opts.vm_id_derivation == VM_ID_DERIVATION_NAME,
+    )
+    .map_err(validation)?;
+
+    Ok(())
+}
+
+/// Uploads the subnet config local file to S3, if any.
+async fn upload_subnet_config(
+    ctx: &InstallContext,
+    progress: &Arc<Mutex<Progress>>,
+) -> io::Result<()> {
+    let InstallContext { opts, s3_tags, .. } = ctx;
+    let ui = ctx.ui;
+    let s3_manager = &ctx.clients.s3_manager;
+
+    if !opts.subnet_config_local_path.is_empty() {
+        ui.print(
+            Color::Green,
+            "\n\n\nSTEP: uploading subnet config local file to S3\n\n",
+        )?;
+
+        if !Path::new(&opts.subnet_config_local_path).exists() {
+            return Err(file_not_found_error(
+                "subnet config",
+                &opts.subnet_config_local_path,
+            ));
+        }
+
+        let file_stem = Path::new(&opts.subnet_config_local_path)
+            .file_stem()
+            .unwrap();
+        let subnet_config_s3_key = format!(
+            "{}{}",
+            s3::append_slash(&opts.s3_key_prefix),
+            file_stem.to_str().unwrap()
+        );
+
+        upload_s3_object(
+            s3_manager,
+            &opts.subnet_config_local_path,
+            &opts.s3_bucket,
+            &subnet_config_s3_key,
+            s3_tags,
+            !opts.s3_object_tags.is_empty(),
+        )
+        .await?;
+    } else {
+        skip_step(progress, "no subnet config");
+    }
+
+    Ok(())
+}
+
+/// Uploads the VM binary local file to S3, unless the nodes download an existing one.
+/// Returns the S3 key of the VM binary, empty to skip downloading it on the nodes.
+async fn upload_vm_binary(
+    ctx: &InstallContext,
+    progress: &Arc<Mutex<Progress>>,
+) -> io::Result<String> {
+    let InstallContext { opts, s3_tags, .. } = ctx;
+    let ui = ctx.ui;
+    let vm_id = ctx.vm_id;
+    let s3_manager = &ctx.clients.s3_manager;
+
+    // empty to skip downloading the VM binary on the nodes
+    let vm_binary_s3_key = if opts.skip_vm_binary_upload {
+        String::new()
+    } else if !opts.vm_binary_s3_key.is_empty() {
+        opts.vm_binary_s3_key.clone()
+    } else {
+        format!("{}{}", s3::append_slash(&opts.s3_key_prefix), vm_id)
+    };
+    if opts.skip_vm_binary_upload {
+        log::info!(
+            "skipping VM binary upload, expecting '{}{vm_id}' on the nodes",
+            s3::append_slash(&opts.vm_binary_remote_dir)
+        );
+        skip_step(progress, "--skip-vm-binary-upload");
+    } else if opts.vm_binary_local_path.is_empty() {
+        // no local file, so the nodes download the VM binary uploaded by a prior run
+        let size = check_s3_object(s3_manager, &opts.s3_bucket, &vm_binary_s3_key).await?;
+        log::info!(
+            "reusing existing VM binary 's3://{}/{vm_binary_s3_key}' ({size} bytes)",
+            opts.s3_bucket
+        );
+    } else {
+        ui.print(
+            Color::Green,
+            "\n\n\nSTEP: uploading VM binary local file to S3\n\n",
+        )?;
+        upload_s3_object(
+            s3_manager,
+            &opts.vm_binary_local_path,
+            &opts.s3_bucket,
+            &vm_binary_s3_key,
+            s3_tags,
+            !opts.s3_object_tags.is_empty(),
+        )
+        .await?;
+    }
+
+    Ok(vm_binary_s3_key)
+}
+
+/// Uploads the subnet chain config local file to S3, if any.
+async fn upload_chain_config(
+    ctx: &InstallContext,
+    progress: &Arc<Mutex<Progress>>,
+) -> io::Result<()> {
+    let InstallContext { opts, s3_tags, .. } = ctx;
+    let ui = ctx.ui;
+    let s3_manager = &ctx.clients.s3_manager;
+
+    if !opts.chain_config_local_path.is_empty() {
+        ui.print(
+            Color::Green,
+            "\n\n\nSTEP: uploading subnet chain config local file to S3\n\n",
+        )?;
+
+        if !Path::new(&opts.chain_config_local_path).exists() {
+            return Err(file_not_found_error(
+                "subnet chain config",
+                &opts.chain_config_local_path,
+            ));
+        }
+
+        let file_stem = Path::new(&opts.chain_config_local_path)
+            .file_stem()
+            .unwrap();
+        let chain_config_s3_key = format!(
+            "{}{}",
+            s3::append_slash(&opts.s3_key_prefix),
+            file_stem.to_str().unwrap()
+        );
+
+        upload_s3_object(
+            s3_manager,
+            &opts.chain_config_local_path,
+            &opts.s3_bucket,
+            &chain_config_s3_key,
+            s3_tags,
+            !opts.s3_object_tags.is_empty(),
+        )
+        .await?;
+    } else {
+        skip_step(progress, "no chain config");
+    }
+
+    Ok(())
+}
+
+/// Adds all validating nodes as primary network validators, unless resuming with the existing subnet.
+async fn add_primary_network_validators(
+    ctx: &InstallContext,
+    progress: &Arc<Mutex<Progress>>,
+    output: &mut InstallOutput,
+) -> io::Result<()> {
+    let InstallContext {
+        opts,
+        target_nodes,
+        all_node_ids,
+        parsed_node_ids,
+        node_id_to_pop,
+        wallet_to_spend,
+        staking_asset,
+        acceptance_poller,
+        ..
+    } = ctx;
+    let ui = ctx.ui;
+    let existing_subnet_id = ctx.existing_subnet_id;
+
+    if existing_subnet_id.is_none() {
+        ui.print(Color::Green, format!(
+                "\n\n\nSTEP: adding all nodes as primary network validators if not yet (period {})\n\n",
+                opts.primary_network_validate_period_in_days
+            ))?;
+        if opts.batch_validator_adds {
+            warn_unbatched_validator_adds(ui, "primary network validator", all_node_ids.len())?;
+        }
+        let stake_amount_in_navax = staking_asset
+            .to_base_units(opts.staking_amount_in_avax)
+            .as_u64();
+
+        let mut handles = Vec::new();
+        for (i, (node_id_str, region_machine_id)) in target_nodes
+            .iter()
+            .filter(|(node_id, _)| all_node_ids.contains(*node_id))
+            .enumerate()
+        {
+            // randomly wait to prevent UTXO double spends from the same wallet
+            let random_wait = Duration::from_secs(1 + (i + 1) as u64)
+                .checked_add(Duration::from_millis(500 + random_manager::u64() % 100))
+                .unwrap();
+
+            log::info!(
+                "spawning add_primary_network_permissionless_validator/add_primary_network_validator on '{}' (of EC2 instance '{}', staking period in days '{}')",
+                node_id_str,
+                region_machine_id.machine_id,
+                opts.primary_network_validate_period_in_days,
+            );
+            let node_id = parsed_node_ids[node_id_str];
+            let handle = if let Some(pop) = node_id_to_pop.get(&node_id) {
+                tokio::spawn(add_primary_network_permissionless_validator(
+                    Arc::new(random_wait),
+                    Arc::new(wallet_to_spend.clone()),
+                    Arc::new(node_id),
+                    Arc::new(pop.clone()),
+                    Arc::new(stake_amount_in_navax),
+                    Arc::new(opts.primary_network_validate_period_in_days),
+                    Arc::new(acceptance_poller.clone()),
+                ))
+            } else {
+                tokio::spawn(add_primary_network_validator(
+                    Arc::new(random_wait),
+                    Arc::new(wallet_to_spend.clone()),
+                    Arc::new(node_id),
+                    Arc::new(stake_amount_in_navax),
+                    Arc::new(opts.primary_network_validate_period_in_days),
+                    Arc::new(acceptance_poller.clone()),
+                ))
+            };
+            handles.push((node_id_str.clone(), handle));
+        }
+        log::info!("STEP: blocking on add_validator handles via JoinHandle");
+        for (node_id, handle) in handles {
+            let (tx_id, added) = handle.await.map_err(|e| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!("failed await on add_validator JoinHandle {}", e),
+                )
+            })??;
+            if added {
+                if let Some(node) = output.node_mut(&node_id) {
+                    node.primary_validator_tx = Some(tx_id);
+                }
+            }
+            checkpoint(progress, output);
+        }
+    } else {
+        skip_step(progress, "existing subnet");
+    }
+
+    Ok(())
+}
+
+/// Creates a new subnet with the wallet, or resumes with the existing subnet.
+/// Returns the subnet Id.
+async fn create_subnet(
+    ctx: &InstallContext,
+    progress: &Arc<Mutex<Progress>>,
+    output: &mut InstallOutput,
+) -> io::Result<ids::Id> {
+    let InstallContext {
+        opts,
+        api_paths,
+        p_chain_rpc_url,
+        priv_key,
+        wallet_to_spend,
+        new_subnet_owners,
+        acceptance_poller,
+        ..
+    } = ctx;
+    let ui = ctx.ui;
+    let existing_subnet_id = ctx.existing_subnet_id;
+
+    let created_subnet_id = if let Some(subnet_id) = existing_subnet_id {
+        log::info!("skipping creating a subnet, using the existing subnet '{subnet_id}'");
+        skip_step(progress, "existing subnet");
+        subnet_id
+    } else {
+        ui.print(Color::Green, "\n\n\nSTEP: creating a subnet\n\n")?;
+        let (owners, _) = new_subnet_owners.as_ref().unwrap();
+        let subnet_id = wallet_to_spend
+            .p()
+            .create_subnet()
+            .control_keys(owners.addresses.clone())
+            .threshold(owners.threshold)
+            .dry_mode(true)
+            .issue()
+            .await
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!("failed create_subnet in dry mode ({})", e),
+                )
+            })?;
+        log::info!("[dry mode] subnet Id '{}'", subnet_id);
+
+        // snapshot right before issuing, to reconcile on acceptance errors
+        let known_subnet_ids =
+            fetch_owned_subnet_ids(&api_paths.base_url(p_chain_rpc_url), priv_key).await?;
+        let created_subnet_id = match wallet_to_spend
+            .p()
+            .create_subnet()
+            .control_keys(owners.addresses.clone())
+            .threshold(owners.threshold)
+            .issue()
+            .await
+        {
+            Ok(subnet_id) => subnet_id,
+            Err(e) => {
+                log::warn!(
+                    "failed create_subnet ({e}), reconciling with the P-chain before failing"
+                );
+                match reconcile_created_subnet(
+                    &api_paths.base_url(p_chain_rpc_url),
+                    priv_key,
+                    &known_subnet_ids,
+                )
+                .await?
+                {
+                    Some(subnet_id) => {
+                        log::info!(
+                            "subnet '{subnet_id}' was created despite the error, continuing"
+                        );
+                        subnet_id
+                    }
+                    None => {
+                        return Err(Error::new(
+                            ErrorKind::Other,
+                            format!(
+                                "failed create_subnet ({e}), no new subnet found on the P-chain"
+                            ),
+                        ));
+                    }
+                }
+            }
+        };
+        if let Some(poller) = acceptance_poller {
+            poller.wait("create subnet", &created_subnet_id).await?;
+        }
+        log::info!("created subnet '{}' (still need track)", created_subnet_id);
+        if let Some(warning) = check_id_prediction(
+            "subnet",
+            &subnet_id,
+            &created_subnet_id,
+            opts.strict_id_prediction,
+        )? {
+            ui.print(Color::Yellow, format!("\n\nWARNING: {warning}\n\n"))?;
+        }
+
+        sleep(Duration::from_secs(10)).await;
+
+        created_subnet_id
+    };
+
+    output.subnet_id = Some(created_subnet_id);
+    checkpoint(progress, output);
+    telemetry::set_run_attribute("subnet_id", created_subnet_id.to_string());
+
+    Ok(created_subnet_id)
+}
+
+/// Sends the SSM doc to download the VM binary, track the subnet, and update the subnet config.
+async fn track_subnet(
+    ctx: &InstallContext,
+    progress: &Arc<Mutex<Progress>>,
+    output: &mut InstallOutput,
+    created_subnet_id: ids::Id,
+    vm_binary_s3_key: &str,
+) -> io::Result<()> {
+    let InstallContext {
+        opts,
+        region_to_instance_ids,
+        deployer,
+        ..
+    } = ctx;
+    let ui = ctx.ui;
+    let vm_id = ctx.vm_id;
+
+    ui.print(
+        Color::Green,
+        "\n\n\nSTEP: send SSM doc to download Vm binary, track subnet Id, update subnet config\n\n",
+    )?;
+    if opts.track_subnets_mode == "replace" {
+        log::warn!("nodes will replace their existing track-subnets with '{created_subnet_id}'");
+    }
+    let subcmd = install_subnet_args(
+        &opts.s3_region,
+        &opts.s3_bucket,
+        vm_binary_s3_key,
+        &format!("{}{}", s3::append_slash(&opts.vm_binary_remote_dir), vm_id),
+        &created_subnet_id.to_string(),
+        &opts.avalanchego_config_remote_path,
+        &opts.track_subnets_mode,
+    );
+    let avalanched_args = if !opts.subnet_config_local_path.is_empty() {
+        let file_stem = Path::new(&opts.subnet_config_local_path)
+            .file_stem()
+            .unwrap();
+        let subnet_config_s3_key = format!(
+            "{}{}",
+            s3::append_slash(&opts.s3_key_prefix),
+            file_stem.to_str().unwrap()
+        );
+
+        // If a subnet id is 2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt,
+        // the config file for this subnet is located at {subnet-config-dir}/2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt.json.
+        format!("{subcmd} --subnet-config-s3-key {subnet_config_s3_key} --subnet-config-local-path {subnet_config_local_path}",
+            subnet_config_s3_key = subnet_config_s3_key,
+            subnet_config_local_path = format!("{}{}.json", s3::append_slash(&opts.subnet_config_remote_dir), created_subnet_id),
+        )
+    } else {
+        subcmd
+    };
+
+    record_ssm_commands(
+        progress,
+        output,
+        deployer.run(region_to_instance_ids, &avalanched_args).await,
+    )?;
+
+    Ok(())
+}
+
+/// Adds all validating nodes as subnet validators, unless resuming with the existing subnet.
+async fn add_subnet_validators(
+    ctx: &InstallContext,
+    progress: &Arc<Mutex<Progress>>,
+    output: &mut InstallOutput,
+    created_subnet_id: ids::Id,
+) -> io::Result<()> {
+    let InstallContext {
+        opts,
+        all_node_ids,
+        api_paths,
+        rpc_headers,
+        p_chain_rpc_url,
+        wallet_to_spend,
+        acceptance_poller,
+        ..
+    } = ctx;
+    let ui = ctx.ui;
+    let network_id = ctx.network_id;
+    let existing_subnet_id = ctx.existing_subnet_id;
+    let subnet_validation_end_at = ctx.subnet_validation_end_at;
+
+    if existing_subnet_id.is_none() {
+        ui.print(
+            Color::Green,
+            "\n\n\nSTEP: adding all nodes as subnet validators\n\n",
+        )?;
+        if opts.batch_validator_adds {
+            warn_unbatched_validator_adds(ui, "subnet validator", all_node_ids.len())?;
+        }
+        // earlier steps may have taken long enough to violate the minimum duration
+        if let Some(end_at) = subnet_validation_end_at {
+            validate_subnet_validation_end_at(
+                end_at,
+                unix_now(),
+                opts.primary_network_validate_period_in_days,
+                network_id,
+            )?;
+
+            // nodes that were already primary network validators may end sooner
+            // than the primary validate period, so check the actual end times
+            let primary_end_times = fetch_primary_validator_end_times(
+                p_chain_rpc_url,
+                all_node_ids,
+                rpc_headers,
+                api_paths,
+            )
+            .await?;
+            check_subnet_end_before_primary(end_at, all_node_ids, &primary_end_times)?;
+        }
+        let mut handles = Vec::new();
+        for (i, node_id) in all_node_ids.iter().enumerate() {
+            log::info!(
+                "spawning add_subnet_validator on '{}' (staking period in days '{}', end at '{}')",
+                node_id,
+                opts.subnet_validate_period_in_days,
+                subnet_validation_end_at
+                    .map(format_timestamp)
+                    .unwrap_or_else(|| String::from("(none)"))
+            );
+
+            // randomly wait to prevent UTXO double spends from the same wallet
+            let random_wait = if i < 5 {
+                Duration::from_secs(2 + (i * 2) as u64)
+                    .checked_add(Duration::from_millis(500 + random_manager::u64() % 100))
+                    .unwrap()
+            } else {
+                Duration::from_secs(5 + (i * 2) as u64)
+                    .checked_add(Duration::from_millis(500 + random_manager::u64() % 100))
+                    .unwrap()
+            };
+            handles.push((
+                node_id.clone(),
+                tokio::spawn(add_subnet_network_validator(
+                    Arc::new(random_wait),
+                    Arc::new(wallet_to_spend.clone()),
+                    Arc::new(node_id.to_owned()),
+                    Arc::new(created_subnet_id.to_owned()),
+                    Arc::new(opts.subnet_validate_period_in_days),
+                    Arc::new(subnet_validation_end_at),
+                    Arc::new(acceptance_poller.clone()),
+                )),
+            ));
+        }
+
+        // collect all results, so that one failure does not hide the others
+        log::info!("STEP: blocking on add_subnet_validator handles via JoinHandle");
+        let mut succeeded = Vec::new();
+        let mut skipped = Vec::new();
+        let mut failed = Vec::new();
+        for (node_id, handle) in handles {
+            match handle.await {
+                // not added when the wallet finds the node already validating the subnet
+                Ok(Ok((_, false))) => {
+                    if let Some(node) = output.node_mut(&node_id) {
+                        node.status = NodeStatus::AlreadyValidating;
+                    }
+                    skipped.push(node_id)
+                }
+                Ok(Ok((tx_id, true))) => {
+                    if let Some(node) = output.node_mut(&node_id) {
+                        node.subnet_validator_tx = Some(tx_id);
+                    }
+                    succeeded.push((node_id, tx_id))
+                }
+                Ok(Err(e)) => {
+                    let reason = e.to_string();
+                    if opts.continue_on_existing_subnet_validator
+                        && is_existing_validator_error(&reason)
+                    {
+                        log::warn!("'{node_id}' already validates the subnet, skipping ({reason})");
+                        if let Some(node) = output.node_mut(&node_id) {
+                            node.status = NodeStatus::AlreadyValidating;
+                        }
+                        skipped.push(node_id);
+                    } else {
+                        failed.push((node_id, reason));
+                    }
+                }
+                Err(e) => failed.push((node_id, format!("failed await on JoinHandle {}", e))),
+            }
+        }
+        checkpoint(progress, output);
+        sleep(Duration::from_secs(5)).await;
+
+        ui.print_line("");
+        ui.print(
+            Color::Green,
+            format!(
+                "\nSUBNET VALIDATORS ({} succeeded, {} skipped, {} failed)\n",
+                succeeded.len(),
+                skipped.len(),
+                failed.len()
+            ),
+        )?;
+        for (node_id, tx_id) in succeeded.iter() {
+            ui.print_line(&format!("SUCCEEDED\t{node_id}\ttx id {tx_id}"));
+        }
+        for node_id in skipped.iter() {
+            ui.print_line(&format!("SKIPPED\t{node_id}\talready a subnet validator"));
+        }
+        for (node_id, reason) in failed.iter() {
+            ui.print_line(&format!("FAILED\t{node_id}\t{reason}"));
+        }
+        if !failed.is_empty() {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!(
+                    "failed to add {} node(s) as subnet validators {:?} (subnet '{created_subnet_id}')",
+                    failed.len(),
+                    failed
+                        .iter()
+                        .map(|(node_id, _)| node_id.as_str())
+                        .collect::<Vec<&str>>()
+                ),
+            ));
+        }
+    } else {
+        skip_step(progress, "existing subnet");
+    }
+
+    Ok(())
+}
+
+/// Saves the subnet Id for the second run that creates the blockchain ("--exit-after-create-subnet").
+async fn save_created_subnet(
+    ctx: &InstallContext,
+    output: &mut InstallOutput,
+    created_subnet_id: ids::Id,
+) -> io::Result<()> {
+    let InstallContext {
+        opts,
+        tx_status_poller,
+        ..
+    } = ctx;
+    let ui = ctx.ui;
+    let existing_subnet_id = ctx.existing_subnet_id;
+
+    fs::create_dir_all(&opts.output_dir)?;
+    let subnet_id_path = Path::new(&opts.output_dir)
+        .join(format!(
+            "{}-subnet-id.txt",
+            opts.chain_name.replace(' ', "-")
+        ))
+        .display()
+        .to_string();
+    fs::write(&subnet_id_path, created_subnet_id.to_string())?;
+
+    ui.print(Color::Blue, format!(
+            "\n\n\nSUBNET CREATED!\nsubnet Id: {created_subnet_id} (saved to '{subnet_id_path}')\n\nre-run with '--subnet-id {created_subnet_id}' to create the blockchain\n\n"
+        ))?;
+    if opts.no_check_acceptance {
+        tx_status_poller
+            .wait_all(&output.issued_tx_ids(existing_subnet_id.is_none()))
+            .await?;
+    }
+    output.complete_nodes();
+    print_node_results(ui, &output.nodes)?;
+    Ok(())
+}
+
+/// Creates the blockchain on the subnet with the genesis, and waits for it on the P-chain.
+/// Returns the blockchain Id.
+async fn create_blockchain(
+    ctx: &InstallContext,
+    progress: &Arc<Mutex<Progress>>,
+    output: &mut InstallOutput,
+    created_subnet_id: ids::Id,
+) -> io::Result<ids::Id> {
+    let InstallContext {
+        opts,
+        api_paths,
+        rpc_headers,
+        p_chain_rpc_url,
+        wallet_to_spend,
+        chain_genesis_bytes,
+        acceptance_poller,
+        ..
+    } = ctx;
+    let ui = ctx.ui;
+    let vm_id = ctx.vm_id;
+
+    ui.print(
+        Color::Green,
+        "\n\n\nSTEP: creating a blockchain with the genesis\n\n",
+    )?;
+    let predicted_blockchain_id = wallet_to_spend
+        .p()
+        .create_chain()
+        .subnet_id(created_subnet_id)
+        .genesis_data(chain_genesis_bytes.clone())
+        .vm_id(vm_id)
+        .chain_name(opts.chain_name.clone())
+        .dry_mode(true)
+        .issue()
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed create_chain in dry mode ({})", e),
+            )
+        })?;
+    log::info!("[dry mode] blockchain Id {predicted_blockchain_id} for subnet {created_subnet_id}");
+
+    let blockchain_id = wallet_to_spend
+        .p()
+        .create_chain()
+        .subnet_id(created_subnet_id)
+        .genesis_data(chain_genesis_bytes.clone())
+        .vm_id(vm_id)
+        .chain_name(opts.chain_name.clone())
+        .issue()
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!(
+                    "failed create_chain for subnet '{created_subnet_id}' ({})",
+                    e
+                ),
+            )
+        })?;
+    if let Some(poller) = acceptance_poller {
+        poller.wait("create chain", &blockchain_id).await?;
+    }
+    log::info!("created a blockchain {blockchain_id} for subnet {created_subnet_id}");
+    output.blockchain_id = Some(blockchain_id);
+    checkpoint(progress, output);
+    telemetry::set_run_attribute("blockchain_id", blockchain_id.to_string());
+    if let Some(warning) = check_id_prediction(
+        "blockchain",
+        &predicted_blockchain_id,
+        &blockchain_id,
+        opts.strict_id_prediction,
+    )? {
+        ui.print(Color::Yellow, format!("\n\nWARNING: {warning}\n\n"))?;
+    }
+    wait_for_blockchain(
+        p_chain_rpc_url,
+        &blockchain_id,
+        &created_subnet_id,
+        &vm_id,
+        rpc_headers,
+        api_paths,
+    )
+    .await?;
+
+    Ok(blockchain_id)
+}
+
+/// Sends the SSM docs to update the chain config and to alias the blockchain, if any.
+/// Returns the node ids that registered the alias on the running node.
+async fn update_chain_config_and_alias(
+    ctx: &InstallContext,
+    progress: &Arc<Mutex<Progress>>,
+    output: &mut InstallOutput,
+    blockchain_id: ids::Id,
+) -> io::Result<Vec<String>> {
+    let InstallContext {
+        opts,
+        target_nodes,
+        region_to_instance_ids,
+        api_paths,
+        rpc_headers,
+        deployer,
+        ..
+    } = ctx;
+    let ui = ctx.ui;
+
+    let mut registered_alias_node_ids = Vec::new();
+    if opts.chain_config_local_path.is_empty() && opts.chain_alias.is_empty() {
+        skip_step(progress, "no chain config nor alias");
+    }
+    if !opts.chain_config_local_path.is_empty() {
+        ui.print(
+            Color::Green,
+            "\n\n\nSTEP: sending SSM doc for chain-config updates\n\n",
+        )?;
+
+        let file_stem = Path::new(&opts.chain_config_local_path)
+            .file_stem()
+            .unwrap();
+        let chain_config_s3_key = format!(
+            "{}{}",
+            s3::append_slash(&opts.s3_key_prefix),
+            file_stem.to_str().unwrap()
+        );
+
+        // If a Subnet's chain id is 2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt,
+        // the config file for this chain is located at {chain-config-dir}/2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt/config.json
+        // (or the file name set by --chain-config-remote-filename).
+        let avalanched_args = format!("install-chain --log-level info --s3-region {region} --s3-bucket {s3_bucket} --chain-config-s3-key {chain_config_s3_key} --chain-config-local-path {chain_config_local_path}",
+            region = opts.s3_region,
+            s3_bucket = opts.s3_bucket,
+            chain_config_s3_key = chain_config_s3_key,
+            chain_config_local_path = format!("{}{}/{}", s3::append_slash(&opts.chain_config_remote_dir), blockchain_id, opts.chain_config_remote_filename),
+        );
+
+        record_ssm_commands(
+            progress,
+            output,
+            deployer
+                .run_chain_config(region_to_instance_ids, &avalanched_args)
+                .await,
+        )?;
+    }
+
+    if !opts.chain_alias.is_empty() {
+        ui.print(
+            Color::Green,
+            format!(
+                "\n\n\nSTEP: sending SSM doc to alias the blockchain to '{}'\n\n",
+                opts.chain_alias
+            ),
+        )?;
+
+        let avalanched_args = format!("alias-chain --log-level info --blockchain-id {blockchain_id} --chain-alias {chain_alias} --chain-aliases-path {chain_aliases_path}",
+            chain_alias = opts.chain_alias,
+            chain_aliases_path = opts.chain_aliases_remote_path,
+        );
+        record_ssm_commands(
+            progress,
+            output,
+            deployer.run(region_to_instance_ids, &avalanched_args).await,
+        )?;
+
+        // the aliases file is only read at the node start, so register the alias
+        // on the running nodes that expose their own RPC (with the admin API)
+        for node_id in target_nodes.keys() {
+            let Some(node_rpc_url) = opts.node_rpc_urls.get(node_id) else {
+                log::warn!("no RPC URL for node '{node_id}' in --node-rpc-urls, the alias takes effect after its restart");
+                continue;
+            };
+            match register_chain_alias(&api_paths.base_url(node_rpc_url), &blockchain_id, &opts.chain_alias, rpc_headers).await {
+                Ok(()) => {
+                    log::info!("registered alias '{}' on node '{node_id}'", opts.chain_alias);
+                    registered_alias_node_ids.push(node_id.clone());
+                }
+                Err(e) => log::warn!("failed to register alias '{}' on node '{node_id}' ({e}), the alias takes effect after its restart", opts.chain_alias),
+            }
+        }
+    }
+
+    Ok(registered_alias_node_ids)
+}
+
+/// Polls the issued transactions (if not yet), checks the node health,
+/// and waits for the nodes to bootstrap the blockchain (if set).
+async fn verify_nodes(
+    ctx: &InstallContext,
+    output: &mut InstallOutput,
+    blockchain_id: ids::Id,
+) -> io::Result<()> {
+    let InstallContext {
+        opts,
+        target_nodes,
+        api_paths,
+        rpc_headers,
+        tx_status_poller,
+        ..
+    } = ctx;
+    let ui = ctx.ui;
+    let existing_subnet_id = ctx.existing_subnet_id;
+
+    if opts.no_check_acceptance {
+        ui.print(
+            Color::Green,
+            "\n\n\nSTEP: polling the acceptance of the issued transactions\n\n",
+        )?;
+        tx_status_poller
+            .wait_all(&output.issued_tx_ids(existing_subnet_id.is_none()))
+            .await?;
+    }
+
+    ui.print(
+        Color::Green,
+        "\n\n\nSTEP: verifying node health and bootstrap status via per-node RPC\n\n",
+    )?;
+    for node_id in target_nodes.keys() {
+        // the shared --chain-rpc-url would report another node's status as this node's
+        let Some(node_rpc_url) = opts.node_rpc_urls.get(node_id).cloned() else {
+            log::warn!("node '{node_id}' health is unknown (no RPC URL in --node-rpc-urls)");
+            continue;
+        };
+
+        match json_client_info::get_node_id(&api_paths.base_url(&node_rpc_url)).await {
+            Ok(resp) => match resp.result {
+                Some(result) => {
+                    if result.node_id.to_string() != *node_id {
+                        log::warn!(
+                            "node RPC '{node_rpc_url}' is served by '{}', expected '{node_id}'",
+                            result.node_id
+                        );
+                    }
+                }
+                None => {
+                    log::warn!("no node id in the response from '{node_rpc_url}' for '{node_id}' (RPC error)");
+                }
+            },
+            Err(e) => {
+                log::warn!("failed to fetch node id from '{node_rpc_url}' for '{node_id}' ({e})");
+            }
+        }
+
+        match json_client_health::check(Arc::new(node_rpc_url.clone()), false).await {
+            Ok(res) => {
+                if res.healthy {
+                    log::info!(
+                        "node '{node_id}' is healthy and bootstrapped (via '{node_rpc_url}')"
+                    );
+                } else {
+                    log::warn!("node '{node_id}' is not healthy yet (via '{node_rpc_url}')");
+                }
+            }
+            Err(e) => {
+                log::warn!("health check failed for '{node_id}' via '{node_rpc_url}' ({e})");
+            }
+        }
+    }
+
+    if opts.wait_for_bootstrap_minutes > 0 {
+        ui.print(
+            Color::Green,
+            format!(
+                "\n\n\nSTEP: waiting up to {} minute(s) for the nodes to bootstrap the blockchain {blockchain_id}\n\n",
+                opts.wait_for_bootstrap_minutes
+            ),
+        )?;
+        let deadline = Instant::now() + Duration::from_secs(opts.wait_for_bootstrap_minutes * 60);
+        let mut reports = Vec::new();
+        let mut handles = Vec::new();
+        for node_id in target_nodes.keys() {
+            let Some(node_rpc_url) = opts.node_rpc_urls.get(node_id).cloned() else {
+                reports.push(BootstrapStatus::unknown(node_id));
+                continue;
+            };
+            handles.push(tokio::spawn(wait_for_chain_bootstrap(
+                node_id.clone(),
+                node_rpc_url,
+                blockchain_id,
+                rpc_headers.clone(),
+                api_paths.clone(),
+                deadline,
+            )));
+        }
+        for handle in handles {
+            reports.push(handle.await.map_err(|e| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!("failed to join the bootstrap wait ({})", e),
+                )
+            })?);
+        }
+        reports.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+
+        ui.print(Color::Blue, "bootstrap status:\n")?;
+        ui.print_line(&format_bootstrap_report(&reports));
+        let stuck: Vec<&str> = reports
+            .iter()
+            .filter(|r| r.bootstrapped == Some(false))
+            .map(|r| r.node_id.as_str())
+            .collect();
+        let unknown: Vec<&str> = reports
+            .iter()
+            .filter(|r| r.bootstrapped.is_none())
+            .map(|r| r.node_id.as_str())
+            .collect();
+        if !unknown.is_empty() {
+            ui.print(
+                Color::Yellow,
+                format!(
+                    "\n\nWARNING: bootstrap status of {} node(s) is unknown (no RPC URL in --node-rpc-urls): {}\n\n",
+                    unknown.len(),
+                    unknown.join(", ")
+                ),
+            )?;
+        }
+        if !stuck.is_empty() {
+            ui.print(
+                Color::Yellow,
+                format!(
+                    "\n\nWARNING: {} node(s) did not bootstrap the blockchain within {} minute(s): {}\n\n",
+                    stuck.len(),
+                    opts.wait_for_bootstrap_minutes,
+                    stuck.join(", ")
+                ),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Upper bound of the post-install hook run.
+const POST_INSTALL_HOOK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Returns the environment variables of the post-install hook
+/// (empty if the run did not produce the Id).
+pub fn post_install_hook_envs(output: &InstallOutput) -> Vec<(String, String)> {
+    let id = |id: &Option<ids::Id>| id.map(|id| id.to_string()).unwrap_or_default();
+    vec![
+        (String::from("AVAX_SUBNET_ID"), id(&output.subnet_id)),
+        (
+            String::from("AVAX_BLOCKCHAIN_ID"),
+            id(&output.blockchain_id),
+        ),
+        (String::from("AVAX_VM_ID"), id(&output.vm_id)),
+        (
+            String::from("AVAX_NETWORK_ID"),
+            output
+                .network_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            String::from("AVAX_RESULT_JSON"),
+            serde_json::to_string(output).unwrap(),
+        ),
+    ]
+}
+
+/// Runs the post-install hook with the results in its environment, and logs its output.
+/// A failed hook (not started, timed out, or exited non-zero) fails the run only
+/// if "fail_on_error" is set, as the install itself has already succeeded.
+async fn run_post_install_hook(
+    hook: &str,
+    output: &InstallOutput,
+    fail_on_error: bool,
+) -> io::Result<()> {
+    let _span = telemetry::span("post-install hook", &[("hook", hook.to_string())]);
+    log::info!("running the post-install hook '{hook}'");
+
+    let mut cmd = tokio::process::Command::new(hook);
+    cmd.envs(post_install_hook_envs(output)).kill_on_drop(true);
+    let ret = match timeout(POST_INSTALL_HOOK_TIMEOUT, cmd.output()).await {
+        Err(_) => Err(Error::new(
+            ErrorKind::TimedOut,
+            format!("post-install hook '{hook}' did not exit in {POST_INSTALL_HOOK_TIMEOUT:?}"),
+        )),
+        Ok(Err(e)) => Err(Error::new(
+            ErrorKind::Other,
+            format!("failed to run the post-install hook '{hook}' ({})", e),
+        )),
+        Ok(Ok(out)) => {
+            log::info!(
+                "post-install hook '{hook}' exited with {} (stdout '{}', stderr '{}')",
+                out.status,
+                String::from_utf8_lossy(&out.stdout).trim(),
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+            if out.status.success() {
+                Ok(())
+            } else {
+                Err(Error::new(
+                    ErrorKind::Other,
+                    format!("post-install hook '{hook}' failed ({})", out.status),
+                ))
+            }
+        }
+    };
+    match ret {
+        Err(e) if !fail_on_error => {
+            log::warn!("{e} (ignored without --fail-on-hook-error)");
+            Ok(())
+        }
+        ret => ret,
+    }
+}
+
+/// Maximum length of the blockchain name accepted by avalanchego.
+/// ref. <https://github.com/ava-labs/avalanchego/blob/master/vms/platformvm/txs/create_chain_tx.go>
+const MAX_CHAIN_NAME_LEN: usize = 128;
+
+/// Maximum length of the chain name when the VM Id is derived from it
+/// (the name is right-padded into the 32-byte Id).
+const MAX_VM_NAME_LEN: usize = 32;
+
+/// Compares the dry-run Id with the created one, which differ if the wallet
+/// UTXOs changed in between (e.g., a concurrent spend from the same key).
+/// Returns the warning on mismatch, or fails if "strict" to abort further steps.
+pub fn check_id_prediction(
+    kind: &str,
+    predicted: &ids::Id,
+    created: &ids::Id,
+    strict: bool,
+) -> io::Result<Option<String>> {
+    if predicted == created {
+        return Ok(None);
+    }
+    let msg = format!(
+        "created {kind} Id '{created}' differs from the dry-run {kind} Id '{predicted}' (the wallet UTXOs changed, e.g., a concurrent spend), use '{created}'"
+    );
+    if strict {
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!("{msg}, aborting further steps (--strict-id-prediction)"),
+        ));
+    }
+    log::warn!("{msg}");
+    Ok(Some(msg))
+}
+
+pub const VM_ID_DERIVATION_NAME: &str = "name";
+pub const VM_ID_DERIVATION_SHA256_BINARY: &str = "sha256-binary";
+
+/// Derives the VM Id: "name" uses avalanchego's conversion of the chain name
+/// (zero-padded to 32 bytes), "sha256-binary" uses the SHA-256 digest of the
+/// VM binary, so that each binary build is keyed by its own VM Id.
+pub fn derive_vm_id(
+    derivation: &str,
+    chain_name: &str,
+    vm_binary_local_path: &str,
+) -> io::Result<ids::Id> {
+    match derivation {
+        VM_ID_DERIVATION_NAME => subnet::vm_name_to_id(chain_name),
+        VM_ID_DERIVATION_SHA256_BINARY => {
+            if vm_binary_local_path.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "--vm-id-derivation 'sha256-binary' requires --vm-binary-local-path",
+                ));
+            }
+            let digest = vm_binary::sha256_file(vm_binary_local_path)?;
+            Ok(ids::Id::from_slice(&digest))
+        }
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "unknown VM Id derivation '{derivation}' (expected '{VM_ID_DERIVATION_NAME}' or '{VM_ID_DERIVATION_SHA256_BINARY}')"
+            ),
+        )),
+    }
+}
+
+/// Validates the chain name against the avalanchego "CreateChainTx" rules
+/// (non-empty, at most 128 bytes, ASCII letters, digits, and spaces only).
+/// If the VM Id is derived from the chain name, the name must also fit in 32 bytes.
+pub fn validate_chain_name(chain_name: &str, derive_vm_id: bool) -> io::Result<()> {
+    if chain_name.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "chain name is empty (must be 1 to 128 ASCII letters, digits, or spaces)",
+        ));
+    }
+    if chain_name.len() > MAX_CHAIN_NAME_LEN {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "chain name '{chain_name}' is {} bytes (must be at most {MAX_CHAIN_NAME_LEN} bytes)",
+                chain_name.len()
+            ),
+        ));
+    }
+    if let Some(c) = chain_name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == ' '))
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "chain name '{chain_name}' has an illegal character '{c}' (must be ASCII letters, digits, or spaces)"
+            ),
+        ));
+    }
+    if derive_vm_id && chain_name.len() > MAX_VM_NAME_LEN {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "chain name '{chain_name}' is {} bytes, too long to derive the VM Id (must be at most {MAX_VM_NAME_LEN} bytes, or set --vm-id)",
+                chain_name.len()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Parses all node ids (with the source of each, e.g., "--node-rpc-urls"),
+/// reporting every malformed id together instead of failing on the first one.
+pub fn parse_node_ids<'a>(
+    node_ids: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> io::Result<HashMap<String, ids::node::Id>> {
+    let mut parsed = HashMap::new();
+    let mut invalid = Vec::new();
+    for (source, node_id) in node_ids {
+        if parsed.contains_key(node_id) {
+            continue;
+        }
+        match ids::node::Id::from_str(node_id) {
+            Ok(id) => {
+                parsed.insert(node_id.to_string(), id);
+            }
+            Err(e) => invalid.push(format!("'{node_id}' in {source} ({e})")),
+        }
+    }
+    if invalid.is_empty() {
+        return Ok(parsed);
+    }
+
+    invalid.sort();
+    invalid.dedup();
+    Err(Error::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "{} invalid node id(s): {}",
+            invalid.len(),
+            invalid.join(", ")
+        ),
+    ))
+}
+
+/// Offset of the validation start time from the time of issuing the staking tx
+/// (same as the "validate_period_in_days" offset).
+const VALIDATION_START_OFFSET_SECS: u64 = 60;
+
+/// Maximum staking duration of both primary network and subnet validators.
+/// ref. <https://github.com/ava-labs/avalanchego/blob/master/genesis/genesis_mainnet.go>
+const MAX_STAKE_DURATION_SECS: u64 = 365 * 24 * 60 * 60;
+
+/// Returns the minimum staking duration of the network (2 weeks on the mainnet,
+/// 24 hours on the fuji and local networks), also applied to the subnet validators.
+/// ref. <https://github.com/ava-labs/avalanchego/blob/master/genesis/genesis_fuji.go>
+pub fn min_stake_duration_secs(network_id: u32) -> u64 {
+    match network_id {
+        1 => 14 * 24 * 60 * 60,
+        _ => 24 * 60 * 60,
+    }
+}
+
+fn unix_now() -> u64 {
+    Utc::now().timestamp() as u64
+}
+
+/// Parses the timestamp either in Unix seconds or RFC 3339 (e.g., "2023-07-01T00:00:00Z").
+pub fn parse_timestamp(s: &str) -> io::Result<u64> {
+    let s = s.trim();
+    if let Ok(secs) = s.parse::<u64>() {
+        return Ok(secs);
+    }
+    let dt = DateTime::parse_from_rfc3339(s).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "invalid timestamp '{s}' (expected Unix seconds or RFC 3339, {})",
+                e
+            ),
+        )
+    })?;
+    u64::try_from(dt.timestamp()).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("timestamp '{s}' is before the Unix epoch"),
+        )
+    })
+}
+
+fn to_date_time(secs: u64) -> io::Result<DateTime<Utc>> {
+    Utc.timestamp_opt(secs as i64, 0).single().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("timestamp {secs} is out of range"),
+        )
+    })
+}
+
+fn format_timestamp(secs: u64) -> String {
+    match to_date_time(secs) {
+        Ok(dt) => format!("{} ({secs})", dt.to_rfc3339()),
+        Err(_) => secs.to_string(),
+    }
+}
+
+/// Validates the absolute end time of the subnet validation against the
+/// protocol bounds: the validation starts "VALIDATION_START_OFFSET_SECS" after now,
+/// must last at least the network's minimum staking duration and at most a year,
+/// and must end strictly before the primary network validation that starts no
+/// earlier than now (thus ends no earlier than the primary validate period from now).
+pub fn validate_subnet_validation_end_at(
+    end_at: u64,
+    now: u64,
+    primary_network_validate_period_in_days: u64,
+    network_id: u32,
+) -> io::Result<()> {
+    let start_at = now + VALIDATION_START_OFFSET_SECS;
+    if end_at <= start_at {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "subnet validation end {} is not after the validation start {}",
+                format_timestamp(end_at),
+                format_timestamp(start_at)
+            ),
+        ));
+    }
+
+    let duration = end_at - start_at;
+    let min_duration = min_stake_duration_secs(network_id);
+    if duration < min_duration {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "subnet validation end {} is only {duration} seconds after the start (network Id {network_id} requires at least {min_duration} seconds)",
+                format_timestamp(end_at)
+            ),
+        ));
+    }
+    if duration > MAX_STAKE_DURATION_SECS {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "subnet validation end {} is {duration} seconds after the start (must be at most {MAX_STAKE_DURATION_SECS} seconds)",
+                format_timestamp(end_at)
+            ),
+        ));
+    }
+
+    let primary_end_at = start_at + primary_network_validate_period_in_days * 24 * 60 * 60;
+    if end_at >= primary_end_at {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "subnet validation end {} must be before the primary network validation end {} ({primary_network_validate_period_in_days} days)",
+                format_timestamp(end_at),
+                format_timestamp(primary_end_at)
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Fetches the end time (Unix seconds) of the primary network validation of each
+/// node, from the current and pending validators (the validators just added only
+/// start after "VALIDATION_START_OFFSET_SECS").
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetcurrentvalidators>
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetpendingvalidators>
+async fn fetch_primary_validator_end_times(
+    p_chain_rpc_url: &str,
+    node_ids: &[String],
+    rpc_headers: &[(String, String)],
+    api_paths: &ApiPaths,
+) -> io::Result<HashMap<String, u64>> {
+    let mut end_times = HashMap::new();
+    for method in [
+        "platform.getCurrentValidators",
+        "platform.getPendingValidators",
+    ] {
+        let result = call_json_rpc(
+            p_chain_rpc_url,
+            &api_paths.p_chain,
+            method,
+            serde_json::json!({ "nodeIDs": node_ids }),
+            rpc_headers,
+        )
+        .await?;
+        for (node_id, end_at) in parse_validator_end_times(&result)? {
+            end_times.entry(node_id).or_insert(end_at);
+        }
+    }
+    Ok(end_times)
+}
+
+/// Parses the node Ids and end times of the "platform.getCurrentValidators"
+/// (or "platform.getPendingValidators") result, where "endTime" is a string.
+pub fn parse_validator_end_times(result: &serde_json::Value) -> io::Result<HashMap<String, u64>> {
+    let validators = result["validators"].as_array().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("no 'validators' in the result ({result})"),
+        )
+    })?;
+    let mut end_times = HashMap::new();
+    for v in validators {
+        let (Some(node_id), Some(end_time)) = (v["nodeID"].as_str(), v["endTime"].as_str()) else {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("validator without 'nodeID' or 'endTime' ({v})"),
+            ));
+        };
+        let end_at = end_time.parse::<u64>().map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid endTime '{end_time}' of '{node_id}' ({e})"),
+            )
+        })?;
+        end_times.insert(node_id.to_string(), end_at);
+    }
+    Ok(end_times)
+}
+
+/// Checks that the subnet validation of every node ends strictly before its
+/// primary network validation. Fails if a node is not a primary network validator.
+pub fn check_subnet_end_before_primary(
+    end_at: u64,
+    node_ids: &[String],
+    primary_end_times: &HashMap<String, u64>,
+) -> io::Result<()> {
+    let mut problems = Vec::new();
+    for node_id in node_ids.iter() {
+        match primary_end_times.get(node_id) {
+            Some(primary_end_at) if end_at < *primary_end_at => {}
+            Some(primary_end_at) => problems.push(format!(
+                "'{node_id}' primary network validation ends at {}",
+                format_timestamp(*primary_end_at)
+            )),
+            None => problems.push(format!("'{node_id}' is not a primary network validator")),
+        }
+    }
+    if !problems.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "subnet validation end {} must be before the primary network validation end: {}",
+                format_timestamp(end_at),
+                problems.join(", ")
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Builds the avalanched "install-subnet" args to download the VM binary and
+/// track the subnet Id (merged into the node's existing track-subnets unless "replace").
+/// The download is omitted if the VM binary S3 key is empty.
+pub fn install_subnet_args(
+    s3_region: &str,
+    s3_bucket: &str,
+    vm_binary_s3_key: &str,
+    vm_binary_local_path: &str,
+    subnet_id_to_track: &str,
+    avalanchego_config_remote_path: &str,
+    track_subnets_mode: &str,
+) -> String {
+    let vm_binary_args = if vm_binary_s3_key.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " --vm-binary-s3-key {vm_binary_s3_key} --vm-binary-local-path {vm_binary_local_path}"
+        )
+    };
+    format!("install-subnet --log-level info --s3-region {s3_region} --s3-bucket {s3_bucket}{vm_binary_args} --subnet-id-to-track {subnet_id_to_track} --track-subnets-mode {track_subnets_mode} --avalanchego-config-path {avalanchego_config_remote_path}")
+}
+
+/// Returns true if the error is from adding a node that already validates the subnet.
+/// ref. <https://github.com/ava-labs/avalanchego/blob/master/vms/platformvm/txs/executor/staker_tx_verification.go>
+pub fn is_existing_validator_error(msg: &str) -> bool {
+    let msg = msg.to_lowercase();
+    msg.contains("duplicate validator")
+        || msg.contains("already a validator")
+        || msg.contains("already validating")
+}
+
+/// Checks that the node is healthy ("health.health") and bootstrapped on the
+/// primary network ("info.isBootstrapped" for P/X/C) via its own RPC endpoint.
+/// Returns the list of problems, empty if the node is ready for staking.
+async fn check_node_ready(
+    node_rpc_url: &str,
+    rpc_headers: &[(String, String)],
+    api_paths: &ApiPaths,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    match call_json_rpc(
+        node_rpc_url,
+        &api_paths.health(),
+        "health.health",
+        serde_json::json!({}),
+        rpc_headers,
+    )
+    .await
+    {
+        Ok(result) => {
+            if !result["healthy"].as_bool().unwrap_or(false) {
+                problems.push("not healthy".to_string());
+            }
+        }
+        Err(e) => problems.push(format!("health check failed ({e})")),
+    }
+    for chain in ["P", "X", "C"] {
+        let params = serde_json::json!({ "chain": chain });
+        match call_json_rpc(
+            node_rpc_url,
+            &api_paths.info,
+            "info.isBootstrapped",
+            params,
+            rpc_headers,
+        )
+        .await
+        {
+            Ok(result) => {
+                if !result["isBootstrapped"].as_bool().unwrap_or(false) {
+                    problems.push(format!("{chain}-chain not bootstrapped"));
+                }
+            }
+            Err(e) => problems.push(format!("{chain}-chain isBootstrapped failed ({e})")),
+        }
+    }
+    problems
+}
+
+/// Last known bootstrap status of a node on the new blockchain.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct BootstrapStatus {
+    pub node_id: String,
+    /// Empty if the node has no RPC URL in "--node-rpc-urls".
+    pub rpc_url: String,
+    /// None if unknown, as the node has no RPC URL in "--node-rpc-urls"
+    /// (the shared endpoint would report another node's status).
+    pub bootstrapped: Option<bool>,
+    /// Last accepted height, if the VM serves "eth_blockNumber" (e.g., subnet-evm).
+    pub height: Option<u64>,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+}
+
+impl BootstrapStatus {
+    fn unknown(node_id: &str) -> Self {
+        Self {
+            node_id: node_id.to_string(),
+            rpc_url: String::new(),
+            bootstrapped: None,
+            height: None,
+            attempts: 0,
+            last_error: None,
+        }
+    }
+}
+
+/// Maximum wait between the bootstrap polls of a node.
+const BOOTSTRAP_POLL_MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Polls "info.isBootstrapped" for the blockchain on the node with backoff
+/// until bootstrapped or the deadline, returning the last known status
+/// (never fails, so that a stuck node does not hide the status of the others).
+async fn wait_for_chain_bootstrap(
+    node_id: String,
+    node_rpc_url: String,
+    blockchain_id: ids::Id,
+    rpc_headers: Vec<(String, String)>,
+    api_paths: ApiPaths,
+    deadline: Instant,
+) -> BootstrapStatus {
+    let mut status = BootstrapStatus {
+        node_id,
+        rpc_url: node_rpc_url.clone(),
+        bootstrapped: Some(false),
+        height: None,
+        attempts: 0,
+        last_error: None,
+    };
+    let mut wait = Duration::from_secs(5);
+    loop {
+        status.attempts += 1;
+        match call_json_rpc(
+            &node_rpc_url,
+            &api_paths.info,
+            "info.isBootstrapped",
+            serde_json::json!({ "chain": blockchain_id.to_string() }),
+            &rpc_headers,
+        )
+        .await
+        {
+            Ok(result) => {
+                status.bootstrapped = Some(result["isBootstrapped"].as_bool().unwrap_or(false));
+                status.last_error = None;
+            }
+            Err(e) => status.last_error = Some(e.to_string()),
+        }
+        if let Ok(result) = call_json_rpc(
+            &node_rpc_url,
+            &api_paths.evm(&blockchain_id),
+            "eth_blockNumber",
+            serde_json::json!([]),
+            &rpc_headers,
+        )
+        .await
+        {
+            if let Some(h) = result.as_str() {
+                status.height = u64::from_str_radix(h.trim_start_matches("0x"), 16).ok();
+            }
+        }
+        if status.bootstrapped == Some(true) {
+            log::info!(
+                "node '{}' bootstrapped the blockchain {blockchain_id} (height {:?})",
+                status.node_id,
+                status.height
+            );
+            return status;
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return status;
+        }
+        log::info!(
+            "node '{}' has not bootstrapped the blockchain {blockchain_id} yet (attempt {}, height {:?}), retrying in {:?}",
+            status.node_id,
+            status.attempts,
+            status.height,
+            wait
+        );
+        sleep(wait.min(deadline - now)).await;
+        wait = (wait * 2).min(BOOTSTRAP_POLL_MAX_WAIT);
+    }
+}
+
+/// Formats the bootstrap status of the nodes, one node per line.
+pub fn format_bootstrap_report(statuses: &[BootstrapStatus]) -> String {
+    statuses
+        .iter()
+        .map(|s| {
+            format!(
+                "{}\t{}\theight {}\tattempts {}\t{}{}",
+                s.node_id,
+                match s.bootstrapped {
+                    Some(true) => "bootstrapped",
+                    Some(false) => "NOT bootstrapped",
+                    None => "unknown",
+                },
+                s.height
+                    .map(|h| h.to_string())
+                    .unwrap_or_else(|| String::from("-")),
+                s.attempts,
+                s.rpc_url,
+                s.last_error
+                    .as_ref()
+                    .map(|e| format!("\tlast error: {e}"))
+                    .unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Timeout for each step of the RPC reachability preflight.
+const RPC_PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Returns the host and port of the RPC URL (defaults to the scheme's port).
+pub fn rpc_host_port(rpc_url: &str) -> io::Result<(String, u16)> {
+    let u = reqwest::Url::parse(rpc_url).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid RPC URL '{rpc_url}' ({})", e),
+        )
+    })?;
+    let host = u.host_str().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("RPC URL '{rpc_url}' has no host"),
+        )
+    })?;
+    let port = u.port_or_known_default().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("RPC URL '{rpc_url}' has no port"),
+        )
+    })?;
+    Ok((
+        host.trim_matches(|c| c == '[' || c == ']').to_string(),
+        port,
+    ))
+}
+
+/// Checks that the RPC endpoint is reachable (DNS, TCP, then "info.getNetworkID")
+/// before building the wallet, and returns its network Id.
+/// Each failure names the URL with a hint on what to check.
+async fn check_rpc_reachable(
+    rpc_url: &str,
+    rpc_headers: &[(String, String)],
+    api_paths: &ApiPaths,
+) -> io::Result<u32> {
+    let (host, port) = rpc_host_port(rpc_url)?;
+
+    let addrs: Vec<std::net::SocketAddr> = match timeout(
+        RPC_PREFLIGHT_TIMEOUT,
+        tokio::net::lookup_host((host.as_str(), port)),
+    )
+    .await
+    {
+        Ok(Ok(addrs)) => addrs.collect(),
+        Ok(Err(e)) => {
+            return Err(Error::new(
+                    ErrorKind::NotFound,
+                    format!("failed to resolve host '{host}' of RPC '{rpc_url}' ({e}), DNS failure: check the hostname and the resolver"),
+                ));
+        }
+        Err(_) => {
+            return Err(Error::new(
+                    ErrorKind::TimedOut,
+                    format!("timed out resolving host '{host}' of RPC '{rpc_url}', DNS failure: check the resolver"),
+                ));
+        }
+    };
+    if addrs.is_empty() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("host '{host}' of RPC '{rpc_url}' resolved to no address, DNS failure: check the hostname"),
+        ));
+    }
+
+    match timeout(
+        RPC_PREFLIGHT_TIMEOUT,
+        tokio::net::TcpStream::connect(&addrs[..]),
+    )
+    .await
+    {
+        Ok(Ok(_)) => log::info!("connected to '{host}:{port}' for RPC '{rpc_url}'"),
+        Ok(Err(e)) if e.kind() == ErrorKind::ConnectionRefused => {
+            return Err(Error::new(
+                ErrorKind::ConnectionRefused,
+                format!("connection refused by '{host}:{port}' for RPC '{rpc_url}', check that the node is running and serving HTTP on port {port}"),
+            ));
+        }
+        Ok(Err(e)) => {
+            return Err(Error::new(
+                e.kind(),
+                format!("failed to connect to '{host}:{port}' for RPC '{rpc_url}' ({e}), check the network route and firewall/security group"),
+            ));
+        }
+        Err(_) => {
+            return Err(Error::new(
+                ErrorKind::TimedOut,
+                format!("timed out connecting to '{host}:{port}' for RPC '{rpc_url}', check the firewall/security group"),
+            ));
+        }
+    }
+
+    let result = match timeout(
+        RPC_PREFLIGHT_TIMEOUT,
+        call_json_rpc(
+            rpc_url,
+            &api_paths.info,
+            "info.getNetworkID",
+            serde_json::json!({}),
+            rpc_headers,
+        ),
+    )
+    .await
+    {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => {
+            // keeps the kind, so that only timeouts and rate limits are retried
+            return Err(Error::new(
+                e.kind(),
+                format!("'{host}:{port}' is reachable but RPC '{rpc_url}' failed ({e}), HTTP error: check the URL scheme, the 'info' API, and --query-rpc-header"),
+            ));
+        }
+        Err(_) => {
+            return Err(Error::new(
+                ErrorKind::TimedOut,
+                format!("'{host}:{port}' is reachable but RPC '{rpc_url}' timed out on info.getNetworkID, HTTP error: check the URL scheme"),
+            ));
+        }
+    };
+    result["networkID"]
+        .as_str()
+        .and_then(|id| id.parse::<u32>().ok())
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("unexpected info.getNetworkID result '{result}' from RPC '{rpc_url}'"),
+            )
+        })
+}
+
+pub const DEFAULT_P_CHAIN_API_PATH: &str = "/ext/bc/P";
+pub const DEFAULT_INFO_API_PATH: &str = "/ext/info";
+/// "{blockchain_id}" is replaced with the blockchain Id.
+pub const DEFAULT_EVM_API_PATH: &str = "/ext/bc/{blockchain_id}/rpc";
+
+/// API paths of the RPC calls, for endpoints that do not serve the standard
+/// avalanchego paths (e.g., API gateways routing by a path prefix).
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct ApiPaths {
+    pub p_chain: String,
+    pub info: String,
+    pub evm: String,
+    /// Path prefix in front of the standard P-chain and info paths, appended to
+    /// the base URL of the wallet and avalanche-types clients (and the health
+    /// and admin calls).
+    pub prefix: String,
+}
+
+impl Default for ApiPaths {
+    fn default() -> Self {
+        Self {
+            p_chain: DEFAULT_P_CHAIN_API_PATH.to_string(),
+            info: DEFAULT_INFO_API_PATH.to_string(),
+            evm: DEFAULT_EVM_API_PATH.to_string(),
+            prefix: String::new(),
+        }
+    }
+}
+
+impl ApiPaths {
+    /// Returns the API paths, failing if any is not an absolute path, or if the
+    /// P-chain and info paths are not the standard paths behind the same prefix,
+    /// since the avalanche-types wallet only takes the base URL and appends the
+    /// standard paths (thus cannot honour other rewrites).
+    pub fn new(p_chain: &str, info: &str, evm: &str) -> io::Result<Self> {
+        for (flag, path) in [
+            ("--p-chain-api-path", p_chain),
+            ("--info-api-path", info),
+            ("--evm-api-path", evm),
+        ] {
+            if !path.starts_with('/') || path.contains(char::is_whitespace) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("invalid {flag} '{path}' (must start with '/' with no whitespace)"),
+                ));
+            }
+        }
+        let prefix = p_chain
+            .strip_suffix(DEFAULT_P_CHAIN_API_PATH)
+            .filter(|prefix| info.strip_suffix(DEFAULT_INFO_API_PATH) == Some(*prefix))
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "--p-chain-api-path '{p_chain}' and --info-api-path '{info}' must be '[PREFIX]{DEFAULT_P_CHAIN_API_PATH}' and '[PREFIX]{DEFAULT_INFO_API_PATH}' with the same prefix, as the wallet only supports a path prefix"
+                    ),
+                )
+            })?;
+        Ok(Self {
+            p_chain: p_chain.to_string(),
+            info: info.to_string(),
+            evm: evm.to_string(),
+            prefix: prefix.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Returns the RPC URL with the path prefix, for the wallet and the
+    /// avalanche-types clients that append the standard paths.
+    pub fn base_url(&self, rpc_url: &str) -> String {
+        format!("{}{}", rpc_url.trim_end_matches('/'), self.prefix)
+    }
+
+    /// Returns the health API path.
+    pub fn health(&self) -> String {
+        format!("{}/ext/health", self.prefix)
+    }
+
+    /// Returns the EVM RPC path of the blockchain.
+    pub fn evm(&self, blockchain_id: &ids::Id) -> String {
+        self.evm
+            .replace("{blockchain_id}", &blockchain_id.to_string())
+    }
+}
+
+/// Parses the "Name: Value" RPC headers (e.g., API keys for hosted RPC providers).
+pub fn parse_rpc_headers(headers: &[String]) -> io::Result<Vec<(String, String)>> {
+    let mut parsed = Vec::new();
+    for h in headers.iter() {
+        let (name, value) = h.split_once(':').ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "invalid RPC header '{}' (expected 'Name: Value')",
+                    redact_rpc_header(h)
+                ),
+            )
+        })?;
+        let (name, value) = (name.trim(), value.trim());
+        if reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_err()
+            || reqwest::header::HeaderValue::from_str(value).is_err()
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid RPC header '{}'", redact_rpc_header(h)),
+            ));
+        }
+        parsed.push((name.to_string(), value.to_string()));
+    }
+    Ok(parsed)
+}
+
+/// Redacts the header value, to not leak credentials in logs.
+pub fn redact_rpc_header(header: &str) -> String {
+    match header.split_once(':') {
+        Some((name, _)) => format!("{}: ***", name.trim()),
+        None => String::from("***"),
+    }
+}
+
+/// Calls the JSON-RPC method with the custom headers, and returns its "result".
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/issuing-api-calls>
+pub async fn call_json_rpc(
+    rpc_url: &str,
+    path: &str,
+    method: &str,
+    params: serde_json::Value,
+    rpc_headers: &[(String, String)],
+) -> io::Result<serde_json::Value> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (name, value) in rpc_headers.iter() {
+        headers.insert(
+            reqwest::header::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+            reqwest::header::HeaderValue::from_str(value).unwrap(),
+        );
+    }
+    let cli = reqwest::ClientBuilder::new()
+        .user_agent(env!("CARGO_PKG_NAME"))
+        .default_headers(headers)
+        .timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed ClientBuilder build {}", e),
+            )
+        })?;
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    });
+    let resp = cli
+        .post(format!("{}{path}", rpc_url.trim_end_matches('/')))
+        .header("content-type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| {
+            let kind = if e.is_timeout() {
+                ErrorKind::TimedOut
+            } else if e.is_connect() {
+                ErrorKind::ConnectionRefused
+            } else {
+                ErrorKind::Other
+            };
+            InstallError::RpcUnreachable.error(kind, format!("failed to send {method} {}", e))
+        })?;
+    if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        // "try again later", as EAGAIN
+        return Err(Error::new(
+            ErrorKind::WouldBlock,
+            format!("{method} rate limited by '{rpc_url}' (HTTP 429 Too Many Requests)"),
+        ));
+    }
+    let d = resp.text().await.map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to read {method} response {}", e),
+        )
+    })?;
+
+    let mut v: serde_json::Value = serde_json::from_str(&d).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("failed to parse {method} response '{d}' ({})", e),
+        )
+    })?;
+    if !v["error"].is_null() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!("{method} failed ({})", v["error"]),
+        ));
+    }
+    Ok(v["result"].take())
+}
+
+/// Fetches the BLS proof of possession via "info.getNodeID" from the node's own RPC endpoint.
+/// Returns None if the node does not have a BLS signer (e.g., pre-Durango).
+async fn fetch_node_pop(
+    node_id: &ids::node::Id,
+    node_rpc_url: &str,
+) -> io::Result<Option<key::bls::ProofOfPossession>> {
+    let resp = json_client_info::get_node_id(node_rpc_url)
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed get_node_id '{node_rpc_url}' ({e})"),
+            )
+        })?;
+    let result = resp.result.ok_or_else(|| {
+        Error::new(
+            ErrorKind::Other,
+            format!("no getNodeID result from '{node_rpc_url}'"),
+        )
+    })?;
+    if result.node_id != *node_id {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "node RPC '{node_rpc_url}' is served by '{}', expected '{node_id}'",
+                result.node_id
+            ),
+        ));
+    }
+    Ok(result.node_pop)
+}
+
+/// Normalizes the S3 key prefix by collapsing duplicate slashes and
+/// trimming leading/trailing slashes (e.g., "/foo//bar/" becomes "foo/bar"),
+/// so that "s3::append_slash" always yields well-formed keys.
+pub fn normalize_s3_key_prefix(prefix: &str) -> io::Result<String> {
+    let normalized = prefix
+        .split('/')
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<&str>>()
+        .join("/");
+    if normalized.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid S3 key prefix '{prefix}' (empty after normalization)"),
+        ));
+    }
+    Ok(normalized)
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_normalize_s3_key_prefix --exact --show-output
+#[test]
+fn test_normalize_s3_key_prefix() {
+    assert_eq!(normalize_s3_key_prefix("foo").unwrap(), "foo");
+    assert_eq!(normalize_s3_key_prefix("foo/").unwrap(), "foo");
+    assert_eq!(normalize_s3_key_prefix("/foo").unwrap(), "foo");
+    assert_eq!(normalize_s3_key_prefix("foo//bar").unwrap(), "foo/bar");
+    assert_eq!(normalize_s3_key_prefix("//foo///bar//").unwrap(), "foo/bar");
+    assert_eq!(
+        s3::append_slash(&normalize_s3_key_prefix("foo//bar/").unwrap()),
+        "foo/bar/"
+    );
+    assert!(normalize_s3_key_prefix("").is_err());
+    assert!(normalize_s3_key_prefix("//").is_err());
+}
+
+/// Returns the region of the S3 bucket.
+/// ref. <https://docs.aws.amazon.com/AmazonS3/latest/API/API_GetBucketLocation.html>
+async fn fetch_bucket_region(s3_manager: &s3::Manager, s3_bucket: &str) -> io::Result<String> {
+    let out = s3_manager
+        .cli
+        .get_bucket_location()
+        .bucket(s3_bucket)
+        .send()
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed get_bucket_location for '{s3_bucket}' ({})", e),
+            )
+        })?;
+
+    // empty location constraint means "us-east-1"
+    let region = out
+        .location_constraint()
+        .map(|c| c.as_str().to_string())
+        .unwrap_or_default();
+    if region.is_empty() {
+        Ok(String::from("us-east-1"))
+    } else {
+        Ok(region)
+    }
+}
+
+/// Tag key of the run Id on the uploaded S3 objects.
+pub const S3_TAG_RUN_ID: &str = "avalancheup-run-id";
+/// Tag key of the chain name on the uploaded S3 objects.
+pub const S3_TAG_CHAIN_NAME: &str = "avalancheup-chain-name";
+
+/// Returns the tags of the uploaded S3 objects (sorted by the key): the
+/// user-defined tags with the run Id and chain name, within the S3 limits.
+/// ref. <https://docs.aws.amazon.com/AmazonS3/latest/userguide/object-tagging.html>
+pub fn s3_object_tags(
+    user_tags: &HashMap<String, String>,
+    run_id: &str,
+    chain_name: &str,
+) -> io::Result<Vec<(String, String)>> {
+    const MAX_TAGS: usize = 10;
+    const MAX_KEY_LEN: usize = 128;
+    const MAX_VALUE_LEN: usize = 256;
+
+    let mut tags = BTreeMap::new();
+    for (k, v) in user_tags.iter() {
+        if k == S3_TAG_RUN_ID || k == S3_TAG_CHAIN_NAME {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("--s3-object-tags key '{k}' is reserved"),
+            ));
+        }
+        if k.is_empty() || k.len() > MAX_KEY_LEN || k.starts_with("aws:") {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid --s3-object-tags key '{k}' (1 to {MAX_KEY_LEN} characters, no 'aws:' prefix)"),
+            ));
+        }
+        if v.len() > MAX_VALUE_LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("--s3-object-tags value for '{k}' exceeds {MAX_VALUE_LEN} characters"),
+            ));
+        }
+        tags.insert(k.clone(), v.clone());
+    }
+    tags.insert(S3_TAG_RUN_ID.to_string(), run_id.to_string());
+    tags.insert(S3_TAG_CHAIN_NAME.to_string(), chain_name.to_string());
+    if tags.len() > MAX_TAGS {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "{} S3 object tags exceed the limit of {MAX_TAGS} (at most {} in --s3-object-tags)",
+                tags.len(),
+                MAX_TAGS - 2
+            ),
+        ));
+    }
+    Ok(tags.into_iter().collect())
+}
+
+/// Returns the tags in the URL query format of the "x-amz-tagging" header.
+/// ref. <https://docs.aws.amazon.com/AmazonS3/latest/API/API_PutObject.html>
+pub fn s3_tagging_query(tags: &[(String, String)]) -> String {
+    form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(tags)
+        .finish()
+}
+
+/// Uploads the local file to S3 with the tags. With "--s3-object-tags"
+/// ("require_tagging"), the tags are set in the same request so that the object
+/// is never untagged, and the upload fails without "s3:PutObjectTagging".
+/// Otherwise, the default tags (run Id and chain name) are set after the upload,
+/// only logging a failure (e.g., a role without "s3:PutObjectTagging").
+async fn upload_s3_object(
+    s3_manager: &s3::Manager,
+    local_path: &str,
+    s3_bucket: &str,
+    s3_key: &str,
+    tags: &[(String, String)],
+    require_tagging: bool,
+) -> io::Result<()> {
+    if !require_tagging {
+        s3_manager
+            .put_object(local_path, s3_bucket, s3_key)
+            .await
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!(
+                        "failed put_object '{local_path}' to 's3://{s3_bucket}/{s3_key}' ({e})"
+                    ),
+                )
+            })?;
+        if let Err(e) = tag_s3_object(s3_manager, s3_bucket, s3_key, tags).await {
+            log::warn!("skipped tagging the uploaded object ({e})");
+        }
+        return Ok(());
+    }
+
+    let body = ByteStream::from_path(Path::new(local_path))
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to read '{local_path}' ({})", e),
+            )
+        })?;
+    s3_manager
+        .cli
+        .put_object()
+        .bucket(s3_bucket)
+        .key(s3_key)
+        .body(body)
+        .tagging(s3_tagging_query(tags))
+        .send()
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!(
+                    "failed put_object with tagging for 's3://{s3_bucket}/{s3_key}' ({})",
+                    e
+                ),
+            )
+        })?;
+    log::info!(
+        "uploaded '{local_path}' to 's3://{s3_bucket}/{s3_key}' with {} tag(s)",
+        tags.len()
+    );
+    Ok(())
+}
+
+/// Replaces the tags of the uploaded S3 object.
+/// ref. <https://docs.aws.amazon.com/AmazonS3/latest/API/API_PutObjectTagging.html>
+async fn tag_s3_object(
+    s3_manager: &s3::Manager,
+    s3_bucket: &str,
+    s3_key: &str,
+    tags: &[(String, String)],
+) -> io::Result<()> {
+    let tag_set = tags
+        .iter()
+        .map(|(k, v)| Tag::builder().key(k).value(v).build())
+        .collect();
+    s3_manager
+        .cli
+        .put_object_tagging()
+        .bucket(s3_bucket)
+        .key(s3_key)
+        .tagging(Tagging::builder().set_tag_set(Some(tag_set)).build())
+        .send()
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!(
+                    "failed put_object_tagging for 's3://{s3_bucket}/{s3_key}' ({})",
+                    e
+                ),
+            )
+        })?;
+    log::info!(
+        "tagged 's3://{s3_bucket}/{s3_key}' with {} tag(s)",
+        tags.len()
+    );
+    Ok(())
+}
+
+/// Returns the size of the S3 object, or an error if the object does not exist.
+/// ref. <https://docs.aws.amazon.com/AmazonS3/latest/API/API_HeadObject.html>
+async fn check_s3_object(
+    s3_manager: &s3::Manager,
+    s3_bucket: &str,
+    s3_key: &str,
+) -> io::Result<i64> {
+    let out = s3_manager
+        .cli
+        .head_object()
+        .bucket(s3_bucket)
+        .key(s3_key)
+        .send()
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("failed head_object for 's3://{s3_bucket}/{s3_key}' ({})", e),
+            )
+        })?;
+    Ok(out.content_length())
+}
+
+/// Resolved configuration for "--print-env" (never includes the key itself).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ResolvedEnv {
+    network_id: u32,
+    network_name: String,
+    chain_rpc_url: String,
+    p_chain_rpc_url: String,
+    tx_submit_rpc_url: String,
+    s3_region: String,
+    s3_bucket: String,
+    s3_key_prefix: String,
+    ssm_docs: HashMap<String, String>,
+    ssm_doc_version: String,
+    aws_profile_name: String,
+    aws_identity: sts::Identity,
+    key_source: String,
+    p_chain_address: String,
+    vm_id: String,
+    vm_id_source: String,
+}
+
+/// avalanchego config snippet to persist the subnet/chain installed by this run,
+/// to be merged into each node's static config.
+/// ref. <https://docs.avax.network/nodes/configure/avalanchego-config-flags>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct NodeConfig {
+    pub track_subnets: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnet_config_dir: Option<String>,
+    /// Expected subnet config file path ("{subnet-config-dir}/{subnet Id}.json").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnet_config_file: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_config_dir: Option<String>,
+    /// Expected chain config file path ("{chain-config-dir}/{blockchain Id}/{file name}").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_config_file: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_aliases_file: Option<String>,
+    /// Contents of the "chain-aliases-file" (blockchain Id to aliases).
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub chain_aliases: BTreeMap<String, Vec<String>>,
+}
+
+/// Builds the avalanchego config snippet from the Ids created by this run.
+pub fn build_node_config(
+    subnet_id: &ids::Id,
+    blockchain_id: &ids::Id,
+    subnet_config_remote_dir: &str,
+    chain_config_remote_dir: &str,
+    chain_config_remote_filename: &str,
+    chain_aliases_remote_path: &str,
+    chain_alias: &str,
+) -> NodeConfig {
+    let (subnet_config_dir, subnet_config_file) = if subnet_config_remote_dir.is_empty() {
+        (None, None)
+    } else {
+        (
+            Some(subnet_config_remote_dir.trim_end_matches('/').to_string()),
+            Some(format!(
+                "{}{subnet_id}.json",
+                s3::append_slash(subnet_config_remote_dir)
+            )),
+        )
+    };
+    let (chain_config_dir, chain_config_file) = if chain_config_remote_dir.is_empty() {
+        (None, None)
+    } else {
+        (
+            Some(chain_config_remote_dir.trim_end_matches('/').to_string()),
+            Some(format!(
+                "{}{blockchain_id}/{chain_config_remote_filename}",
+                s3::append_slash(chain_config_remote_dir)
+            )),
+        )
+    };
+
+    let mut chain_aliases = BTreeMap::new();
+    let chain_aliases_file = if chain_alias.is_empty() {
+        None
+    } else {
+        chain_aliases.insert(blockchain_id.to_string(), vec![chain_alias.to_string()]);
+        Some(chain_aliases_remote_path.to_string())
+    };
+
+    NodeConfig {
+        track_subnets: subnet_id.to_string(),
+        subnet_config_dir,
+        subnet_config_file,
+        chain_config_dir,
+        chain_config_file,
+        chain_aliases_file,
+        chain_aliases,
+    }
+}
+
+/// Writes the config snippet as JSON if the path ends with ".json", otherwise as YAML.
+fn write_node_config(node_config: &NodeConfig, file_path: &str) -> io::Result<()> {
+    let d = if file_path.ends_with(".json") {
+        serde_json::to_string_pretty(node_config).map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to encode node config to JSON ({})", e),
+            )
+        })?
+    } else {
+        serde_yaml::to_string(node_config).map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to encode node config to YAML ({})", e),
+            )
+        })?
+    };
+    if let Some(parent) = Path::new(file_path).parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+    fs::write(file_path, d)
+}
+
+/// Returns the "STAKE_ASSET_SYMBOL" and "STAKING_DECIMALS" args shared by the
+/// subcommands that convert or display staking amounts (see [`StakingAsset`]).
+pub fn staking_asset_args() -> [Arg; 2] {
+    [
+        Arg::new("STAKE_ASSET_SYMBOL")
+            .long("stake-asset-symbol")
+            .help("Sets the staking asset symbol to label balances and amounts with on custom networks (empty then AVAX)")
+            .required(false)
+            .num_args(1),
+        Arg::new("STAKING_DECIMALS")
+            .long("staking-decimals")
+            .visible_alias("stake-asset-decimals")
+            .help("Sets the number of decimals of the staking asset on the P-chain, used for the staking amount conversion and display")
+            .required(false)
+            .num_args(1)
+            .value_parser(value_parser!(u32))
+            .default_value("9"),
+    ]
+}
+
+/// Denomination of the staking asset on the P-chain (defaults to AVAX with 9 decimals),
+/// for custom networks forked from avalanchego with a renamed or rescaled staking token.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StakingAsset {
+    pub asset_id: Option<ids::Id>,
+    /// Label for the amounts (empty then AVAX, or the asset Id if set).
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+impl StakingAsset {
+    pub fn new(asset_id: &str, symbol: &str, decimals: u32) -> io::Result<Self> {
+        if symbol.chars().any(char::is_whitespace) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid staking asset symbol '{symbol}' (must not contain whitespace)"),
+            ));
+        }
+        // 10^decimals must fit in u64 for the staking amount in base units
+        if decimals > 18 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("staking decimals {decimals} too large (must be <= 18)"),
+            ));
+        }
+        let asset_id = if asset_id.is_empty() {
+            None
+        } else {
+            Some(ids::Id::from_str(asset_id).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("invalid staking asset id '{asset_id}' ({})", e),
+                )
+            })?)
+        };
+        Ok(Self {
+            asset_id,
+            symbol: symbol.to_string(),
+            decimals,
+        })
+    }
+
+    pub fn symbol(&self) -> String {
+        if !self.symbol.is_empty() {
+            return self.symbol.clone();
+        }
+        match &self.asset_id {
+            Some(asset_id) => format!("(asset {asset_id})"),
+            None => String::from("AVAX"),
+        }
+    }
+
+    pub fn base_symbol(&self) -> String {
+        if self.symbol() == "AVAX" && self.decimals == 9 {
+            return String::from("nAVAX");
+        }
+        format!("{} base units", self.symbol())
+    }
+
+    /// Converts the amount in the staking asset to its smallest denomination.
+    pub fn to_base_units(&self, amount: u64) -> primitive_types::U256 {
+        primitive_types::U256::from(amount)
+            * primitive_types::U256::from(10).pow(self.decimals.into())
+    }
+
+    /// Converts the amount in the smallest denomination to the staking asset (truncated).
+    pub fn to_display_units(&self, base_units: primitive_types::U256) -> primitive_types::U256 {
+        base_units / primitive_types::U256::from(10).pow(self.decimals.into())
+    }
+}
+
+/// Number of attempts to reach the RPC endpoint before giving up (doubles the wait on each retry).
+const RPC_RETRIES: u32 = 5;
+
+/// Fetches the network Id with retries and backoff, since it is the very first
+/// network call and a transient failure would otherwise abort the whole run.
+/// Fails right away on errors that retries cannot fix (e.g., invalid URL, unknown host).
+pub async fn fetch_network_id(
+    rpc_url: &str,
+    rpc_headers: &[(String, String)],
+    api_paths: &ApiPaths,
+) -> io::Result<u32> {
+    let mut wait = Duration::from_secs(1);
+    let mut attempt = 1;
+    loop {
+        match check_rpc_reachable(rpc_url, rpc_headers, api_paths).await {
+            Ok(network_id) => return Ok(network_id),
+            Err(e) if attempt < RPC_RETRIES && is_transient_rpc_error(e.kind()) => {
+                log::warn!(
+                    "failed to fetch network Id from '{rpc_url}' (attempt {attempt}/{RPC_RETRIES}, {e}), retrying in {:?}",
+                    wait
+                );
+            }
+            Err(e) if is_transient_rpc_error(e.kind()) => {
+                return Err(InstallError::RpcUnreachable
+                    .error(e.kind(), format!("{e} (after {RPC_RETRIES} attempts)")));
+            }
+            Err(e) => return Err(e),
+        }
+        sleep(wait).await;
+        wait *= 2;
+        attempt += 1;
+    }
+}
+
+/// Returns true if the RPC error kind may go away on retry (timeouts, refused
+/// connections while the node restarts, rate limits as "WouldBlock").
+/// Other errors (e.g., RPC errors in the response) are not retried.
+pub fn is_transient_rpc_error(kind: ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::TimedOut
+            | ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::WouldBlock
+    )
+}
+
+/// Loads the hotkey, failing with an error that does not leak the key itself.
+/// The key is decoded into a buffer scrubbed on drop (unlike "Key::from_hex").
+pub fn load_private_key(key: &str) -> io::Result<key::secp256k1::private_key::Key> {
+    let invalid_key = |e: String| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid key (expected a private key in hex format) ({e})"),
+        )
+    };
+    let raw = Zeroizing::new(
+        hex::decode(key.trim_start_matches("0x")).map_err(|e| invalid_key(e.to_string()))?,
+    );
+    key::secp256k1::private_key::Key::from_bytes(&raw).map_err(|e| invalid_key(e.to_string()))
+}
+
+/// Builds the wallet with retries and backoff, since the builder fetches
+/// the network information from the RPC endpoint.
+pub async fn build_wallet(
+    chain_rpc_url: &str,
+    priv_key: &key::secp256k1::private_key::Key,
+) -> io::Result<wallet::Wallet<key::secp256k1::private_key::Key>> {
+    let mut wait = Duration::from_secs(1);
+    let mut last_err = None;
+    for attempt in 1..=RPC_RETRIES {
+        match wallet::Builder::new(priv_key)
+            .base_http_url(chain_rpc_url.to_string())
+            .build()
+            .await
+        {
+            Ok(w) => return Ok(w),
+            Err(e) => {
+                log::warn!(
+                    "failed to build wallet with '{chain_rpc_url}' (attempt {attempt}/{RPC_RETRIES}, {e})"
+                );
+                last_err = Some(e);
+            }
+        }
+        // no wait after the last attempt
+        if attempt < RPC_RETRIES {
+            log::info!("retrying wallet build in {:?}", wait);
+            sleep(wait).await;
+            wait *= 2;
+        }
+    }
+    Err(InstallError::RpcUnreachable.error(
+        ErrorKind::Other,
+        format!(
+            "failed to build wallet with RPC '{chain_rpc_url}' after {RPC_RETRIES} attempts (last error: {})",
+            last_err.unwrap()
+        ),
+    ))
+}
+
+/// Fetches the wallet's P-chain balance with retries and backoff.
+/// If "platform.getBalance" keeps failing (e.g., rate limited or disabled on shared RPCs),
+/// falls back to summing the unlocked outputs of the wallet's UTXO set.
+pub async fn fetch_p_chain_balance(
+    chain_rpc_url: &str,
+    wallet_to_spend: &wallet::Wallet<key::secp256k1::private_key::Key>,
+    rpc_headers: &[(String, String)],
+    api_paths: &ApiPaths,
+) -> io::Result<u64> {
+    let mut wait = Duration::from_secs(1);
+    let mut last_err = None;
+    let mut rate_limited = false;
+    for attempt in 1..=RPC_RETRIES {
+        match wallet_to_spend.p().balance().await {
+            Ok(balance) => return Ok(balance),
+            Err(e) => {
+                rate_limited = rate_limited || is_rate_limited_error(&e.to_string());
+                log::warn!(
+                    "failed to fetch P-chain balance from '{chain_rpc_url}' (attempt {attempt}/{RPC_RETRIES}, {e})"
+                );
+                last_err = Some(e);
+            }
+        }
+        // no wait after the last attempt
+        if attempt < RPC_RETRIES {
+            log::info!("retrying P-chain balance fetch in {:?}", wait);
+            sleep(wait).await;
+            wait *= 2;
+        }
+    }
+    let last_err = last_err.unwrap();
+
+    log::warn!(
+        "falling back to the UTXO set of '{}' for the P-chain balance",
+        wallet_to_spend.p_address
+    );
+    match fetch_p_chain_balance_from_utxos(
+        chain_rpc_url,
+        &wallet_to_spend.p_address,
+        &wallet_to_spend.avax_asset_id,
+        rpc_headers,
+        api_paths,
+    )
+    .await
+    {
+        Ok((balance, utxos)) => {
+            if utxos == 0 {
+                log::warn!(
+                    "'{}' has no UTXOs on the P-chain (fund the address first)",
+                    wallet_to_spend.p_address
+                );
+            }
+            log::info!("computed P-chain balance {balance} from {utxos} UTXO(s)");
+            Ok(balance)
+        }
+        Err(e) => {
+            rate_limited = rate_limited || is_rate_limited_error(&e.to_string());
+            let hint = if rate_limited {
+                ", rate limited by the RPC endpoint, retry later or use a dedicated endpoint via --p-chain-rpc-url"
+            } else {
+                ""
+            };
+            Err(Error::new(
+                ErrorKind::Other,
+                format!(
+                    "failed to fetch P-chain balance from '{chain_rpc_url}' after {RPC_RETRIES} attempts and the UTXO fallback{hint} (last balance error: {last_err}, UTXO fallback error: {e})"
+                ),
+            ))
+        }
+    }
+}
+
+/// Returns true if the RPC error message indicates rate limiting.
+pub fn is_rate_limited_error(msg: &str) -> bool {
+    let msg = msg.to_lowercase();
+    msg.contains("429") || msg.contains("too many requests") || msg.contains("rate limit")
+}
+
+/// Fails if the remote directory is not absolute, since the SSM command runs the
+/// installer from the agent's working directory, where avalanchego would not find
+/// the relative config directory.
+pub fn check_remote_dir(flag: &str, dir: &str) -> io::Result<()> {
+    if dir.is_empty() || dir.starts_with('/') {
+        return Ok(());
+    }
+    Err(Error::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "{flag} '{dir}' is not an absolute path, set the directory avalanchego reads on the node (e.g., '/{}')",
+            dir.trim_start_matches("./")
+        ),
+    ))
+}
+
+/// Fails if the remote file name is not a bare file name, since it is joined
+/// with the remote directory on the node.
+pub fn check_remote_filename(flag: &str, file_name: &str) -> io::Result<()> {
+    if file_name.is_empty() || file_name == "." || file_name == ".." || file_name.contains('/') {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "{flag} '{file_name}' must be a bare file name without '/' (e.g., 'config.json')"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Target instances of a region, and the SSM document to send them.
+struct SsmBatch {
+    region: String,
+    ssm_doc: String,
+    ssm_doc_version: Option<String>,
+    instance_ids: Vec<String>,
+}
+
+/// Sends the SSM command with the avalanched args to all target instances
+/// (grouped by region), and polls the command status for each instance.
+/// Up to "--region-concurrency" regions are processed at once, and all regions
+/// run to completion so that the failures of every region are reported together.
+/// Records the command Id and its target instance Ids of each command sent in
+/// "sent_commands", including those of the failed regions.
+#[allow(clippy::too_many_arguments)]
+async fn send_ssm_commands(
+    opts: &Flags,
+    ssm_managers: &HashMap<String, ssm::Manager>,
+    credentials_provider: &Option<SharedCredentialsProvider>,
+    ui: Ui,
+    region_to_instance_ids: &HashMap<String, Vec<String>>,
+    region_to_ssm_doc: &HashMap<String, String>,
+    ssm_doc_version: &Option<String>,
+    avalanched_args: &str,
+    sent_commands: &mut deployer::SentCommands,
+) -> io::Result<()> {
+    let mut batches = Vec::new();
+    for (region, instance_ids) in region_to_instance_ids.iter() {
+        let ssm_doc = region_to_ssm_doc.get(region).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("--ssm-docs does not have the document name for the region '{region}'"),
+            )
+        })?;
+
+        if opts.print_ssm_args {
+            print_ssm_args(
+                ui,
+                region,
+                ssm_doc,
+                ssm_doc_version,
+                instance_ids,
+                avalanched_args,
+            )?;
+            continue;
+        }
+
+        let regional_ssm_manager = match ssm_managers.get(region) {
+            Some(ssm_manager) => ssm_manager.clone(),
+            None => {
+                let shared_config = load_aws_config(
+                    region,
+                    &opts.profile_name,
+                    Duration::from_secs(30),
+                    credentials_provider,
+                )
+                .await;
+                ssm::Manager::new(&shared_config)
+            }
+        };
+        batches.push((
+            regional_ssm_manager,
+            SsmBatch {
+                region: region.clone(),
+                ssm_doc: ssm_doc.clone(),
+                ssm_doc_version: ssm_doc_version.clone(),
+                instance_ids: instance_ids.clone(),
+            },
+        ));
+    }
+    if batches.is_empty() {
+        return Ok(());
+    }
+
+    // bounds the regions processed at once, to stay under the account-wide API limits
+    let region_permits = Arc::new(Semaphore::new(opts.region_concurrency as usize));
+    let opts = Arc::new(opts.clone());
+    let avalanched_args: Arc<str> = Arc::from(avalanched_args);
+    let mut tasks = JoinSet::new();
+    for (ssm_manager, batch) in batches {
+        let region_permits = region_permits.clone();
+        let opts = opts.clone();
+        let avalanched_args = avalanched_args.clone();
+        tasks.spawn(async move {
+            // never closed, thus never fails
+            let _permit = region_permits.acquire_owned().await.unwrap();
+            let region = batch.region.clone();
+            let instances = batch.instance_ids.len();
+            let start = Instant::now();
+            let mut commands = Vec::new();
+            let result = send_regional_ssm_commands(
+                &opts,
+                &ssm_manager,
+                ui,
+                &batch,
+                &avalanched_args,
+                &mut commands,
+            )
+            .await;
+            (region, instances, start.elapsed(), commands, result)
+        });
+    }
+
+    let mut region_results = BTreeMap::new();
+    while let Some(joined) = tasks.join_next().await {
+        let (region, instances, elapsed, commands, result) = joined.map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to join the regional SSM task ({})", e),
+            )
+        })?;
+        region_results.insert(region, (instances, elapsed, commands, result));
+    }
+
+    let mut failures = Vec::new();
+    for (region, (instances, elapsed, commands, result)) in region_results {
+        match result {
+            Ok(()) => {
+                log::info!(
+                    "region '{region}': sent {} SSM command(s) to {instances} instance(s) in {:?}",
+                    commands.len(),
+                    elapsed
+                );
+            }
+            Err(e) => {
+                log::warn!(
+                    "region '{region}': SSM failed on {instances} instance(s) in {:?} after {} command(s) ({e})",
+                    elapsed,
+                    commands.len()
+                );
+                failures.push((region, e));
+            }
+        }
+        sent_commands.extend(commands);
+    }
+    aggregate_region_errors(failures)
+}
+
+/// Sends the SSM command to the instances of a region, and polls the command
+/// status for each instance. The instances whose invocation failed transiently
+/// are sent a new command, up to "--ssm-max-attempts" commands in total.
+/// Each command is recorded in "sent_commands" once sent.
+async fn send_regional_ssm_commands(
+    opts: &Flags,
+    ssm_manager: &ssm::Manager,
+    ui: Ui,
+    batch: &SsmBatch,
+    avalanched_args: &str,
+    sent_commands: &mut deployer::SentCommands,
+) -> io::Result<()> {
+    let region = &batch.region;
+    let mut pending_instance_ids = batch.instance_ids.clone();
+    for attempt in 1..=opts.ssm_max_attempts {
+        log::info!(
+            "sending SSM commands for the region '{region}' with instances {:?} (document '{}', version '{}', attempt {attempt}/{})",
+            pending_instance_ids,
+            batch.ssm_doc,
+            batch.ssm_doc_version.as_deref().unwrap_or("$DEFAULT"),
+            opts.ssm_max_attempts,
+        );
+
+        // ref. <https://docs.aws.amazon.com/systems-manager/latest/APIReference/API_SendCommand.html>
+        let ssm_output = ssm_manager
+            .cli
+            .send_command()
+            .document_name(batch.ssm_doc.clone())
+            .set_document_version(batch.ssm_doc_version.clone())
+            .set_instance_ids(Some(pending_instance_ids.clone()))
+            .parameters("avalanchedArgs", vec![avalanched_args.to_string()])
+            .output_s3_region(opts.s3_region.clone())
+            .output_s3_bucket_name(opts.s3_bucket.clone())
+            .output_s3_key_prefix(format!(
+                "{}ssm-output-logs",
+                s3::append_slash(&opts.s3_key_prefix)
+            ))
+            .send()
+            .await
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!("failed send_command in the region '{region}' ({:?})", e),
+                )
+            })?;
+        let ssm_command_id = ssm_output
+            .command()
+            .and_then(|c| c.command_id())
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!("send_command in the region '{region}' returned no command Id"),
+                )
+            })?
+            .to_string();
+        log::info!("sent SSM command {}", ssm_command_id);
+        sent_commands.push((ssm_command_id.clone(), pending_instance_ids.clone()));
+        sleep(Duration::from_secs(30)).await;
+
+        ui.print(
+            Color::Green,
+            format!(
+                "\n\n\nSTEP: checking the status of SSM command in the region '{region}'...\n\n"
+            ),
+        )?;
+        let mut transient_failures = Vec::new();
+        for instance_id in pending_instance_ids.iter() {
+            let _span = telemetry::span(
+                "ssm poll",
+                &[
+                    ("region", region.clone()),
+                    ("instance_id", instance_id.clone()),
+                    ("ssm_command_id", ssm_command_id.clone()),
+                ],
+            );
+            match ssm_manager
+                .poll_command(
+                    &ssm_command_id,
+                    instance_id,
+                    CommandInvocationStatus::Success,
+                    Duration::from_secs(300),
+                    Duration::from_secs(5),
+                )
+                .await
+            {
+                Ok(status) => {
+                    log::info!("status {:?} for instance id {}", status, instance_id);
+                }
+                Err(e) => {
+                    // the poll fails on the terminal states other than "Success",
+                    // so fetch the invocation to tell why
+                    let invocation = ssm_manager
+                        .cli
+                        .get_command_invocation()
+                        .command_id(&ssm_command_id)
+                        .instance_id(instance_id)
+                        .send()
+                        .await
+                        .map_err(|e| {
+                            Error::new(
+                                ErrorKind::Other,
+                                format!(
+                                    "failed get_command_invocation for '{instance_id}' ({:?})",
+                                    e
+                                ),
+                            )
+                        })?;
+                    let status_details = invocation.status_details().unwrap_or_default();
+                    let response_code = invocation.response_code();
+                    if !is_transient_ssm_failure(status_details, response_code) {
+                        let msg = format!(
+                            "SSM command '{ssm_command_id}' failed on '{instance_id}' ({e}, status '{status_details}', exit code {response_code})"
+                        );
+                        // still running when the poll gave up, or timed out by the agent
+                        if matches!(
+                            status_details,
+                            "Pending" | "InProgress" | "Delayed" | "ExecutionTimedOut"
+                        ) {
+                            return Err(InstallError::SsmTimeout.error(ErrorKind::TimedOut, msg));
+                        }
+                        return Err(Error::new(ErrorKind::Other, msg));
+                    }
+                    log::warn!(
+                        "SSM command '{ssm_command_id}' failed transiently on '{instance_id}' (status '{status_details}', exit code {response_code})"
+                    );
+                    transient_failures.push(instance_id.clone());
+                }
+            }
+        }
+        if transient_failures.is_empty() {
+            break;
+        }
+        if attempt == opts.ssm_max_attempts {
+            return Err(InstallError::SsmTimeout.error(
+                ErrorKind::TimedOut,
+                format!(
+                    "SSM command failed transiently on {:?} after {attempt} attempt(s) (retry with a higher '--ssm-max-attempts')",
+                    transient_failures
+                ),
+            ));
+        }
+        pending_instance_ids = transient_failures;
+    }
+    sleep(Duration::from_secs(5)).await;
+
+    Ok(())
+}
+
+/// Returns the failure of each region as one error, with the class of the
+/// most severe regional failure (e.g., a script error over an "SsmTimeout",
+/// since retrying cannot fix it), regardless of the region order.
+pub fn aggregate_region_errors(failures: Vec<(String, io::Error)>) -> io::Result<()> {
+    let most_severe = match failures
+        .iter()
+        .map(|(_, e)| e)
+        .max_by_key(|e| region_error_severity(e))
+    {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+    let regional_msgs: Vec<String> = failures
+        .iter()
+        .map(|(region, e)| format!("region '{region}': {e}"))
+        .collect();
+    let msg = if regional_msgs.len() == 1 {
+        regional_msgs[0].clone()
+    } else {
+        format!(
+            "SSM failed in {} region(s): {}",
+            regional_msgs.len(),
+            regional_msgs.join("; ")
+        )
+    };
+    Err(match InstallError::classify(most_severe) {
+        Some(class) => class.error(most_severe.kind(), msg),
+        None => Error::new(most_severe.kind(), msg),
+    })
+}
+
+/// Returns the severity of the regional failure: the failures that a retry
+/// cannot fix (e.g., invalid inputs or a script error) outrank the retryable ones.
+fn region_error_severity(e: &io::Error) -> u8 {
+    match InstallError::classify(e) {
+        Some(InstallError::Interrupted) => 4,
+        Some(InstallError::Validation) | Some(InstallError::InsufficientBalance) => 3,
+        None => 2,
+        Some(InstallError::RpcUnreachable) => 1,
+        Some(InstallError::SsmTimeout) => 0,
+    }
+}
+
+/// Returns true if the failed SSM command invocation is likely to succeed when
+/// sent again: the command did not reach the instance, or the agent was
+/// interrupted (e.g., restarted) before the script exited on its own.
+/// Script errors (a non-zero exit code) are deterministic, thus not retried.
+/// ref. <https://docs.aws.amazon.com/systems-manager/latest/userguide/monitor-commands.html>
+pub fn is_transient_ssm_failure(status_details: &str, response_code: i32) -> bool {
+    match status_details {
+        "Undeliverable" | "DeliveryTimedOut" | "Terminated" => true,
+        // -1 if the script did not run to the exit,
+        // 137 (SIGKILL) and 143 (SIGTERM) if the agent stopped the script
+        "Failed" | "ExecutionTimedOut" => matches!(response_code, -1 | 137 | 143),
+        _ => false,
+    }
+}
+
+/// Checks that every region of the target instances has the SSM document,
+/// so that multi-region installs do not fail after the on-chain operations.
+pub fn check_ssm_docs(
+    region_to_instance_ids: &HashMap<String, Vec<String>>,
+    region_to_ssm_doc: &HashMap<String, String>,
+) -> io::Result<()> {
+    let mut missing_regions: Vec<&str> = region_to_instance_ids
+        .keys()
+        .filter(|region| !region_to_ssm_doc.contains_key(*region))
+        .map(|region| region.as_str())
+        .collect();
+    if missing_regions.is_empty() {
+        return Ok(());
+    }
+    missing_regions.sort();
+    Err(Error::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "--ssm-docs does not have the document name for the region(s) {:?} of the target nodes",
+            missing_regions
+        ),
+    ))
+}
+
+/// Checks that "--avalanchego-config-remote-path" is an absolute path to a JSON file.
+pub fn check_avalanchego_config_remote_path(path: &str) -> io::Result<()> {
+    if !path.starts_with('/') {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("--avalanchego-config-remote-path '{path}' is not an absolute path"),
+        ));
+    }
+    if Path::new(path).extension().and_then(|ext| ext.to_str()) != Some("json") {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("--avalanchego-config-remote-path '{path}' is not a JSON file (expected '.json' extension)"),
+        ));
+    }
+    Ok(())
+}
+
+/// SSM document of AWS to run the shell commands on the instances.
+/// ref. <https://docs.aws.amazon.com/systems-manager/latest/userguide/documents-command-ssm-plugin-reference.html#aws-runShellScript>
+const AWS_RUN_SHELL_SCRIPT: &str = "AWS-RunShellScript";
+
+/// Exit code of the avalanchego config check if the file does not exist.
+const AVALANCHEGO_CONFIG_MISSING_EXIT_CODE: i32 = 3;
+/// Exit code of the avalanchego config check if the file is not valid JSON.
+const AVALANCHEGO_CONFIG_MALFORMED_EXIT_CODE: i32 = 4;
+/// Exit code of the avalanchego config check if the file exists, but neither
+/// python3 nor jq is on the instance to check the JSON.
+const AVALANCHEGO_CONFIG_UNVERIFIED_EXIT_CODE: i32 = 5;
+
+/// Returns the shell commands to check that the avalanchego config exists and is
+/// valid JSON on the instance (with python3, or jq as the fallback).
+pub fn avalanchego_config_check_commands(path: &str) -> Vec<String> {
+    let quoted = format!("'{}'", path.replace('\'', "'\\''"));
+    vec![
+        format!("test -f {quoted} || exit {AVALANCHEGO_CONFIG_MISSING_EXIT_CODE}"),
+        format!(
+            "if command -v python3 >/dev/null; then python3 -c 'import json, sys; json.load(open(sys.argv[1]))' {quoted} || exit {AVALANCHEGO_CONFIG_MALFORMED_EXIT_CODE}; \
+elif command -v jq >/dev/null; then jq empty {quoted} || exit {AVALANCHEGO_CONFIG_MALFORMED_EXIT_CODE}; \
+else exit {AVALANCHEGO_CONFIG_UNVERIFIED_EXIT_CODE}; fi"
+        ),
+    ]
+}
+
+/// Returns why the avalanchego config check failed on an instance.
+fn avalanchego_config_check_failure(status_details: &str, response_code: i32) -> String {
+    match response_code {
+        AVALANCHEGO_CONFIG_MISSING_EXIT_CODE => String::from("missing"),
+        AVALANCHEGO_CONFIG_MALFORMED_EXIT_CODE => String::from("malformed JSON"),
+        _ => format!("check failed (status '{status_details}', exit code {response_code})"),
+    }
+}
+
+/// Checks that the avalanchego config exists and is valid JSON on every target
+/// instance, since the nodes would not track the subnet otherwise.
+/// Reports all the instances where it is missing or malformed, and warns about
+/// the instances where the JSON could not be checked (no python3 or jq).
+async fn check_avalanchego_configs(
+    ssm_managers: &HashMap<String, ssm::Manager>,
+    region_to_instance_ids: &HashMap<String, Vec<String>>,
+    avalanchego_config_remote_path: &str,
+) -> io::Result<()> {
+    let commands = avalanchego_config_check_commands(avalanchego_config_remote_path);
+    let mut regions: Vec<&String> = region_to_instance_ids.keys().collect();
+    regions.sort();
+
+    let mut failures = Vec::new();
+    let mut unverified = Vec::new();
+    for region in regions {
+        let instance_ids = &region_to_instance_ids[region];
+        let ssm_manager = ssm_managers.get(region).ok_or_else(|| {
+            Error::new(
+                ErrorKind::Other,
+                format!("no SSM manager for the region '{region}'"),
+            )
+        })?;
+
+        // ref. <https://docs.aws.amazon.com/systems-manager/latest/APIReference/API_SendCommand.html>
+        let ssm_output = ssm_manager
+            .cli
+            .send_command()
+            .document_name(AWS_RUN_SHELL_SCRIPT)
+            .set_instance_ids(Some(instance_ids.clone()))
+            .parameters("commands", commands.clone())
+            .send()
+            .await
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!(
+                        "failed send_command '{AWS_RUN_SHELL_SCRIPT}' in the region '{region}' ({:?})",
+                        e
+                    ),
+                )
+            })?;
+        let ssm_command_id = ssm_output
+            .command()
+            .and_then(|c| c.command_id())
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!("send_command in the region '{region}' returned no command Id"),
+                )
+            })?
+            .to_string();
+        log::info!(
+            "sent the avalanchego config check {ssm_command_id} to {:?} in the region '{region}'",
+            instance_ids
+        );
+        sleep(Duration::from_secs(5)).await;
+
+        for instance_id in instance_ids.iter() {
+            if ssm_manager
+                .poll_command(
+                    &ssm_command_id,
+                    instance_id,
+                    CommandInvocationStatus::Success,
+                    Duration::from_secs(120),
+                    Duration::from_secs(3),
+                )
+                .await
+                .is_ok()
+            {
+                log::info!("avalanchego config is valid on '{instance_id}'");
+                continue;
+            }
+            let invocation = ssm_manager
+                .cli
+                .get_command_invocation()
+                .command_id(&ssm_command_id)
+                .instance_id(instance_id)
+                .send()
+                .await
+                .map_err(|e| {
+                    Error::new(
+                        ErrorKind::Other,
+                        format!(
+                            "failed get_command_invocation for '{instance_id}' ({:?})",
+                            e
+                        ),
+                    )
+                })?;
+            if invocation.response_code() == AVALANCHEGO_CONFIG_UNVERIFIED_EXIT_CODE {
+                unverified.push(format!("'{instance_id}' in '{region}'"));
+                continue;
+            }
+            failures.push(format!(
+                "'{instance_id}' in '{region}' {}",
+                avalanchego_config_check_failure(
+                    invocation.status_details().unwrap_or_default(),
+                    invocation.response_code()
+                )
+            ));
+        }
+    }
+    if !unverified.is_empty() {
+        log::warn!(
+            "avalanchego config exists but is unverified (no python3 or jq to check the JSON) on {} instance(s): {}",
+            unverified.len(),
+            unverified.join(", ")
+        );
+    }
+    if failures.is_empty() {
+        return Ok(());
+    }
+    Err(Error::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "--avalanchego-config-remote-path '{avalanchego_config_remote_path}' is not valid on {} instance(s): {} (use --skip-avalanchego-config-check to skip)",
+            failures.len(),
+            failures.join(", ")
+        ),
+    ))
+}
+
+/// Prints the SSM document parameters exactly as "SendCommand" would use them,
+/// so that the document can be run manually (e.g., via the SSM console).
+fn print_ssm_args(
+    ui: Ui,
+    region: &str,
+    ssm_doc: &str,
+    ssm_doc_version: &Option<String>,
+    instance_ids: &[String],
+    avalanched_args: &str,
+) -> io::Result<()> {
+    ui.print(Color::Magenta, format!(
+            "\n\n\nSSM ARGS (not sent, run manually)\nregion: {region}\ndocument name: {ssm_doc}\ndocument version: {}\ninstance ids: {}\navalanchedArgs: {avalanched_args}\n\n",
+            ssm_doc_version.as_deref().unwrap_or("$DEFAULT"),
+            instance_ids.join(","),
+        ))?;
+    Ok(())
+}
+
+/// Fails if there are more validating nodes than the cap, so that a mistakenly
+/// large node map does not stake across all of them.
+pub fn check_max_validators(count: usize, max_validators: u32) -> io::Result<()> {
+    if count > max_validators as usize {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "{count} validating node(s) exceed --max-validators {max_validators}, set '--max-validators {count}' if intended"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Maximum duration to wait for the accepted blockchain to be listed by "platform.getBlockchains".
+const BLOCKCHAIN_VISIBLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Waits until "platform.getBlockchains" lists the created blockchain, since
+/// the acceptance reported by the wallet does not guarantee that the node
+/// already serves the blockchain (the chain config and alias steps would
+/// otherwise fail with "chain not found").
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetblockchains>
+async fn wait_for_blockchain(
+    chain_rpc_url: &str,
+    blockchain_id: &ids::Id,
+    subnet_id: &ids::Id,
+    vm_id: &ids::Id,
+    rpc_headers: &[(String, String)],
+    api_paths: &ApiPaths,
+) -> io::Result<()> {
+    let start = Instant::now();
+    loop {
+        match call_json_rpc(
+            chain_rpc_url,
+            &api_paths.p_chain,
+            "platform.getBlockchains",
+            serde_json::json!({}),
+            rpc_headers,
+        )
+        .await
+        {
+            Ok(result) => {
+                if find_blockchain(&result, blockchain_id, subnet_id, vm_id)? {
+                    log::info!(
+                        "blockchain {blockchain_id} listed by platform.getBlockchains (subnet {subnet_id}, VM {vm_id})"
+                    );
+                    return Ok(());
+                }
+                log::info!("blockchain {blockchain_id} not yet listed by platform.getBlockchains");
+            }
+            Err(e) => log::warn!("failed platform.getBlockchains ({e}), retrying"),
+        }
+        if start.elapsed() > BLOCKCHAIN_VISIBLE_TIMEOUT {
+            return Err(Error::new(
+                ErrorKind::TimedOut,
+                format!(
+                    "blockchain {blockchain_id} was accepted but not listed by platform.getBlockchains on '{chain_rpc_url}' after {:?}",
+                    BLOCKCHAIN_VISIBLE_TIMEOUT
+                ),
+            ));
+        }
+        sleep(Duration::from_secs(5)).await;
+    }
 }
 
-pub async fn execute(opts: Flags) -> io::Result<()> {
-    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
-    env_logger::init_from_env(
-        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, opts.log_level),
-    );
+/// Returns true if the "platform.getTxStatus" status is committed, false if
+/// the transaction may still be accepted, and fails if it was dropped or aborted.
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgettxstatus>
+pub fn is_tx_committed(status: &str) -> io::Result<bool> {
+    match status {
+        "Committed" => Ok(true),
+        "Processing" | "Unknown" => Ok(false),
+        _ => Err(Error::new(
+            ErrorKind::Other,
+            format!("transaction status '{status}'"),
+        )),
+    }
+}
 
-    let mut node_id_to_pop = HashMap::new();
-    let mut region_to_ssm_doc = HashMap::new();
-    let mut target_nodes = HashMap::new();
-    if !opts.spec_file_path.is_empty() {
-        let spec = avalanche_ops::aws::spec::Spec::load(&opts.spec_file_path)
-            .expect("failed to load spec");
-        spec.validate()?;
+/// Polls "platform.getTxStatus" for the acceptance of the issued transactions,
+/// so that a dropped transaction fails fast and a slow one is visible in the logs.
+#[derive(Debug, Clone)]
+struct TxStatusPoller {
+    p_chain_rpc_url: String,
+    rpc_headers: Vec<(String, String)>,
+    api_paths: ApiPaths,
+    interval: Duration,
+    /// Maximum duration to wait for the acceptance ("--acceptance-timeout-seconds").
+    acceptance_timeout: Duration,
+}
 
-        for (region, regional_resource) in spec.resource.regional_resources.iter() {
-            region_to_ssm_doc.insert(
-                region.to_string(),
-                regional_resource
-                    .cloudformation_ssm_install_subnet_chain
-                    .clone()
-                    .unwrap(),
+/// Last polled status of the transaction, and the last "platform.getTxStatus"
+/// error if the latest poll failed (retried until the acceptance timeout).
+#[derive(Debug, Default)]
+struct TxPollState {
+    status: String,
+    fetch_error: String,
+}
+
+impl std::fmt::Display for TxPollState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "last status '{}'", self.status)?;
+        if !self.fetch_error.is_empty() {
+            write!(f, ", last getTxStatus error: {}", self.fetch_error)?;
+        }
+        Ok(())
+    }
+}
+
+impl TxStatusPoller {
+    /// Returns the status of the transaction, and its fail reason if any.
+    async fn fetch_status(&self, tx_id: &ids::Id) -> io::Result<(String, String)> {
+        let result = call_json_rpc(
+            &self.p_chain_rpc_url,
+            &self.api_paths.p_chain,
+            "platform.getTxStatus",
+            serde_json::json!({ "txID": tx_id.to_string() }),
+            &self.rpc_headers,
+        )
+        .await?;
+        Ok((
+            result["status"].as_str().unwrap_or_default().to_string(),
+            result["reason"].as_str().unwrap_or_default().to_string(),
+        ))
+    }
+
+    /// Returns true if the transaction is committed, or fails if it was dropped.
+    /// The "platform.getTxStatus" errors (e.g., a restarting node or a rate limit)
+    /// are retried on the next poll, until the acceptance timeout.
+    async fn check(
+        &self,
+        label: &str,
+        tx_id: &ids::Id,
+        state: &mut TxPollState,
+    ) -> io::Result<bool> {
+        let (status, reason) = match self.fetch_status(tx_id).await {
+            Ok(ret) => ret,
+            Err(e) => {
+                log::warn!("{label} tx {tx_id} failed to fetch the status ({e}), retrying");
+                state.fetch_error = e.to_string();
+                return Ok(false);
+            }
+        };
+        state.fetch_error.clear();
+        if status != state.status {
+            log::info!(
+                "{label} tx {tx_id} status '{}' -> '{status}'",
+                if state.status.is_empty() {
+                    "Issued"
+                } else {
+                    state.status.as_str()
+                }
             );
+            state.status = status.clone();
         }
+        is_tx_committed(&status).map_err(|e| {
+            let reason = if reason.is_empty() {
+                String::new()
+            } else {
+                format!(" ({reason})")
+            };
+            Error::new(e.kind(), format!("{label} tx {tx_id}: {e}{reason}"))
+        })
+    }
 
-        if let Some(created_nodes) = &spec.resource.created_nodes {
-            for node in created_nodes {
-                let node_id = ids::node::Id::from_str(&node.node_id).unwrap();
-                node_id_to_pop.insert(node_id, node.proof_of_possession.clone());
-                target_nodes.insert(
-                    node.node_id.clone(),
-                    avalanche_ops::aws::spec::RegionMachineId {
-                        region: node.region.clone(),
-                        machine_id: node.machine_id.clone(),
-                    },
+    /// Polls the transaction until committed.
+    async fn wait(&self, label: &str, tx_id: &ids::Id) -> io::Result<()> {
+        let _span = telemetry::span("poll tx status", &[("tx_id", tx_id.to_string())]);
+        let start = Instant::now();
+        let mut state = TxPollState::default();
+        loop {
+            if self.check(label, tx_id, &mut state).await? {
+                log::info!("{label} tx {tx_id} committed in {:?}", start.elapsed());
+                return Ok(());
+            }
+            if start.elapsed() > self.acceptance_timeout {
+                return Err(Error::new(
+                    ErrorKind::TimedOut,
+                    format!(
+                        "{label} tx {tx_id} not committed after {:?} ({state})",
+                        self.acceptance_timeout
+                    ),
+                ));
+            }
+            sleep(self.interval).await;
+        }
+    }
+
+    /// Polls the transactions issued without waiting for acceptance
+    /// ("--no-check-acceptance") until all are committed.
+    async fn wait_all(&self, tx_ids: &[(String, ids::Id)]) -> io::Result<()> {
+        log::info!("polling the acceptance of {} transaction(s)", tx_ids.len());
+        let start = Instant::now();
+        let mut pending: Vec<(&String, &ids::Id, TxPollState)> = tx_ids
+            .iter()
+            .map(|(label, tx_id)| (label, tx_id, TxPollState::default()))
+            .collect();
+        loop {
+            let mut still_pending = Vec::new();
+            for (label, tx_id, mut state) in pending {
+                if !self.check(label, tx_id, &mut state).await? {
+                    still_pending.push((label, tx_id, state));
+                }
+            }
+            if still_pending.is_empty() {
+                log::info!(
+                    "all {} transaction(s) committed in {:?}",
+                    tx_ids.len(),
+                    start.elapsed()
                 );
+                return Ok(());
+            }
+            if start.elapsed() > self.acceptance_timeout {
+                return Err(Error::new(
+                    ErrorKind::TimedOut,
+                    format!(
+                        "{} transaction(s) not committed after {:?}: {}",
+                        still_pending.len(),
+                        self.acceptance_timeout,
+                        still_pending
+                            .iter()
+                            .map(|(label, tx_id, state)| format!("{label} {tx_id} ({state})"))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                ));
             }
+            pending = still_pending;
+            sleep(self.interval).await;
         }
-    } else {
-        target_nodes = opts.target_nodes.clone();
-        region_to_ssm_doc = opts.ssm_docs.clone();
     }
+}
+
+/// Parses the control keys (P-chain addresses) and the threshold of the subnet
+/// from the "platform.getSubnets" result.
+pub fn parse_subnet_owners(
+    result: &serde_json::Value,
+    subnet_id: &ids::Id,
+) -> io::Result<(Vec<String>, u32)> {
+    let subnet = result["subnets"]
+        .as_array()
+        .and_then(|subnets| {
+            subnets
+                .iter()
+                .find(|s| s["id"].as_str() == Some(subnet_id.to_string().as_str()))
+        })
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("subnet '{subnet_id}' not found in platform.getSubnets"),
+            )
+        })?;
+    let control_keys: Vec<String> = subnet["controlKeys"]
+        .as_array()
+        .map(|keys| {
+            keys.iter()
+                .filter_map(|k| k.as_str().map(|k| k.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    // the threshold is returned as a string
+    let threshold = subnet["threshold"]
+        .as_str()
+        .and_then(|s| s.parse::<u32>().ok())
+        .or_else(|| subnet["threshold"].as_u64().map(|t| t as u32))
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("unexpected threshold in subnet {subnet}"),
+            )
+        })?;
+    Ok((control_keys, threshold))
+}
 
-    if !Path::new(&opts.vm_binary_local_path).exists() {
+/// Checks that the signers are distinct control keys of the subnet that meet
+/// its threshold, and returns the signature indices of the subnet auth
+/// (the positions of the first "threshold" signers among the control keys, sorted).
+pub fn check_subnet_auth(
+    control_keys: &[String],
+    threshold: u32,
+    signers: &[String],
+) -> io::Result<Vec<u32>> {
+    let mut seen = HashSet::new();
+    let duplicates: Vec<&String> = signers.iter().filter(|s| !seen.insert(*s)).collect();
+    if !duplicates.is_empty() {
         return Err(Error::new(
             ErrorKind::InvalidInput,
-            format!("vm binary file '{}' not found", opts.vm_binary_local_path),
+            format!(
+                "duplicate subnet signers {:?} (--key and --subnet-auth-keys)",
+                duplicates
+            ),
         ));
     }
-
-    if !opts.subnet_config_local_path.is_empty() && opts.subnet_config_remote_dir.is_empty() {
+    let non_control: Vec<&String> = signers
+        .iter()
+        .filter(|s| !control_keys.contains(s))
+        .collect();
+    if !non_control.is_empty() {
         return Err(Error::new(
             ErrorKind::InvalidInput,
-            "subnet_config_local_path not empty but subnet_config_remote_dir empty",
+            format!(
+                "{:?} are not control keys of the subnet (control keys {:?})",
+                non_control, control_keys
+            ),
         ));
     }
-    if !opts.chain_config_local_path.is_empty() && opts.chain_config_remote_dir.is_empty() {
+    if signers.len() < threshold as usize {
         return Err(Error::new(
             ErrorKind::InvalidInput,
-            "chain_config_local_path not empty but chain_config_remote_dir empty",
+            format!(
+                "subnet requires {threshold} of {} control keys to sign, got {} (set the other control keys in --subnet-auth-keys)",
+                control_keys.len(),
+                signers.len()
+            ),
         ));
     }
 
-    if !Path::new(&opts.chain_genesis_path).exists() {
+    // same order as the keychain matches the subnet owners
+    let mut sig_indices: Vec<u32> = control_keys
+        .iter()
+        .enumerate()
+        .filter(|(_, k)| signers.contains(k))
+        .map(|(i, _)| i as u32)
+        .take(threshold as usize)
+        .collect();
+    sig_indices.sort();
+    Ok(sig_indices)
+}
+
+/// Returns true if the "platform.getBlockchains" result lists the blockchain,
+/// and fails if it is listed with another subnet or VM Id.
+pub fn find_blockchain(
+    result: &serde_json::Value,
+    blockchain_id: &ids::Id,
+    subnet_id: &ids::Id,
+    vm_id: &ids::Id,
+) -> io::Result<bool> {
+    let blockchain_id_str = blockchain_id.to_string();
+    let blockchain = match result["blockchains"]
+        .as_array()
+        .and_then(|bs| bs.iter().find(|b| b["id"] == blockchain_id_str.as_str()))
+    {
+        Some(b) => b,
+        None => return Ok(false),
+    };
+    let listed_subnet_id = blockchain["subnetID"].as_str().unwrap_or_default();
+    let listed_vm_id = blockchain["vmID"].as_str().unwrap_or_default();
+    if listed_subnet_id != subnet_id.to_string() || listed_vm_id != vm_id.to_string() {
         return Err(Error::new(
-            ErrorKind::InvalidInput,
-            format!("chain genesis file '{}' not found", opts.chain_genesis_path),
+            ErrorKind::InvalidData,
+            format!(
+                "blockchain {blockchain_id} is listed with subnet '{listed_subnet_id}' and VM '{listed_vm_id}', expected subnet {subnet_id} and VM {vm_id}"
+            ),
         ));
     }
-    let f = File::open(&opts.chain_genesis_path).map_err(|e| {
+    Ok(true)
+}
+
+/// Returns the subnet Ids whose control keys include the key's short address.
+async fn fetch_owned_subnet_ids(
+    chain_rpc_url: &str,
+    priv_key: &key::secp256k1::private_key::Key,
+) -> io::Result<Vec<ids::Id>> {
+    let short_addr = priv_key.to_public_key().to_short_id().map_err(|e| {
         Error::new(
             ErrorKind::Other,
-            format!("failed to open {} ({})", opts.chain_genesis_path, e),
+            format!("failed to derive the short address ({e})"),
         )
     })?;
-    let mut reader = BufReader::new(f);
-    let mut chain_genesis_bytes = Vec::new();
-    reader.read_to_end(&mut chain_genesis_bytes)?;
-
-    let vm_id = if opts.vm_id.is_empty() {
-        subnet::vm_name_to_id(&opts.chain_name)?
-    } else {
-        ids::Id::from_str(&opts.vm_id)?
-    };
-    log::info!("VM ID is {}", vm_id.to_string());
-
-    let resp = json_client_info::get_network_id(&opts.chain_rpc_url)
-        .await
-        .unwrap();
-    let network_id = resp.result.unwrap().network_id;
 
-    let priv_key = key::secp256k1::private_key::Key::from_hex(&opts.key).unwrap();
-    let wallet_to_spend = wallet::Builder::new(&priv_key)
-        .base_http_url(opts.chain_rpc_url.clone())
-        .build()
+    let resp = json_client_p::get_subnets(chain_rpc_url, None)
         .await
-        .unwrap();
-
-    let p_chain_balance = wallet_to_spend.p().balance().await.unwrap();
-    let p_chain_address = priv_key
-        .to_public_key()
-        .to_hrp_address(network_id, "P")
-        .unwrap();
-    log::info!(
-        "loaded wallet '{p_chain_address}', fetched its P-chain balance {} AVAX ({p_chain_balance} nAVAX, network id {network_id})",
-        units::cast_xp_navax_to_avax(primitive_types::U256::from(p_chain_balance))
-    );
-
-    let mut all_node_ids = Vec::new();
-    let mut region_to_instance_ids: HashMap<String, Vec<String>> = HashMap::new();
-    for (node_id, region_machine_id) in target_nodes.iter() {
-        log::info!(
-            "will send SSM doc to the node '{node_id}' of '{}' in the region '{}'",
-            region_machine_id.machine_id,
-            region_machine_id.region,
-        );
-        all_node_ids.push(node_id.clone());
-
-        if let Some(instance_ids) = region_to_instance_ids.get_mut(&region_machine_id.region) {
-            instance_ids.push(region_machine_id.machine_id.clone());
-        } else {
-            region_to_instance_ids.insert(
-                region_machine_id.region.clone(),
-                vec![region_machine_id.machine_id.clone()],
-            );
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed get_subnets '{chain_rpc_url}' ({e})"),
+            )
+        })?;
+    let mut owned_subnet_ids = Vec::new();
+    if let Some(rs) = &resp.result {
+        if let Some(subnets) = &rs.subnets {
+            for s in subnets.iter() {
+                if let Some(control_keys) = &s.control_keys {
+                    if control_keys.contains(&short_addr) {
+                        owned_subnet_ids.push(s.id);
+                    }
+                }
+            }
         }
     }
+    Ok(owned_subnet_ids)
+}
 
-    // if all nodes need to be staked
-    println!();
-    let estimated_required_avax =
-        units::cast_avax_to_xp_navax(primitive_types::U256::from(opts.staking_amount_in_avax))
-            .checked_mul(primitive_types::U256::from(all_node_ids.len()))
-            .unwrap();
-    log::info!(
-        "required AVAX to validate all nodes {estimated_required_avax} nAVAX ({} AVAX)",
-        units::cast_xp_navax_to_avax(estimated_required_avax)
-    );
-    if primitive_types::U256::from(p_chain_balance) < estimated_required_avax {
-        log::warn!("'{p_chain_address}' only has {p_chain_balance}, not enough to validate all nodes (needs {estimated_required_avax} nAVAX)");
-        let selected = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Should we still proceed?")
-            .items(&["Yes...?", "No!!!"])
-            .default(0)
-            .interact()
-            .unwrap();
-        if selected == 1 {
-            return Ok(());
-        }
+/// Deep-merges the JSON overrides into the base JSON object.
+/// Nested objects are merged recursively, and other values are replaced.
+pub fn merge_json_overrides(base: &str, overrides: &str) -> io::Result<String> {
+    let mut base: serde_json::Value = serde_json::from_str(base).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("failed to parse base JSON ({})", e),
+        )
+    })?;
+    let overrides: serde_json::Value = serde_json::from_str(overrides).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("failed to parse overrides JSON ({})", e),
+        )
+    })?;
+    if !base.is_object() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "base must be a JSON object",
+        ));
+    }
+    if !overrides.is_object() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "overrides must be a JSON object",
+        ));
     }
 
-    println!();
-    execute!(
-        stdout(),
-        SetForegroundColor(Color::Green),
-        Print(format!(
-            "\nInstalling subnet with network Id '{network_id}', chain rpc url '{}', S3 bucket '{}', S3 key prefix '{}', S3 upload timeout '{}', subnet config local '{}', subnet config remote dir '{}', VM binary local '{}', VM binary remote dir '{}', VM Id '{}', chain name '{}', chain config local '{}', chain config remote dir '{}', chain genesis file '{}', primary network validate period in days '{}', subnet validate period in days '{}', staking amount in avax '{}', node ids to instance ids '{:?}'\n",
-            opts.chain_rpc_url,
-            opts.s3_bucket,
-            opts.s3_key_prefix,
-            opts.s3_upload_timeout,
-            opts.subnet_config_local_path,
-            opts.subnet_config_remote_dir,
-            opts.vm_binary_local_path,
-            opts.vm_binary_remote_dir,
-            vm_id,
-            opts.chain_name,
-            opts.chain_config_local_path,
-            opts.chain_config_remote_dir,
-            opts.chain_genesis_path,
-            opts.primary_network_validate_period_in_days,
-            opts.subnet_validate_period_in_days,
-            opts.staking_amount_in_avax,
-            target_nodes,
-        )),
-        ResetColor
-    )?;
-
-    let shared_config = aws_manager::load_config(
-        Some(opts.s3_region.clone()),
-        Some(opts.profile_name.clone()),
-        Some(Duration::from_secs(opts.s3_upload_timeout)),
-    )
-    .await;
-    let sts_manager = sts::Manager::new(&shared_config);
-    let s3_manager = s3::Manager::new(&shared_config);
-
-    let current_identity = sts_manager.get_identity().await.unwrap();
-    log::info!("current AWS identity: {:?}", current_identity);
+    deep_merge(&mut base, overrides);
+    serde_json::to_string_pretty(&base)
+        .map_err(|e| Error::new(ErrorKind::Other, format!("failed to encode JSON ({})", e)))
+}
 
-    if !opts.skip_prompt {
-        println!();
-        println!();
-        let options = &[
-            format!(
-                "No, I am not ready to install a subnet with the wallet {p_chain_address} of balance {} AVAX, staking amount {} AVAX, primary network staking {} days, subnet staking {} days",
-                    units::cast_xp_navax_to_avax(primitive_types::U256::from(p_chain_balance)),
-                    opts.staking_amount_in_avax,
-                    opts.primary_network_validate_period_in_days,
-                    opts.subnet_validate_period_in_days,
-            ),
-            format!(
-                "Yes, let's install a subnet with the wallet {p_chain_address} of balance {} AVAX, staking amount {} AVAX, primary network staking {} days, subnet staking {} days",
-                    units::cast_xp_navax_to_avax(primitive_types::U256::from(p_chain_balance)),
-                    opts.staking_amount_in_avax,
-                    opts.primary_network_validate_period_in_days,
-                    opts.subnet_validate_period_in_days,
-                ),
-        ];
-        let selected = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Select your 'install-subnet-chain' option")
-            .items(&options[..])
-            .default(0)
-            .interact()
-            .unwrap();
-        if selected == 0 {
-            return Ok(());
+fn deep_merge(base: &mut serde_json::Value, overrides: serde_json::Value) {
+    match (base, overrides) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overrides)) => {
+            for (k, v) in overrides {
+                deep_merge(base.entry(k).or_insert(serde_json::Value::Null), v);
+            }
         }
+        (base, overrides) => *base = overrides,
     }
+}
 
-    //
-    //
-    //
-    //
-    //
-    if !opts.subnet_config_local_path.is_empty() {
-        execute!(
-            stdout(),
-            SetForegroundColor(Color::Green),
-            Print("\n\n\nSTEP: uploading subnet config local file to S3\n\n"),
-            ResetColor
-        )?;
-
-        if !Path::new(&opts.subnet_config_local_path).exists() {
+/// Parses the P-chain addresses (e.g., "P-fuji1...") into the short addresses,
+/// checking that they belong to the network, and returns them with the addresses
+/// normalized to the "P-" prefix.
+pub fn parse_control_keys(
+    addresses: &[String],
+    network_id: u32,
+) -> io::Result<Vec<(String, ids::short::Id)>> {
+    let expected_hrp = constants::NETWORK_ID_TO_HRP
+        .get(&network_id)
+        .copied()
+        .unwrap_or(constants::FALLBACK_HRP);
+    let mut control_keys = Vec::new();
+    for addr in addresses.iter().map(|a| a.trim()) {
+        let (hrp, short_bytes) = key::secp256k1::address::avax_address_to_short_bytes("P", addr)
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("invalid P-chain address '{addr}' ({e})"),
+                )
+            })?;
+        if hrp != expected_hrp {
             return Err(Error::new(
                 ErrorKind::InvalidInput,
-                format!(
-                    "subnet config file '{}' not found",
-                    opts.subnet_config_local_path
-                ),
+                format!("P-chain address '{addr}' is not on network {network_id} (expected HRP '{expected_hrp}', got '{hrp}')"),
             ));
         }
-
-        let file_stem = Path::new(&opts.subnet_config_local_path)
-            .file_stem()
-            .unwrap();
-        let subnet_config_s3_key = format!(
-            "{}{}",
-            s3::append_slash(&opts.s3_key_prefix),
-            file_stem.to_str().unwrap()
-        );
-
-        s3_manager
-            .put_object(
-                &opts.subnet_config_local_path,
-                &opts.s3_bucket,
-                &subnet_config_s3_key,
-            )
-            .await
-            .expect("failed put_object subnet_config_path");
-    }
-
-    //
-    //
-    //
-    //
-    //
-    execute!(
-        stdout(),
-        SetForegroundColor(Color::Green),
-        Print("\n\n\nSTEP: uploading VM binary local file to S3\n\n"),
-        ResetColor
-    )?;
-    let vm_binary_s3_key = format!("{}{}", s3::append_slash(&opts.s3_key_prefix), vm_id);
-    s3_manager
-        .put_object(
-            &opts.vm_binary_local_path,
-            &opts.s3_bucket,
-            &vm_binary_s3_key,
-        )
-        .await
-        .expect("failed put_object vm_binary_path");
-
-    //
-    //
-    //
-    //
-    //
-    if !opts.chain_config_local_path.is_empty() {
-        execute!(
-            stdout(),
-            SetForegroundColor(Color::Green),
-            Print("\n\n\nSTEP: uploading subnet chain config local file to S3\n\n"),
-            ResetColor
-        )?;
-
-        if !Path::new(&opts.chain_config_local_path).exists() {
+        if short_bytes.len() != 20 {
             return Err(Error::new(
                 ErrorKind::InvalidInput,
                 format!(
-                    "subnet chain config file '{}' not found",
-                    opts.chain_config_local_path
+                    "invalid P-chain address '{addr}' ({} bytes)",
+                    short_bytes.len()
                 ),
             ));
         }
+        control_keys.push((
+            format!("P-{}", addr.trim_start_matches("P-").to_lowercase()),
+            ids::short::Id::from_slice(&short_bytes),
+        ));
+    }
+    Ok(control_keys)
+}
 
-        let file_stem = Path::new(&opts.chain_config_local_path)
-            .file_stem()
-            .unwrap();
-        let chain_config_s3_key = format!(
-            "{}{}",
-            s3::append_slash(&opts.s3_key_prefix),
-            file_stem.to_str().unwrap()
-        );
+/// Builds the "secp256k1fx" output owners (e.g., subnet control keys) with the threshold.
+/// The addresses are sorted, to match the canonical ordering of avalanchego.
+/// ref. <https://github.com/ava-labs/avalanchego/blob/master/vms/secp256k1fx/output_owners.go>
+pub fn build_output_owners(
+    addresses: &[ids::short::Id],
+    threshold: u32,
+) -> io::Result<key::secp256k1::txs::OutputOwners> {
+    if addresses.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "no address for the output owners",
+        ));
+    }
+    if threshold == 0 || threshold as usize > addresses.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "invalid threshold {threshold} for {} address(es)",
+                addresses.len()
+            ),
+        ));
+    }
 
-        s3_manager
-            .put_object(
-                &opts.chain_config_local_path,
-                &opts.s3_bucket,
-                &chain_config_s3_key,
-            )
-            .await
-            .expect("failed put_object chain_config_path");
-    }
-
-    //
-    //
-    //
-    //
-    //
-    execute!(
-        stdout(),
-        SetForegroundColor(Color::Green),
-        Print(format!(
-            "\n\n\nSTEP: adding all nodes as primary network validators if not yet (period {})\n\n",
-            opts.primary_network_validate_period_in_days
-        )),
-        ResetColor
-    )?;
-    let stake_amount_in_navax =
-        units::cast_avax_to_xp_navax(primitive_types::U256::from(opts.staking_amount_in_avax))
-            .as_u64();
+    let mut sorted = addresses.to_vec();
+    sorted.sort();
+    sorted.dedup();
+    if sorted.len() != addresses.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "duplicate addresses for the output owners",
+        ));
+    }
 
-    let mut handles = Vec::new();
-    for (i, (node_id, region_machine_id)) in target_nodes.iter().enumerate() {
-        // randomly wait to prevent UTXO double spends from the same wallet
-        let random_wait = Duration::from_secs(1 + (i + 1) as u64)
-            .checked_add(Duration::from_millis(500 + random_manager::u64() % 100))
-            .unwrap();
+    Ok(key::secp256k1::txs::OutputOwners {
+        locktime: 0,
+        threshold,
+        addresses: sorted,
+    })
+}
 
-        log::info!(
-            "spawning add_primary_network_permissionless_validator/add_primary_network_validator on '{}' (of EC2 instance '{}', staking period in days '{}')",
-            node_id,
-            region_machine_id.machine_id,
-            opts.primary_network_validate_period_in_days,
-        );
-        let node_id = ids::node::Id::from_str(node_id).unwrap();
-        if let Some(pop) = node_id_to_pop.get(&node_id) {
-            handles.push(tokio::spawn(add_primary_network_permissionless_validator(
-                Arc::new(random_wait),
-                Arc::new(wallet_to_spend.clone()),
-                Arc::new(node_id),
-                Arc::new(pop.clone()),
-                Arc::new(stake_amount_in_navax),
-                Arc::new(opts.primary_network_validate_period_in_days),
-            )));
-        } else {
-            handles.push(tokio::spawn(add_primary_network_validator(
-                Arc::new(random_wait),
-                Arc::new(wallet_to_spend.clone()),
-                Arc::new(node_id),
-                Arc::new(stake_amount_in_navax),
-                Arc::new(opts.primary_network_validate_period_in_days),
-            )));
+/// Polls the P-chain for the subnets owned by the key, and returns the subnet
+/// that was not in the known list (e.g., created but acceptance poll timed out).
+/// Returns None if no new subnet is found, and errors if the result is ambiguous.
+async fn reconcile_created_subnet(
+    chain_rpc_url: &str,
+    priv_key: &key::secp256k1::private_key::Key,
+    known_subnet_ids: &[ids::Id],
+) -> io::Result<Option<ids::Id>> {
+    let mut wait = Duration::from_secs(1);
+    for attempt in 1..=RPC_RETRIES {
+        sleep(wait).await;
+        wait *= 2;
+
+        let owned_subnet_ids = match fetch_owned_subnet_ids(chain_rpc_url, priv_key).await {
+            Ok(ids) => ids,
+            Err(e) => {
+                log::warn!("failed to fetch owned subnets (attempt {attempt}/{RPC_RETRIES}, {e})");
+                continue;
+            }
+        };
+        let new_subnet_ids: Vec<ids::Id> = owned_subnet_ids
+            .into_iter()
+            .filter(|id| !known_subnet_ids.contains(id))
+            .collect();
+        match new_subnet_ids.len() {
+            0 => log::info!("no new subnet found yet (attempt {attempt}/{RPC_RETRIES})"),
+            1 => return Ok(Some(new_subnet_ids[0])),
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    format!(
+                        "found multiple new subnets {:?}, cannot reconcile the created subnet",
+                        new_subnet_ids
+                    ),
+                ));
+            }
         }
     }
-    log::info!("STEP: blocking on add_validator handles via JoinHandle");
-    for handle in handles {
-        handle.await.map_err(|e| {
+    Ok(None)
+}
+
+/// Runs the local VM binary with "--version" and checks that the output
+/// contains the expected version, to catch a stale binary path.
+/// The binary must be executable on the local machine (e.g., same architecture).
+pub fn verify_vm_version(vm_binary_path: &str, expected_vm_version: &str) -> io::Result<()> {
+    let output = ProcessCommand::new(vm_binary_path)
+        .arg("--version")
+        .output()
+        .map_err(|e| {
             Error::new(
                 ErrorKind::Other,
-                format!("failed await on add_validator JoinHandle {}", e),
+                format!(
+                    "failed to run '{vm_binary_path} --version' ({}), the binary must be executable locally to verify the version",
+                    e
+                ),
             )
         })?;
+    if !output.status.success() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!(
+                "'{vm_binary_path} --version' failed with {} ({})",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        ));
     }
 
-    //
-    //
-    //
-    //
-    //
-    execute!(
-        stdout(),
-        SetForegroundColor(Color::Green),
-        Print("\n\n\nSTEP: creating a subnet\n\n"),
-        ResetColor
-    )?;
-    let subnet_id = wallet_to_spend
-        .p()
-        .create_subnet()
-        .dry_mode(true)
-        .issue()
-        .await
-        .unwrap();
-    log::info!("[dry mode] subnet Id '{}'", subnet_id);
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !version.contains(expected_vm_version) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "VM binary '{vm_binary_path}' version '{version}' does not match the expected version '{expected_vm_version}'"
+            ),
+        ));
+    }
+    log::info!("VM binary '{vm_binary_path}' version '{version}' matches '{expected_vm_version}'");
 
-    let created_subnet_id = wallet_to_spend
-        .p()
-        .create_subnet()
-        .check_acceptance(true)
-        .issue()
-        .await
-        .unwrap();
-    log::info!("created subnet '{}' (still need track)", created_subnet_id);
-    sleep(Duration::from_secs(10)).await;
-
-    //
-    //
-    //
-    //
-    //
-    execute!(
-        stdout(),
-        SetForegroundColor(Color::Green),
-        Print("\n\n\nSTEP: send SSM doc to download Vm binary, track subnet Id, update subnet config\n\n"),
-        ResetColor
-    )?;
-    let subcmd = format!("install-subnet --log-level info --s3-region {s3_region} --s3-bucket {s3_bucket} --vm-binary-s3-key {vm_binary_s3_key} --vm-binary-local-path {vm_binary_local_path} --subnet-id-to-track {subnet_id_to_track} --avalanchego-config-path {avalanchego_config_remote_path}",
-    s3_region = opts.s3_region,
-        s3_bucket = opts.s3_bucket,
-        vm_binary_s3_key = vm_binary_s3_key,
-        vm_binary_local_path = format!("{}{}", s3::append_slash(&opts.vm_binary_remote_dir), vm_id),
-        subnet_id_to_track = created_subnet_id,
-        avalanchego_config_remote_path = opts.avalanchego_config_remote_path,
-    );
-    let avalanched_args = if !opts.subnet_config_local_path.is_empty() {
-        let file_stem = Path::new(&opts.subnet_config_local_path)
-            .file_stem()
-            .unwrap();
-        let subnet_config_s3_key = format!(
-            "{}{}",
-            s3::append_slash(&opts.s3_key_prefix),
-            file_stem.to_str().unwrap()
-        );
+    Ok(())
+}
 
-        // If a subnet id is 2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt,
-        // the config file for this subnet is located at {subnet-config-dir}/2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt.json.
-        format!("{subcmd} --subnet-config-s3-key {subnet_config_s3_key} --subnet-config-local-path {subnet_config_local_path}",
-            subnet_config_s3_key = subnet_config_s3_key,
-            subnet_config_local_path = format!("{}{}.json", s3::append_slash(&opts.subnet_config_remote_dir), created_subnet_id),
-        )
-    } else {
-        subcmd
-    };
+/// Reads the genesis file, "name" is used for the error messages.
+fn read_genesis_file(path: &str, name: &str) -> io::Result<Vec<u8>> {
+    if !Path::new(path).exists() {
+        return Err(file_not_found_error(name, path));
+    }
+    let f = File::open(path)
+        .map_err(|e| Error::new(ErrorKind::Other, format!("failed to open {} ({})", path, e)))?;
+    let mut reader = BufReader::new(f);
+    let mut genesis_bytes = Vec::new();
+    reader.read_to_end(&mut genesis_bytes)?;
+    Ok(genesis_bytes)
+}
 
-    for (region, instance_ids) in region_to_instance_ids.iter() {
-        if !region_to_ssm_doc.contains_key(region) {
-            panic!(
-                "--ssm-docs does not have the document name for the region '{}'",
-                region
-            );
-        }
-        let ssm_doc = region_to_ssm_doc.get(region).unwrap();
+/// Maximum edit distance of a file name suggested for a missing file.
+const MAX_FILE_SUGGESTION_DISTANCE: usize = 3;
 
-        log::info!(
-            "sending SSM commands for the region '{region}' with instances {:?}",
-            instance_ids
-        );
-        let shared_config = aws_manager::load_config(
-            Some(region.clone()),
-            Some(opts.profile_name.clone()),
-            Some(Duration::from_secs(30)),
-        )
-        .await;
-        let regional_ssm_manager = ssm::Manager::new(&shared_config);
+/// Maximum number of file names listed for a missing file.
+const MAX_LISTED_FILES: usize = 10;
 
-        // ref. <https://docs.aws.amazon.com/systems-manager/latest/APIReference/API_SendCommand.html>
-        let ssm_output = regional_ssm_manager
-            .cli
-            .send_command()
-            .document_name(ssm_doc.clone())
-            .set_instance_ids(Some(instance_ids.clone()))
-            .parameters("avalanchedArgs", vec![avalanched_args.clone()])
-            .output_s3_region(opts.s3_region.clone())
-            .output_s3_bucket_name(opts.s3_bucket.clone())
-            .output_s3_key_prefix(format!(
-                "{}ssm-output-logs",
-                s3::append_slash(&opts.s3_key_prefix)
-            ))
-            .send()
-            .await
-            .unwrap();
-        let ssm_output = ssm_output.command().unwrap();
-        let ssm_command_id = ssm_output.command_id().unwrap();
-        log::info!("sent SSM command {}", ssm_command_id);
-        sleep(Duration::from_secs(30)).await;
+/// Returns the "file not found" error, listing the files present in the same
+/// directory (or the cwd) and suggesting the closest file name, if any.
+fn file_not_found_error(name: &str, path: &str) -> io::Error {
+    let p = Path::new(path);
+    let dir = match p.parent() {
+        Some(d) if !d.as_os_str().is_empty() => d,
+        _ => Path::new("."),
+    };
 
-        execute!(
-            stdout(),
-            SetForegroundColor(Color::Green),
-            Print("\n\n\nSTEP: checking the status of SSM command...\n\n"),
-            ResetColor
-        )?;
-        for instance_id in instance_ids.iter() {
-            let status = regional_ssm_manager
-                .poll_command(
-                    ssm_command_id,
-                    instance_id,
-                    CommandInvocationStatus::Success,
-                    Duration::from_secs(300),
-                    Duration::from_secs(5),
-                )
-                .await
-                .unwrap();
-            log::info!("status {:?} for instance id {}", status, instance_id);
+    let mut msg = format!("{name} file '{path}' not found");
+    match fs::read_dir(dir) {
+        Ok(entries) => {
+            let mut file_names: Vec<String> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect();
+            file_names.sort();
+
+            let file_name = p
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if let Some(closest) = suggest_file_name(&file_name, &file_names) {
+                msg.push_str(&format!(
+                    " (did you mean '{}'?)",
+                    p.with_file_name(closest).display()
+                ));
+            }
+
+            if file_names.is_empty() {
+                msg.push_str(&format!(", directory '{}' is empty", dir.display()));
+            } else {
+                msg.push_str(&format!(
+                    ", files in '{}': {}",
+                    dir.display(),
+                    file_names
+                        .iter()
+                        .take(MAX_LISTED_FILES)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+                if file_names.len() > MAX_LISTED_FILES {
+                    msg.push_str(&format!(
+                        " (and {} more)",
+                        file_names.len() - MAX_LISTED_FILES
+                    ));
+                }
+            }
         }
-        sleep(Duration::from_secs(5)).await;
+        Err(_) => msg.push_str(&format!(", directory '{}' not found", dir.display())),
     }
+    Error::new(ErrorKind::InvalidInput, msg)
+}
 
-    //
-    //
-    //
-    //
-    //
-    execute!(
-        stdout(),
-        SetForegroundColor(Color::Green),
-        Print("\n\n\nSTEP: adding all nodes as subnet validators\n\n"),
-        ResetColor
-    )?;
-    let mut handles = Vec::new();
-    for (i, node_id) in all_node_ids.iter().enumerate() {
-        log::info!(
-            "spawning add_subnet_validator on '{}' (staking period in days '{}')",
-            node_id,
-            opts.subnet_validate_period_in_days
-        );
+/// Returns the file name closest to the missing one (case-insensitive),
+/// if any is within the suggestion distance.
+pub fn suggest_file_name<'a>(file_name: &str, file_names: &'a [String]) -> Option<&'a str> {
+    if file_name.is_empty() {
+        return None;
+    }
+    let lower = file_name.to_lowercase();
+    file_names
+        .iter()
+        .map(|f| (f, config_schema::edit_distance(&lower, &f.to_lowercase())))
+        .filter(|(_, d)| *d <= MAX_FILE_SUGGESTION_DISTANCE)
+        .min_by_key(|(_, d)| *d)
+        .map(|(f, _)| f.as_str())
+}
 
-        // TODO: remove this... after fixing flaky errors of utxo not found
-        sleep(Duration::from_secs(2)).await;
+/// Generates the subnet-evm genesis with the operator-provided parameters.
+/// Each allocation is in "address:balance" format with the balance in wei.
+pub fn generate_subnet_evm_genesis(
+    chain_id: u64,
+    gas_limit: u64,
+    fee_config: &str,
+    allocs: &[String],
+) -> io::Result<subnet_evm_genesis::Genesis> {
+    if allocs.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "no --alloc entry for the generated genesis",
+        ));
+    }
 
-        // randomly wait to prevnt UTXO double spends from the same wallet
-        let random_wait = if i < 5 {
-            Duration::from_secs(2 + (i * 2) as u64)
-                .checked_add(Duration::from_millis(500 + random_manager::u64() % 100))
-                .unwrap()
-        } else {
-            Duration::from_secs(5 + (i * 2) as u64)
-                .checked_add(Duration::from_millis(500 + random_manager::u64() % 100))
-                .unwrap()
-        };
-        handles.push(tokio::spawn(add_subnet_network_validator(
-            Arc::new(random_wait),
-            Arc::new(wallet_to_spend.clone()),
-            Arc::new(node_id.to_owned()),
-            Arc::new(created_subnet_id.to_owned()),
-            Arc::new(opts.subnet_validate_period_in_days),
-        )));
-    }
-    log::info!("STEP: blocking on add_subnet_validator handles via JoinHandle");
-    for handle in handles {
-        handle.await.map_err(|e| {
+    let mut alloc = BTreeMap::new();
+    for entry in allocs.iter() {
+        let (addr, balance) = entry.split_once(':').ok_or_else(|| {
             Error::new(
-                ErrorKind::Other,
-                format!("failed await on add_subnet_validator JoinHandle {}", e),
+                ErrorKind::InvalidInput,
+                format!("invalid alloc '{entry}' (expected 'address:balance')"),
+            )
+        })?;
+        let addr = addr.trim().trim_start_matches("0x").to_lowercase();
+        if addr.len() != 40 || !addr.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid alloc address in '{entry}'"),
+            ));
+        }
+        let balance = primitive_types::U256::from_dec_str(balance.trim()).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid alloc balance in '{entry}' ({:?})", e),
             )
         })?;
+        if alloc.contains_key(&addr) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("duplicate alloc address in '{entry}'"),
+            ));
+        }
+        alloc.insert(
+            addr,
+            subnet_evm_genesis::AllocAccount {
+                balance,
+                ..subnet_evm_genesis::AllocAccount::default()
+            },
+        );
     }
-    sleep(Duration::from_secs(5)).await;
-
-    //
-    //
-    //
-    //
-    //
-    execute!(
-        stdout(),
-        SetForegroundColor(Color::Green),
-        Print("\n\n\nSTEP: creating a blockchain with the genesis\n\n"),
-        ResetColor
-    )?;
-    let blockchain_id = wallet_to_spend
-        .p()
-        .create_chain()
-        .subnet_id(created_subnet_id)
-        .genesis_data(chain_genesis_bytes.clone())
-        .vm_id(vm_id)
-        .chain_name(opts.chain_name.clone())
-        .dry_mode(true)
-        .issue()
-        .await
-        .unwrap();
-    log::info!("[dry mode] blockchain Id {blockchain_id} for subnet {created_subnet_id}");
 
-    let blockchain_id = wallet_to_spend
-        .p()
-        .create_chain()
-        .subnet_id(created_subnet_id)
-        .genesis_data(chain_genesis_bytes.clone())
-        .vm_id(vm_id)
-        .chain_name(opts.chain_name.clone())
-        .check_acceptance(true)
-        .issue()
-        .await
-        .unwrap();
-    log::info!("created a blockchain {blockchain_id} for subnet {created_subnet_id}");
-
-    if !opts.chain_config_local_path.is_empty() {
-        execute!(
-            stdout(),
-            SetForegroundColor(Color::Green),
-            Print("\n\n\nSTEP: sending SSM doc for chain-config updates\n\n"),
-            ResetColor
-        )?;
+    let seed_eth_addresses: Vec<String> = alloc.keys().map(|addr| format!("0x{addr}")).collect();
+    let mut genesis = subnet_evm_genesis::Genesis::new(seed_eth_addresses)?;
+    genesis.alloc = Some(alloc);
 
-        let file_stem = Path::new(&opts.chain_config_local_path)
-            .file_stem()
-            .unwrap();
-        let chain_config_s3_key = format!(
-            "{}{}",
-            s3::append_slash(&opts.s3_key_prefix),
-            file_stem.to_str().unwrap()
-        );
+    let mut chain_config = subnet_evm_genesis::ChainConfig::default();
+    if chain_id > 0 {
+        chain_config.chain_id = Some(chain_id);
+    }
 
-        // If a Subnet's chain id is 2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt,
-        // the config file for this chain is located at {chain-config-dir}/2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt/config.json.
-        let avalanched_args = format!("install-chain --log-level info --s3-region {region} --s3-bucket {s3_bucket} --chain-config-s3-key {chain_config_s3_key} --chain-config-local-path {chain_config_local_path}",
-            region = opts.s3_region,
-            s3_bucket = opts.s3_bucket,
-            chain_config_s3_key = chain_config_s3_key,
-            chain_config_local_path = format!("{}{}/config.json", s3::append_slash(&opts.chain_config_remote_dir), blockchain_id),
-        );
+    let mut fee_config = if fee_config.is_empty() {
+        subnet_evm_genesis::FeeConfig::default()
+    } else {
+        serde_json::from_str::<subnet_evm_genesis::FeeConfig>(fee_config).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("failed to parse --fee-config ({})", e),
+            )
+        })?
+    };
+    if gas_limit > 0 {
+        fee_config.gas_limit = Some(gas_limit);
+        genesis.gas_limit = primitive_types::U256::from(gas_limit);
+    }
+    chain_config.fee_config = Some(fee_config);
+    genesis.config = Some(chain_config);
 
-        for (region, instance_ids) in region_to_instance_ids.iter() {
-            if !opts.ssm_docs.contains_key(region) {
-                panic!(
-                    "--ssm-docs does not have the document name for the region '{}'",
-                    region
-                );
-            }
-            let ssm_doc = opts.ssm_docs.get(region).unwrap();
+    Ok(genesis)
+}
 
-            log::info!(
-                "sending SSM commands for the region '{region}' with instances {:?}",
-                instance_ids
-            );
-            let shared_config = aws_manager::load_config(
-                Some(region.clone()),
-                Some(opts.profile_name.clone()),
-                Some(Duration::from_secs(30)),
-            )
-            .await;
-            let regional_ssm_manager = ssm::Manager::new(&shared_config);
+/// Aliases reserved by avalanchego for the primary network chains and VMs.
+const RESERVED_CHAIN_ALIASES: &[&str] = &["P", "X", "C", "platform", "avm", "evm"];
 
-            // ref. <https://docs.aws.amazon.com/systems-manager/latest/APIReference/API_SendCommand.html>
-            let ssm_output = regional_ssm_manager
-                .cli
-                .send_command()
-                .document_name(ssm_doc.clone())
-                .set_instance_ids(Some(instance_ids.clone()))
-                .parameters("avalanchedArgs", vec![avalanched_args.clone()])
-                .output_s3_region(opts.s3_region.clone())
-                .output_s3_bucket_name(opts.s3_bucket.clone())
-                .output_s3_key_prefix(format!(
-                    "{}ssm-output-logs",
-                    s3::append_slash(&opts.s3_key_prefix)
-                ))
-                .send()
-                .await
-                .unwrap();
-            let ssm_output = ssm_output.command().unwrap();
-            let ssm_command_id = ssm_output.command_id().unwrap();
-            log::info!("sent SSM command {}", ssm_command_id);
-            sleep(Duration::from_secs(30)).await;
-
-            execute!(
-                stdout(),
-                SetForegroundColor(Color::Green),
-                Print("\n\n\nSTEP: checking the status of SSM command...\n\n"),
-                ResetColor
-            )?;
-            for instance_id in instance_ids.iter() {
-                let status = regional_ssm_manager
-                    .poll_command(
-                        ssm_command_id,
-                        instance_id,
-                        CommandInvocationStatus::Success,
-                        Duration::from_secs(300),
-                        Duration::from_secs(5),
-                    )
-                    .await
-                    .unwrap();
-                log::info!("status {:?} for instance id {}", status, instance_id);
-            }
-            sleep(Duration::from_secs(5)).await;
-        }
+/// Validates the blockchain alias, which is used in the RPC endpoint path (e.g., /ext/bc/[ALIAS]/rpc).
+pub fn validate_chain_alias(chain_alias: &str) -> io::Result<()> {
+    if RESERVED_CHAIN_ALIASES.contains(&chain_alias) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "chain alias '{chain_alias}' is reserved (reserved aliases {:?})",
+                RESERVED_CHAIN_ALIASES
+            ),
+        ));
+    }
+    if ids::Id::from_str(chain_alias).is_ok() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("chain alias '{chain_alias}' collides with the Id format"),
+        ));
+    }
+    if let Some(c) = chain_alias
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_'))
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "chain alias '{chain_alias}' has an illegal character '{c}' (must be ASCII letters, digits, '-', or '_')"
+            ),
+        ));
     }
+    Ok(())
+}
 
-    println!();
-    execute!(
-        stdout(),
-        SetForegroundColor(Color::Blue),
-        Print(format!(
-            "\n\n\nSUCCESS!\nsubnet Id: {created_subnet_id}\nblockchain Id: {blockchain_id}\n\n"
-        )),
-        ResetColor
-    )?;
+/// Registers the blockchain alias on the running node via "admin.aliasChain"
+/// (requires avalanchego "--api-admin-enabled").
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/admin#adminaliaschain>
+async fn register_chain_alias(
+    node_rpc_url: &str,
+    blockchain_id: &ids::Id,
+    chain_alias: &str,
+    rpc_headers: &[(String, String)],
+) -> io::Result<()> {
+    call_json_rpc(
+        node_rpc_url,
+        "/ext/admin",
+        "admin.aliasChain",
+        serde_json::json!({ "chain": blockchain_id.to_string(), "alias": chain_alias }),
+        rpc_headers,
+    )
+    .await
+    .map(|_| ())
+}
 
-    Ok(())
+/// Describes where the alias is in effect: registered on the running nodes,
+/// or only written to the aliases file (read by avalanchego at the next start,
+/// if it runs with "--chain-aliases-file" set to the same path).
+pub fn format_chain_alias_status(
+    chain_alias: &str,
+    chain_aliases_remote_path: &str,
+    registered: usize,
+    total: usize,
+) -> String {
+    if chain_alias.is_empty() {
+        return String::from("(none)");
+    }
+    if registered == total {
+        return format!("{chain_alias} (registered on all {total} node(s))");
+    }
+    format!(
+        "{chain_alias} (registered on {registered}/{total} node(s), the others after a restart with --chain-aliases-file '{chain_aliases_remote_path}')"
+    )
 }
 
 /// randomly wait to prevent UTXO double spends from the same wallet
@@ -1049,7 +7406,8 @@ async fn add_primary_network_validator(
     node_id: Arc<ids::node::Id>,
     stake_amount_in_navax: Arc<u64>,
     primary_network_validate_period_in_days: Arc<u64>,
-) {
+    tx_status_poller: Arc<Option<TxStatusPoller>>,
+) -> io::Result<(ids::Id, bool)> {
     let random_wait_dur = random_wait_dur.as_ref();
     log::info!(
         "adding '{node_id}' as a primary network validator after waiting random {:?}",
@@ -1061,18 +7419,32 @@ async fn add_primary_network_validator(
     let stake_amount_in_navax = stake_amount_in_navax.as_ref();
     let primary_network_validate_period_in_days = primary_network_validate_period_in_days.as_ref();
 
+    let _span = telemetry::span(
+        "add primary network validator",
+        &[("node_id", node_id.to_string())],
+    );
     let (tx_id, added) = wallet_to_spend
         .p()
         .add_validator()
         .node_id(*node_id)
         .stake_amount(*stake_amount_in_navax)
         .validate_period_in_days(*primary_network_validate_period_in_days, 60)
-        .check_acceptance(true)
         .issue()
         .await
-        .unwrap();
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed add_validator for '{node_id}' ({})", e),
+            )
+        })?;
+    if let (true, Some(poller)) = (added, tx_status_poller.as_ref()) {
+        poller
+            .wait(&format!("primary network validator {node_id}"), &tx_id)
+            .await?;
+    }
 
     log::info!("primary network validator tx id {}, added {}", tx_id, added);
+    Ok((tx_id, added))
 }
 
 /// randomly wait to prevent UTXO double spends from the same wallet
@@ -1083,7 +7455,8 @@ async fn add_primary_network_permissionless_validator(
     pop: Arc<key::bls::ProofOfPossession>,
     stake_amount_in_navax: Arc<u64>,
     primary_network_validate_period_in_days: Arc<u64>,
-) {
+    tx_status_poller: Arc<Option<TxStatusPoller>>,
+) -> io::Result<(ids::Id, bool)> {
     let random_wait_dur = random_wait_dur.as_ref();
     log::info!(
         "adding '{node_id}' as a primary network permissionless validator after waiting random {:?}",
@@ -1096,6 +7469,10 @@ async fn add_primary_network_permissionless_validator(
     let stake_amount_in_navax = stake_amount_in_navax.as_ref();
     let primary_network_validate_period_in_days = primary_network_validate_period_in_days.as_ref();
 
+    let _span = telemetry::span(
+        "add primary network permissionless validator",
+        &[("node_id", node_id.to_string())],
+    );
     let (tx_id, added) = wallet_to_spend
         .p()
         .add_permissionless_validator()
@@ -1103,12 +7480,35 @@ async fn add_primary_network_permissionless_validator(
         .proof_of_possession(pop.clone())
         .stake_amount(*stake_amount_in_navax)
         .validate_period_in_days(*primary_network_validate_period_in_days, 60)
-        .check_acceptance(true)
         .issue()
         .await
-        .unwrap();
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed add_validator for '{node_id}' ({})", e),
+            )
+        })?;
+    if let (true, Some(poller)) = (added, tx_status_poller.as_ref()) {
+        poller
+            .wait(&format!("primary network validator {node_id}"), &tx_id)
+            .await?;
+    }
 
     log::info!("primary network validator tx id {}, added {}", tx_id, added);
+    Ok((tx_id, added))
+}
+
+/// Warns that "--batch-validator-adds" falls back to one transaction per node,
+/// since each P-chain validator transaction (AddValidatorTx, AddPermissionlessValidatorTx,
+/// AddSubnetValidatorTx) adds a single validator.
+/// ref. <https://docs.avax.network/reference/avalanchego/p-chain/txn-format>
+fn warn_unbatched_validator_adds(ui: Ui, tx_kind: &str, nodes: usize) -> io::Result<()> {
+    ui.print(
+        Color::Yellow,
+        format!(
+            "\n\nWARNING: --batch-validator-adds is not supported for the {tx_kind} transactions (one validator per transaction), issuing {nodes} transaction(s), one per node\n\n"
+        ),
+    )
 }
 
 /// randomly wait to prevent UTXO double spends from the same wallet
@@ -1118,7 +7518,9 @@ async fn add_subnet_network_validator(
     node_id: Arc<String>,
     subnet_id: Arc<ids::Id>,
     subnet_validate_period_in_days: Arc<u64>,
-) {
+    subnet_validation_end_at: Arc<Option<u64>>,
+    tx_status_poller: Arc<Option<TxStatusPoller>>,
+) -> io::Result<(ids::Id, bool)> {
     let random_wait_dur = random_wait_dur.as_ref();
     log::info!(
         "adding '{node_id}' as a subnet validator '{subnet_id}' after waiting random {:?}",
@@ -1126,20 +7528,1230 @@ async fn add_subnet_network_validator(
     );
     sleep(*random_wait_dur).await;
 
-    let node_id = node::Id::from_str(&node_id).unwrap();
+    let node_id = node::Id::from_str(&node_id)?;
     let subnet_id = subnet_id.as_ref();
     let subnet_validate_period_in_days = subnet_validate_period_in_days.as_ref();
 
-    let (tx_id, added) = wallet_to_spend
+    let _span = telemetry::span(
+        "add subnet validator",
+        &[
+            ("node_id", node_id.to_string()),
+            ("subnet_id", subnet_id.to_string()),
+        ],
+    );
+
+    let tx = wallet_to_spend
         .p()
         .add_subnet_validator()
         .node_id(node_id)
         .subnet_id(*subnet_id)
-        .validate_period_in_days(*subnet_validate_period_in_days, 60)
-        .check_acceptance(true)
-        .issue()
-        .await
-        .unwrap();
+        .validate_period_in_days(*subnet_validate_period_in_days, 60);
+    // same end time for all nodes (only the start time differs)
+    let tx = match *subnet_validation_end_at {
+        Some(end_at) => tx.end_time(to_date_time(end_at)?),
+        None => tx,
+    };
+    let (tx_id, added) = tx.issue().await.map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed add_subnet_validator for '{node_id}' ({})", e),
+        )
+    })?;
+    if let (true, Some(poller)) = (added, tx_status_poller.as_ref()) {
+        poller
+            .wait(&format!("subnet validator {node_id}"), &tx_id)
+            .await?;
+    }
 
     log::info!("subnet validator tx id {}, added {}", tx_id, added);
+    Ok((tx_id, added))
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_generate_subnet_evm_genesis --exact --show-output
+#[test]
+fn test_generate_subnet_evm_genesis() {
+    let addr = "0x8db97C7cEcE249c2b98bDC0226Cc4C2A57BF52FC";
+
+    let genesis = generate_subnet_evm_genesis(
+        99999,
+        8000000,
+        "{\"targetBlockRate\":2}",
+        &[format!("{addr}:1000000000000000000")],
+    )
+    .unwrap();
+    let config = genesis.config.unwrap();
+    assert_eq!(config.chain_id, Some(99999));
+    assert_eq!(config.fee_config.unwrap().gas_limit, Some(8000000));
+    assert!(genesis
+        .alloc
+        .unwrap()
+        .contains_key("8db97c7cece249c2b98bdc0226cc4c2a57bf52fc"));
+
+    assert!(generate_subnet_evm_genesis(0, 0, "", &[]).is_err());
+    assert!(generate_subnet_evm_genesis(0, 0, "", &[addr.to_string()]).is_err());
+    assert!(generate_subnet_evm_genesis(0, 0, "", &["0x1234:100".to_string()]).is_err());
+    assert!(generate_subnet_evm_genesis(0, 0, "", &[format!("{addr}:abc")]).is_err());
+    assert!(generate_subnet_evm_genesis(0, 0, "{", &[format!("{addr}:100")]).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_install_subnet_args --exact --show-output
+#[test]
+fn test_install_subnet_args() {
+    let cmd = command();
+    let matches = cmd
+        .try_get_matches_from(vec![
+            NAME,
+            "--s3-region=us-west-2",
+            "--s3-bucket=test",
+            "--s3-key-prefix=test",
+            "--chain-rpc-url=http://localhost:9650",
+            "--key=test",
+            "--vm-binary-local-path=/tmp/vm",
+            "--vm-binary-remote-dir=/data/plugins",
+            "--chain-name=test",
+            "--avalanchego-config-remote-path=/data/avalanche-configs/config.json",
+        ])
+        .unwrap();
+    let track_subnets_mode = matches.get_one::<String>("TRACK_SUBNETS_MODE").unwrap();
+    assert_eq!(track_subnets_mode, "merge");
+
+    let args = install_subnet_args(
+        "us-west-2",
+        "test",
+        "test/vm",
+        "/data/plugins/vm",
+        "2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt",
+        "/data/avalanche-configs/config.json",
+        track_subnets_mode,
+    );
+    assert!(args.contains("--track-subnets-mode merge"));
+    assert!(
+        args.contains("--subnet-id-to-track 2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt")
+    );
+    assert!(args.contains("--vm-binary-s3-key test/vm --vm-binary-local-path /data/plugins/vm"));
+
+    // "--skip-vm-binary-upload" does not require the local VM binary
+    assert!(command()
+        .try_get_matches_from(vec![
+            NAME,
+            "--s3-region=us-west-2",
+            "--s3-bucket=test",
+            "--s3-key-prefix=test",
+            "--chain-rpc-url=http://localhost:9650",
+            "--key=test",
+            "--skip-vm-binary-upload",
+            "--vm-binary-remote-dir=/data/plugins",
+            "--chain-name=test",
+            "--avalanchego-config-remote-path=/data/avalanche-configs/config.json",
+        ])
+        .is_ok());
+    let args = install_subnet_args(
+        "us-west-2",
+        "test",
+        "",
+        "/data/plugins/vm",
+        "2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt",
+        "/data/avalanche-configs/config.json",
+        track_subnets_mode,
+    );
+    assert!(!args.contains("--vm-binary-s3-key"));
+    assert!(!args.contains("--vm-binary-local-path"));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_build_output_owners --exact --show-output
+#[test]
+fn test_build_output_owners() {
+    let addr1 = ids::short::Id::from_slice(&[1; 20]);
+    let addr2 = ids::short::Id::from_slice(&[2; 20]);
+    let addr3 = ids::short::Id::from_slice(&[3; 20]);
+
+    let owners = build_output_owners(&[addr3.clone(), addr1.clone(), addr2.clone()], 2).unwrap();
+    assert_eq!(owners.threshold, 2);
+    assert_eq!(owners.locktime, 0);
+    assert_eq!(
+        owners.addresses,
+        vec![addr1.clone(), addr2.clone(), addr3.clone()]
+    );
+
+    assert!(build_output_owners(&[], 1).is_err());
+    assert!(build_output_owners(std::slice::from_ref(&addr1), 0).is_err());
+    assert!(build_output_owners(&[addr1.clone(), addr2], 3).is_err());
+    assert!(build_output_owners(&[addr1.clone(), addr1], 1).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_parse_control_keys --exact --show-output
+#[test]
+fn test_parse_control_keys() {
+    let k = key::secp256k1::private_key::Key::generate().unwrap();
+    let addr = k.to_public_key().to_hrp_address(5, "P").unwrap();
+    let short_addr = k.to_public_key().to_short_id().unwrap();
+
+    let control_keys = parse_control_keys(
+        &[addr.clone(), format!(" {}", addr.trim_start_matches("P-"))],
+        5,
+    )
+    .unwrap();
+    assert_eq!(
+        control_keys,
+        vec![
+            (addr.clone(), short_addr.clone()),
+            (addr.clone(), short_addr)
+        ]
+    );
+
+    let err = parse_control_keys(&[addr], 1).unwrap_err();
+    assert!(err.to_string().contains("is not on network 1"));
+    assert!(parse_control_keys(&[String::from("P-fuji1invalid")], 5).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_merge_json_overrides --exact --show-output
+#[test]
+fn test_merge_json_overrides() {
+    let merged = merge_json_overrides(
+        r#"{"validatorOnly":false,"proposerMinBlockDelay":1000000000,"consensusParameters":{"k":20,"alpha":15}}"#,
+        r#"{"validatorOnly":true,"consensusParameters":{"k":10}}"#,
+    )
+    .unwrap();
+    let merged: serde_json::Value = serde_json::from_str(&merged).unwrap();
+    assert_eq!(
+        merged,
+        serde_json::json!({
+            "validatorOnly": true,
+            "proposerMinBlockDelay": 1000000000,
+            "consensusParameters": {"k": 10, "alpha": 15},
+        })
+    );
+
+    assert!(merge_json_overrides("{}", "[]").is_err());
+    assert!(merge_json_overrides("[]", "{}").is_err());
+    assert!(merge_json_overrides("{", "{}").is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_is_existing_validator_error --exact --show-output
+#[test]
+fn test_is_existing_validator_error() {
+    assert!(is_existing_validator_error(
+        "failed add_subnet_validator for 'NodeID-7Xhw2mDxuDS44j42TCB6U5579esbSt3Lg' (failed to issue tx: duplicate validator)"
+    ));
+    assert!(is_existing_validator_error(
+        "NodeID-7Xhw2mDxuDS44j42TCB6U5579esbSt3Lg is already a validator of the subnet"
+    ));
+    assert!(!is_existing_validator_error("insufficient funds"));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_staking_asset --exact --show-output
+#[test]
+fn test_staking_asset() {
+    let avax = StakingAsset::new("", "", 9).unwrap();
+    assert_eq!(avax.symbol(), "AVAX");
+    assert_eq!(avax.base_symbol(), "nAVAX");
+    assert_eq!(
+        avax.to_base_units(2000),
+        primitive_types::U256::from(2_000_000_000_000_u64)
+    );
+    assert_eq!(
+        avax.to_display_units(primitive_types::U256::from(2_500_000_000_u64)),
+        primitive_types::U256::from(2)
+    );
+
+    let custom =
+        StakingAsset::new("FvwEAhmxKfeiG8SnEvq42hc6whRyY3EFYAvebMqDNDGCgxN5Z", "", 6).unwrap();
+    assert!(custom.asset_id.is_some());
+    assert_eq!(
+        custom.to_base_units(5),
+        primitive_types::U256::from(5_000_000)
+    );
+
+    assert!(StakingAsset::new("invalid", "", 9).is_err());
+    assert!(StakingAsset::new("", "", 19).is_err());
+
+    let labeled = StakingAsset::new("", "FOO", 6).unwrap();
+    assert_eq!(labeled.symbol(), "FOO");
+    assert_eq!(labeled.base_symbol(), "FOO base units");
+    assert_eq!(
+        labeled.to_display_units(primitive_types::U256::from(7_000_000)),
+        primitive_types::U256::from(7)
+    );
+    assert_eq!(
+        StakingAsset::new("", "AVAX", 9).unwrap().base_symbol(),
+        "nAVAX"
+    );
+    assert!(StakingAsset::new("", "FOO BAR", 9).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_parse_rpc_headers --exact --show-output
+#[test]
+fn test_parse_rpc_headers() {
+    let parsed = parse_rpc_headers(&[
+        String::from("x-api-key: secret"),
+        String::from("Authorization: Basic dXNlcjpwYXNz"),
+    ])
+    .unwrap();
+    assert_eq!(
+        parsed,
+        vec![
+            (String::from("x-api-key"), String::from("secret")),
+            (
+                String::from("Authorization"),
+                String::from("Basic dXNlcjpwYXNz")
+            ),
+        ]
+    );
+    assert_eq!(redact_rpc_header("x-api-key: secret"), "x-api-key: ***");
+
+    assert!(parse_rpc_headers(&[String::from("no-colon")]).is_err());
+    assert!(parse_rpc_headers(&[String::from("bad name: value")]).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_build_node_config --exact --show-output
+#[test]
+fn test_build_node_config() {
+    let subnet_id =
+        ids::Id::from_str("2PsShLjrFFwR51DMcAh8pyuwzLn1Ym3zRhuXLTmLCR1STk2mL6").unwrap();
+    let blockchain_id =
+        ids::Id::from_str("2q9e4r6Mu3U68nU1fYjgbR6JvwrRx36CohpAX5UQxse55x1Q5").unwrap();
+
+    let node_config = build_node_config(
+        &subnet_id,
+        &blockchain_id,
+        "/data/subnet-configs/",
+        "/data/chain-configs",
+        "config.json",
+        "/data/chain-aliases.json",
+        "mychain",
+    );
+    assert_eq!(node_config.track_subnets, subnet_id.to_string());
+    assert_eq!(
+        node_config.subnet_config_dir,
+        Some(String::from("/data/subnet-configs"))
+    );
+    assert_eq!(
+        node_config.subnet_config_file,
+        Some(format!("/data/subnet-configs/{subnet_id}.json"))
+    );
+    assert_eq!(
+        node_config.chain_config_file,
+        Some(format!("/data/chain-configs/{blockchain_id}/config.json"))
+    );
+    assert_eq!(
+        node_config.chain_aliases.get(&blockchain_id.to_string()),
+        Some(&vec![String::from("mychain")])
+    );
+
+    let node_config = build_node_config(
+        &subnet_id,
+        &blockchain_id,
+        "",
+        "/data/chain-configs",
+        "vm.toml",
+        "",
+        "",
+    );
+    assert_eq!(
+        node_config.chain_config_file,
+        Some(format!("/data/chain-configs/{blockchain_id}/vm.toml"))
+    );
+
+    let node_config = build_node_config(&subnet_id, &blockchain_id, "", "", "config.json", "", "");
+    let encoded = serde_json::to_string(&node_config).unwrap();
+    assert_eq!(encoded, format!("{{\"track-subnets\":\"{subnet_id}\"}}"));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_rpc_host_port --exact --show-output
+#[test]
+fn test_rpc_host_port() {
+    assert_eq!(
+        rpc_host_port("http://localhost:9650").unwrap(),
+        (String::from("localhost"), 9650)
+    );
+    assert_eq!(
+        rpc_host_port("https://api.avax.network").unwrap(),
+        (String::from("api.avax.network"), 443)
+    );
+    assert_eq!(
+        rpc_host_port("http://[::1]:9650/ext/bc/P").unwrap(),
+        (String::from("::1"), 9650)
+    );
+    assert!(rpc_host_port("localhost:9650").is_err());
+    assert!(rpc_host_port("not a url").is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_hash_map_string_to_string_parser --exact --show-output
+#[test]
+fn test_hash_map_string_to_string_parser() {
+    use clap::builder::TypedValueParser;
+    use std::ffi::OsStr;
+
+    let cmd = command();
+    let parser = HashMapStringToStringParser {};
+
+    let m = parser
+        .parse_ref(&cmd, None, OsStr::new(r#"{"a":"1","b":"2"}"#))
+        .unwrap();
+    assert_eq!(m.len(), 2);
+    assert_eq!(m.get("a"), Some(&String::from("1")));
+    assert_eq!(m.get("b"), Some(&String::from("2")));
+
+    assert!(parser
+        .parse_ref(&cmd, None, OsStr::new("{}"))
+        .unwrap()
+        .is_empty());
+
+    for invalid in [r#"{"a":"1""#, "", "[]", r#"{"a":1}"#, r#"{"a":null}"#] {
+        let err = parser
+            .parse_ref(&cmd, None, OsStr::new(invalid))
+            .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+    }
+
+    // non-UTF8 input is parsed as empty, thus rejected as invalid JSON
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        let err = parser
+            .parse_ref(&cmd, None, OsStr::from_bytes(&[0x7b, 0xff, 0x7d]))
+            .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_target_nodes_parser --exact --show-output
+#[test]
+fn test_target_nodes_parser() {
+    use clap::builder::TypedValueParser;
+    use std::ffi::OsStr;
+
+    let cmd = command();
+    let without_region =
+        r#"{"NodeID-a":{"machine_id":"i-a"},"NodeID-b":{"region":"us-west-2","machine_id":"i-b"}}"#;
+
+    let m = TargetNodesParser {}
+        .parse_ref(&cmd, None, OsStr::new(without_region))
+        .unwrap();
+    assert_eq!(m.len(), 2);
+    assert_eq!(
+        m.get("NodeID-a"),
+        Some(&avalanche_ops::aws::spec::RegionMachineId {
+            region: String::new(),
+            machine_id: String::from("i-a"),
+        })
+    );
+    assert_eq!(m.get("NodeID-b").unwrap().region, "us-west-2");
+    assert!(TargetNodesParser {}
+        .parse_ref(
+            &cmd,
+            None,
+            OsStr::new(r#"{"NodeID-a":{"region":"us-west-2"}}"#)
+        )
+        .is_err());
+
+    // the shared spec type stays strict, e.g., for "deploy-report"
+    assert!(HashMapStringToRegionInstanceIdParser {}
+        .parse_ref(&cmd, None, OsStr::new(without_region))
+        .is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_format_step_timings --exact --show-output
+#[test]
+fn test_format_step_timings() {
+    let step_timings = vec![
+        StepTiming {
+            step: Step::CreateSubnet,
+            duration_ms: 12_345,
+            skipped: false,
+        },
+        StepTiming {
+            step: Step::AddSubnetValidators,
+            duration_ms: 600_000,
+            skipped: false,
+        },
+        StepTiming {
+            step: Step::UploadSubnetConfig,
+            duration_ms: 0,
+            skipped: true,
+        },
+    ];
+    assert_eq!(
+        format_step_timings(&step_timings),
+        "creating a subnet             12.3s
+adding subnet validators     600.0s
+uploading subnet config        0.0s  (skipped)
+total                        612.3s"
+    );
+    assert_eq!(format_step_timings(&[]), "total       0.0s");
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_check_ssm_docs --exact --show-output
+#[test]
+fn test_check_ssm_docs() {
+    let mut region_to_instance_ids = HashMap::new();
+    region_to_instance_ids.insert(String::from("us-west-2"), vec![String::from("i-1")]);
+    region_to_instance_ids.insert(String::from("eu-west-1"), vec![String::from("i-2")]);
+    region_to_instance_ids.insert(String::from("ap-northeast-1"), vec![String::from("i-3")]);
+
+    let mut region_to_ssm_doc = HashMap::new();
+    region_to_ssm_doc.insert(String::from("us-west-2"), String::from("doc"));
+    let err = check_ssm_docs(&region_to_instance_ids, &region_to_ssm_doc).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err
+        .to_string()
+        .contains(r#"["ap-northeast-1", "eu-west-1"]"#));
+
+    region_to_ssm_doc.insert(String::from("eu-west-1"), String::from("doc"));
+    region_to_ssm_doc.insert(String::from("ap-northeast-1"), String::from("doc"));
+    assert!(check_ssm_docs(&region_to_instance_ids, &region_to_ssm_doc).is_ok());
+    assert!(check_ssm_docs(&HashMap::new(), &HashMap::new()).is_ok());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_validate_subnet_validation_end_at --exact --show-output
+#[test]
+fn test_validate_subnet_validation_end_at() {
+    const DAY: u64 = 24 * 60 * 60;
+
+    assert_eq!(parse_timestamp("1688169600").unwrap(), 1688169600);
+    assert_eq!(parse_timestamp("2023-07-01T00:00:00Z").unwrap(), 1688169600);
+    assert_eq!(
+        parse_timestamp("2023-07-01T09:00:00+09:00").unwrap(),
+        1688169600
+    );
+    assert!(parse_timestamp("2023-07-01").is_err());
+    assert!(parse_timestamp("1969-12-31T00:00:00Z").is_err());
+
+    let now = 1688169600;
+    let start = now + VALIDATION_START_OFFSET_SECS;
+
+    // fuji: at least 1 day, before the 16-day primary network validation ends
+    assert!(validate_subnet_validation_end_at(start + 14 * DAY, now, 16, 5).is_ok());
+    assert!(validate_subnet_validation_end_at(start + DAY, now, 16, 5).is_ok());
+    assert!(validate_subnet_validation_end_at(start + DAY - 1, now, 16, 5).is_err());
+    assert!(validate_subnet_validation_end_at(now, now, 16, 5).is_err());
+    assert!(validate_subnet_validation_end_at(start + 16 * DAY, now, 16, 5).is_err());
+    assert!(validate_subnet_validation_end_at(start + 16 * DAY - 1, now, 16, 5).is_ok());
+
+    // mainnet: at least 2 weeks
+    assert!(validate_subnet_validation_end_at(start + 7 * DAY, now, 30, 1).is_err());
+    assert!(validate_subnet_validation_end_at(start + 14 * DAY, now, 30, 1).is_ok());
+
+    // at most a year
+    assert!(validate_subnet_validation_end_at(start + 366 * DAY, now, 400, 5).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_check_subnet_end_before_primary --exact --show-output
+#[test]
+fn test_check_subnet_end_before_primary() {
+    let result = serde_json::json!({
+        "validators": [
+            {"nodeID": "NodeID-1", "startTime": "1600000000", "endTime": "1700000000"},
+            {"nodeID": "NodeID-2", "startTime": "1600000000", "endTime": "1690000000"}
+        ]
+    });
+    let end_times = parse_validator_end_times(&result).unwrap();
+    assert_eq!(end_times["NodeID-1"], 1700000000);
+    assert_eq!(end_times["NodeID-2"], 1690000000);
+    assert!(parse_validator_end_times(&serde_json::json!({})).is_err());
+    assert!(parse_validator_end_times(
+        &serde_json::json!({"validators": [{"nodeID": "NodeID-1", "endTime": "x"}]})
+    )
+    .is_err());
+
+    let node_ids = vec![String::from("NodeID-1"), String::from("NodeID-2")];
+    assert!(check_subnet_end_before_primary(1689999999, &node_ids, &end_times).is_ok());
+
+    // the existing primary validator "NodeID-2" ends first
+    let err = check_subnet_end_before_primary(1695000000, &node_ids, &end_times).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("'NodeID-2'"));
+    assert!(!err.to_string().contains("'NodeID-1'"));
+    assert!(check_subnet_end_before_primary(1690000000, &node_ids, &end_times).is_err());
+
+    let err =
+        check_subnet_end_before_primary(1, &[String::from("NodeID-3")], &end_times).unwrap_err();
+    assert!(err.to_string().contains("not a primary network validator"));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_parse_node_ids --exact --show-output
+#[test]
+fn test_parse_node_ids() {
+    let valid = "NodeID-7Xhw2mDxuDS44j42TCB6U5579esbSt3Lg";
+    let parsed = parse_node_ids(vec![("target nodes", valid), ("--node-rpc-urls", valid)]).unwrap();
+    assert_eq!(parsed.len(), 1);
+    assert_eq!(parsed[valid].to_string(), valid);
+
+    let err = parse_node_ids(vec![
+        ("target nodes", valid),
+        ("target nodes", "NodeID-invalid"),
+        ("--allowed-nodes", "foo"),
+        ("--node-rpc-urls", ""),
+    ])
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let msg = err.to_string();
+    assert!(msg.starts_with("3 invalid node id(s): "));
+    assert!(msg.contains("'NodeID-invalid' in target nodes"));
+    assert!(msg.contains("'foo' in --allowed-nodes"));
+    assert!(msg.contains("'' in --node-rpc-urls"));
+
+    assert!(parse_node_ids(Vec::new()).unwrap().is_empty());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_is_transient_rpc_error --exact --show-output
+#[test]
+fn test_is_transient_rpc_error() {
+    assert!(is_transient_rpc_error(ErrorKind::TimedOut));
+    assert!(is_transient_rpc_error(ErrorKind::ConnectionRefused));
+    assert!(is_transient_rpc_error(ErrorKind::WouldBlock));
+    assert!(!is_transient_rpc_error(ErrorKind::Other));
+    assert!(!is_transient_rpc_error(ErrorKind::InvalidInput));
+    assert!(!is_transient_rpc_error(ErrorKind::NotFound));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_is_rate_limited_error --exact --show-output
+#[test]
+fn test_is_rate_limited_error() {
+    assert!(is_rate_limited_error("HTTP 429 Too Many Requests"));
+    assert!(is_rate_limited_error("rate limit exceeded"));
+    assert!(!is_rate_limited_error("connection refused"));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_install_output_nodes --exact --show-output
+#[test]
+fn test_install_output_nodes() {
+    let node = |node_id: &str, region: &str, validator: bool| NodeResult {
+        node_id: node_id.to_string(),
+        region: region.to_string(),
+        instance_id: format!("i-{node_id}"),
+        validator,
+        primary_validator_tx: None,
+        subnet_validator_tx: None,
+        ssm_command_ids: Vec::new(),
+        status: NodeStatus::Pending,
+    };
+    let mut output = InstallOutput {
+        nodes: vec![
+            node("a", "us-west-2", true),
+            node("b", "eu-west-1", true),
+            node("c", "us-west-2", false),
+        ],
+        ..Default::default()
+    };
+
+    output.add_ssm_commands(vec![
+        (
+            String::from("cmd-1"),
+            vec![String::from("i-a"), String::from("i-c")],
+        ),
+        (String::from("cmd-2"), vec![String::from("i-b")]),
+    ]);
+    assert_eq!(output.ssm_command_ids, vec!["cmd-1", "cmd-2"]);
+    assert_eq!(output.nodes[0].ssm_command_ids, vec!["cmd-1"]);
+    assert_eq!(output.nodes[1].ssm_command_ids, vec!["cmd-2"]);
+    assert_eq!(output.nodes[2].ssm_command_ids, vec!["cmd-1"]);
+
+    let tx_id = ids::Id::from_str("2PsShLjrFFwR51DMcAh8pyuwzLn1Ym3zRhuXLTmLCR1STk2mL6").unwrap();
+    output.node_mut("a").unwrap().subnet_validator_tx = Some(tx_id);
+    output.node_mut("b").unwrap().status = NodeStatus::AlreadyValidating;
+    assert!(output.node_mut("d").is_none());
+
+    output.complete_nodes();
+    assert_eq!(output.nodes[0].status, NodeStatus::Installed);
+    assert_eq!(output.nodes[1].status, NodeStatus::AlreadyValidating);
+    assert_eq!(output.nodes[2].status, NodeStatus::Installed);
+
+    assert_eq!(
+        format_node_result(&output.nodes[0]),
+        "a\tus-west-2\ti-a\tInstalled\tprimary tx -\tsubnet tx 2PsShLjrFFwR51DMcAh8pyuwzLn1Ym3zRhuXLTmLCR1STk2mL6\tSSM commands cmd-1"
+    );
+    assert_eq!(
+        format_node_result(&output.nodes[2]),
+        "c\tus-west-2\ti-c\tInstalled\tprimary tx -\tsubnet tx (non-validating)\tSSM commands cmd-1"
+    );
+    assert_eq!(
+        format_region_results(&output.nodes),
+        vec![
+            "eu-west-1\t1 node(s)\tSSM commands cmd-2",
+            "us-west-2\t2 node(s)\tSSM commands cmd-1",
+        ]
+    );
+
+    let encoded = serde_json::to_string(&output.nodes[1]).unwrap();
+    assert!(encoded.contains(r#""status":"already_validating""#));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_is_transient_ssm_failure --exact --show-output
+#[test]
+fn test_is_transient_ssm_failure() {
+    assert!(is_transient_ssm_failure("Undeliverable", -1));
+    assert!(is_transient_ssm_failure("DeliveryTimedOut", -1));
+    assert!(is_transient_ssm_failure("Terminated", -1));
+    assert!(is_transient_ssm_failure("Failed", -1));
+    assert!(is_transient_ssm_failure("Failed", 143));
+    assert!(is_transient_ssm_failure("ExecutionTimedOut", 137));
+
+    assert!(!is_transient_ssm_failure("Failed", 1));
+    assert!(!is_transient_ssm_failure("Failed", 2));
+    assert!(!is_transient_ssm_failure("ExecutionTimedOut", 1));
+    assert!(!is_transient_ssm_failure("Canceled", -1));
+    assert!(!is_transient_ssm_failure("Success", 0));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_derive_vm_id --exact --show-output
+#[test]
+fn test_derive_vm_id() {
+    assert_eq!(
+        derive_vm_id(VM_ID_DERIVATION_NAME, "subnetevm", "").unwrap(),
+        subnet::vm_name_to_id("subnetevm").unwrap()
+    );
+
+    let mut f = tempfile::NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut f, b"abc").unwrap();
+    let vm_binary_local_path = f.path().display().to_string();
+    let vm_id = derive_vm_id(
+        VM_ID_DERIVATION_SHA256_BINARY,
+        "subnetevm",
+        &vm_binary_local_path,
+    )
+    .unwrap();
+    // SHA-256 of "abc"
+    assert_eq!(
+        vm_id,
+        ids::Id::from_slice(
+            &hex::decode("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+                .unwrap()
+        )
+    );
+
+    assert!(derive_vm_id(VM_ID_DERIVATION_SHA256_BINARY, "subnetevm", "").is_err());
+    assert!(derive_vm_id("unknown", "subnetevm", "").is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_check_id_prediction --exact --show-output
+#[test]
+fn test_check_id_prediction() {
+    let predicted =
+        ids::Id::from_str("2PsShLjrFFwR51DMcAh8pyuwzLn1Ym3zRhuXLTmLCR1STk2mL6").unwrap();
+    let created = ids::Id::from_str("2q9e4r6Mu3U68nU1fYjgbR6JvwrRx36CohpAX5UQxse55x1Q5").unwrap();
+
+    assert_eq!(
+        check_id_prediction("subnet", &predicted, &predicted, true).unwrap(),
+        None
+    );
+
+    let warning = check_id_prediction("subnet", &predicted, &created, false)
+        .unwrap()
+        .unwrap();
+    assert!(warning.contains(&created.to_string()));
+    assert!(warning.contains(&predicted.to_string()));
+
+    let err = check_id_prediction("blockchain", &predicted, &created, true).unwrap_err();
+    assert!(err.to_string().contains("--strict-id-prediction"));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_check_remote_dir --exact --show-output
+#[test]
+fn test_check_remote_dir() {
+    assert!(check_remote_dir("--subnet-config-remote-dir", "").is_ok());
+    assert!(check_remote_dir("--subnet-config-remote-dir", "/data/subnet-configs").is_ok());
+    assert!(check_remote_dir("--chain-config-remote-dir", "/data/chain-configs/").is_ok());
+
+    let err = check_remote_dir("--chain-config-remote-dir", "./data/chain-configs").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("'/data/chain-configs'"));
+    assert!(check_remote_dir("--subnet-config-remote-dir", "data/subnet-configs").is_err());
+
+    assert!(check_remote_filename("--chain-config-remote-filename", "config.json").is_ok());
+    assert!(check_remote_filename("--chain-config-remote-filename", "").is_err());
+    assert!(check_remote_filename("--chain-config-remote-filename", "..").is_err());
+    assert!(check_remote_filename("--chain-config-remote-filename", "sub/config.json").is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_find_blockchain --exact --show-output
+#[test]
+fn test_find_blockchain() {
+    let blockchain_id =
+        ids::Id::from_str("2PsShLjrFFwR51DMcAh8pyuwzLn1Ym3zRhuXLTmLCR1STk2mL6").unwrap();
+    let subnet_id = ids::Id::from_str("FvwEAhmxKfeiG8SnEvq42hc6whRyY3EFYAvebMqDNDGCgxN5Z").unwrap();
+    let vm_id = ids::Id::from_str("srEXiWaHuhNyGwPUi444Tu47ZEDwxTWrbQiuD7FmgSAQ6X7Dy").unwrap();
+    let result = serde_json::json!({
+        "blockchains": [
+            {
+                "id": blockchain_id.to_string(),
+                "name": "subnetevm",
+                "subnetID": subnet_id.to_string(),
+                "vmID": vm_id.to_string(),
+            }
+        ]
+    });
+
+    assert!(find_blockchain(&result, &blockchain_id, &subnet_id, &vm_id).unwrap());
+    assert!(!find_blockchain(&result, &subnet_id, &subnet_id, &vm_id).unwrap());
+    assert!(!find_blockchain(&serde_json::json!({}), &blockchain_id, &subnet_id, &vm_id).unwrap());
+
+    let err = find_blockchain(&result, &blockchain_id, &blockchain_id, &vm_id).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_check_max_validators --exact --show-output
+#[test]
+fn test_check_max_validators() {
+    assert!(check_max_validators(0, 50).is_ok());
+    assert!(check_max_validators(50, 50).is_ok());
+
+    let err = check_max_validators(51, 50).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("--max-validators 51"));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_format_bootstrap_report --exact --show-output
+#[test]
+fn test_format_bootstrap_report() {
+    let statuses = vec![
+        BootstrapStatus {
+            node_id: String::from("NodeID-A"),
+            rpc_url: String::from("http://10.0.0.1:9650"),
+            bootstrapped: Some(true),
+            height: Some(12),
+            attempts: 1,
+            last_error: None,
+        },
+        BootstrapStatus {
+            node_id: String::from("NodeID-B"),
+            rpc_url: String::from("http://10.0.0.2:9650"),
+            bootstrapped: Some(false),
+            height: None,
+            attempts: 7,
+            last_error: Some(String::from("connection refused")),
+        },
+        BootstrapStatus::unknown("NodeID-C"),
+    ];
+    let report = format_bootstrap_report(&statuses);
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(
+        lines[0],
+        "NodeID-A\tbootstrapped\theight 12\tattempts 1\thttp://10.0.0.1:9650"
+    );
+    assert!(lines[1].starts_with("NodeID-B\tNOT bootstrapped\theight -\tattempts 7"));
+    assert!(lines[1].ends_with("last error: connection refused"));
+    assert_eq!(lines[2], "NodeID-C\tunknown\theight -\tattempts 0\t");
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_check_subnet_auth --exact --show-output
+#[test]
+fn test_check_subnet_auth() {
+    let subnet_id =
+        ids::Id::from_str("2PsShLjrFFwR51DMcAh8pyuwzLn1Ym3zRhuXLTmLCR1STk2mL6").unwrap();
+    let result = serde_json::json!({
+        "subnets": [{
+            "id": "2PsShLjrFFwR51DMcAh8pyuwzLn1Ym3zRhuXLTmLCR1STk2mL6",
+            "controlKeys": ["P-fuji1a", "P-fuji1b", "P-fuji1c"],
+            "threshold": "2"
+        }]
+    });
+    let (control_keys, threshold) = parse_subnet_owners(&result, &subnet_id).unwrap();
+    assert_eq!(control_keys.len(), 3);
+    assert_eq!(threshold, 2);
+    let err = parse_subnet_owners(&serde_json::json!({ "subnets": [] }), &subnet_id).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    let signers = |ss: &[&str]| ss.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+    assert_eq!(
+        check_subnet_auth(
+            &control_keys,
+            threshold,
+            &signers(&["P-fuji1c", "P-fuji1a"])
+        )
+        .unwrap(),
+        vec![0, 2]
+    );
+    // extra signers beyond the threshold are not used
+    assert_eq!(
+        check_subnet_auth(
+            &control_keys,
+            threshold,
+            &signers(&["P-fuji1b", "P-fuji1c", "P-fuji1a"])
+        )
+        .unwrap(),
+        vec![0, 1]
+    );
+    assert!(
+        check_subnet_auth(&control_keys, threshold, &signers(&["P-fuji1a"]))
+            .unwrap_err()
+            .to_string()
+            .starts_with("subnet requires 2 of 3 control keys to sign, got 1")
+    );
+    assert!(check_subnet_auth(
+        &control_keys,
+        threshold,
+        &signers(&["P-fuji1a", "P-fuji1a"])
+    )
+    .unwrap_err()
+    .to_string()
+    .starts_with("duplicate subnet signers"));
+    assert!(check_subnet_auth(
+        &control_keys,
+        threshold,
+        &signers(&["P-fuji1a", "P-fuji1x"])
+    )
+    .unwrap_err()
+    .to_string()
+    .contains("are not control keys of the subnet"));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_install_plan_to_mermaid --exact --show-output
+#[test]
+fn test_install_plan_to_mermaid() {
+    let mut plan = InstallPlan {
+        network_name: String::from("fuji"),
+        target_node_count: 3,
+        regions: vec![String::from("eu-west-1"), String::from("us-west-2")],
+        validator_count: 2,
+        existing_subnet_id: None,
+        staking_amount: String::from("1 AVAX"),
+        primary_network_validate_period_in_days: 16,
+        subnet_validate_period_in_days: 14,
+        backend: String::from("ec2"),
+        chain_name: String::from("my \"evm\""),
+        vm_id: String::from("srEXiWaHuhNyGwPUi444Tu47ZEDwxTWrbQiuD7FmgSAQ6X7Dy"),
+        exit_after_create_subnet: false,
+        chain_config: true,
+        chain_alias: String::new(),
+    };
+    let mermaid = plan.to_mermaid();
+    assert!(mermaid.starts_with("flowchart TD\n"));
+    assert!(mermaid.contains("track[\"track subnet (SSM)<br/>3 node(s)\"]"));
+    assert!(mermaid.contains("chain[\"create chain 'my #quot;evm#quot;'"));
+    assert!(mermaid.contains(
+        "    nodes --> primary_validators\n    primary_validators --> subnet\n    subnet --> track\n    track --> subnet_validators\n    subnet_validators --> chain\n    chain --> chain_config\n"
+    ));
+    assert!(!mermaid.contains("chain_alias"));
+
+    plan.existing_subnet_id = Some(String::from(
+        "2PsShLjrFFwR51DMcAh8pyuwzLn1Ym3zRhuXLTmLCR1STk2mL6",
+    ));
+    plan.backend = String::from("anr");
+    plan.chain_alias = String::from("evm");
+    let mermaid = plan.to_mermaid();
+    assert!(!mermaid.contains("primary_validators"));
+    // resuming still uploads the VM binary and tracks the subnet
+    assert!(mermaid.contains("    nodes --> subnet\n    subnet --> track\n    track --> chain\n"));
+    assert!(mermaid.contains("track[\"track subnet (ANR)<br/>3 node(s)\"]"));
+    assert!(mermaid.contains("chain_alias[\"alias chain 'evm' (ANR)\"]"));
+
+    plan.exit_after_create_subnet = true;
+    assert!(plan.to_mermaid().ends_with("    subnet --> track\n"));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_is_tx_committed --exact --show-output
+#[test]
+fn test_is_tx_committed() {
+    assert!(is_tx_committed("Committed").unwrap());
+    assert!(!is_tx_committed("Processing").unwrap());
+    assert!(!is_tx_committed("Unknown").unwrap());
+    assert_eq!(
+        is_tx_committed("Dropped").unwrap_err().to_string(),
+        "transaction status 'Dropped'"
+    );
+    assert!(is_tx_committed("Aborted").is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_file_not_found_error --exact --show-output
+#[test]
+fn test_file_not_found_error() {
+    let file_names = vec![
+        String::from("genesis.json"),
+        String::from("subnet_evm"),
+        String::from("subnet-config.json"),
+    ];
+    assert_eq!(
+        suggest_file_name("subnet-evm", &file_names),
+        Some("subnet_evm")
+    );
+    assert_eq!(
+        suggest_file_name("Genesis.JSON", &file_names),
+        Some("genesis.json")
+    );
+    assert_eq!(suggest_file_name("chain-config.yaml", &file_names), None);
+    assert_eq!(suggest_file_name("", &file_names), None);
+
+    let dir = tempfile::tempdir().unwrap();
+    for f in file_names.iter() {
+        File::create(dir.path().join(f)).unwrap();
+    }
+    let path = dir.path().join("subnet-evm").display().to_string();
+    let err = file_not_found_error("vm binary", &path);
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let msg = err.to_string();
+    assert!(msg.starts_with(&format!("vm binary file '{path}' not found")));
+    assert!(msg.contains(&format!(
+        "(did you mean '{}'?)",
+        dir.path().join("subnet_evm").display()
+    )));
+    assert!(msg.ends_with("genesis.json, subnet-config.json, subnet_evm"));
+
+    let path = dir
+        .path()
+        .join("missing/genesis.json")
+        .display()
+        .to_string();
+    assert!(file_not_found_error("chain genesis", &path)
+        .to_string()
+        .ends_with("missing' not found"));
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_aggregate_region_errors --exact --show-output
+#[test]
+fn test_aggregate_region_errors() {
+    assert!(aggregate_region_errors(Vec::new()).is_ok());
+
+    let err = aggregate_region_errors(vec![(
+        String::from("us-west-2"),
+        InstallError::SsmTimeout.error(ErrorKind::TimedOut, "still running"),
+    )])
+    .unwrap_err();
+    assert_eq!(err.to_string(), "region 'us-west-2': still running");
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+    assert_eq!(exit_code(&err), 4);
+
+    let err = aggregate_region_errors(vec![
+        (
+            String::from("ap-northeast-1"),
+            Error::new(ErrorKind::Other, "script failed"),
+        ),
+        (
+            String::from("us-west-2"),
+            InstallError::SsmTimeout.error(ErrorKind::TimedOut, "still running"),
+        ),
+    ])
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "SSM failed in 2 region(s): region 'ap-northeast-1': script failed; region 'us-west-2': still running"
+    );
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert_eq!(exit_code(&err), error::EXIT_CODE_OTHER);
+
+    // the script error still outranks the timeout of an earlier region
+    let err = aggregate_region_errors(vec![
+        (
+            String::from("ap-northeast-1"),
+            InstallError::SsmTimeout.error(ErrorKind::TimedOut, "still running"),
+        ),
+        (
+            String::from("us-west-2"),
+            Error::new(ErrorKind::Other, "script failed"),
+        ),
+    ])
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert_eq!(exit_code(&err), error::EXIT_CODE_OTHER);
+
+    let err = aggregate_region_errors(vec![
+        (
+            String::from("ap-northeast-1"),
+            Error::new(ErrorKind::Other, "script failed"),
+        ),
+        (
+            String::from("us-west-2"),
+            InstallError::Validation.error(ErrorKind::InvalidInput, "no SSM document"),
+        ),
+    ])
+    .unwrap_err();
+    assert_eq!(exit_code(&err), 2);
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_s3_object_tags --exact --show-output
+#[test]
+fn test_s3_object_tags() {
+    let mut user_tags = HashMap::new();
+    user_tags.insert(String::from("team"), String::from("infra"));
+    user_tags.insert(String::from("cost-center"), String::from("1234"));
+    assert_eq!(
+        s3_object_tags(&user_tags, "1690000000-0000abcd", "my chain").unwrap(),
+        vec![
+            (String::from(S3_TAG_CHAIN_NAME), String::from("my chain")),
+            (
+                String::from(S3_TAG_RUN_ID),
+                String::from("1690000000-0000abcd")
+            ),
+            (String::from("cost-center"), String::from("1234")),
+            (String::from("team"), String::from("infra")),
+        ]
+    );
+    assert_eq!(s3_object_tags(&HashMap::new(), "r", "c").unwrap().len(), 2);
+
+    let mut reserved = HashMap::new();
+    reserved.insert(String::from(S3_TAG_RUN_ID), String::from("x"));
+    assert!(s3_object_tags(&reserved, "r", "c").is_err());
+
+    let mut aws_prefixed = HashMap::new();
+    aws_prefixed.insert(String::from("aws:createdBy"), String::from("x"));
+    assert!(s3_object_tags(&aws_prefixed, "r", "c").is_err());
+
+    let too_many: HashMap<String, String> = (0..9)
+        .map(|i| (format!("k{i}"), String::from("v")))
+        .collect();
+    let err = s3_object_tags(&too_many, "r", "c").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    assert_eq!(
+        s3_tagging_query(&[
+            (String::from("team"), String::from("infra")),
+            (String::from("chain name"), String::from("a&b=c")),
+        ]),
+        "team=infra&chain+name=a%26b%3Dc"
+    );
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_post_install_hook_envs --exact --show-output
+#[test]
+fn test_post_install_hook_envs() {
+    let subnet_id =
+        ids::Id::from_str("2PsShLjrFFwR51DMcAh8pyuwzLn1Ym3zRhuXLTmLCR1STk2mL6").unwrap();
+    let output = InstallOutput {
+        subnet_id: Some(subnet_id),
+        network_id: Some(5),
+        ..Default::default()
+    };
+    let envs: HashMap<String, String> = post_install_hook_envs(&output).into_iter().collect();
+    assert_eq!(envs["AVAX_SUBNET_ID"], subnet_id.to_string());
+    assert_eq!(envs["AVAX_BLOCKCHAIN_ID"], "");
+    assert_eq!(envs["AVAX_VM_ID"], "");
+    assert_eq!(envs["AVAX_NETWORK_ID"], "5");
+
+    let result: InstallOutput = serde_json::from_str(&envs["AVAX_RESULT_JSON"]).unwrap();
+    assert_eq!(result, output);
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_check_avalanchego_config_remote_path --exact --show-output
+#[test]
+fn test_check_avalanchego_config_remote_path() {
+    assert!(check_avalanchego_config_remote_path("/data/avalanche-configs/config.json").is_ok());
+    assert!(check_avalanchego_config_remote_path("data/config.json").is_err());
+    assert!(check_avalanchego_config_remote_path("/data/config.yaml").is_err());
+    assert!(check_avalanchego_config_remote_path("/data/config").is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_avalanchego_config_check --exact --show-output
+#[test]
+fn test_avalanchego_config_check() {
+    let commands = avalanchego_config_check_commands("/data/it's config.json");
+    assert_eq!(commands.len(), 2);
+    assert_eq!(
+        commands[0],
+        "test -f '/data/it'\\''s config.json' || exit 3"
+    );
+    assert!(commands[1]
+        .contains("json.load(open(sys.argv[1]))' '/data/it'\\''s config.json' || exit 4;"));
+    assert!(commands[1].contains("jq empty '/data/it'\\''s config.json' || exit 4;"));
+    assert!(commands[1].ends_with("else exit 5; fi"));
+
+    assert_eq!(avalanchego_config_check_failure("Failed", 3), "missing");
+    assert_eq!(
+        avalanchego_config_check_failure("Failed", 4),
+        "malformed JSON"
+    );
+    assert_eq!(
+        avalanchego_config_check_failure("Undeliverable", -1),
+        "check failed (status 'Undeliverable', exit code -1)"
+    );
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_check_role_session_name --exact --show-output
+#[test]
+fn test_check_role_session_name() {
+    assert!(check_role_session_name("deploy-alice@example.com").is_ok());
+    assert!(check_role_session_name("ci_run=42,job.1+2").is_ok());
+    assert!(check_role_session_name("a").is_err());
+    assert!(check_role_session_name(&"a".repeat(65)).is_err());
+    assert!(check_role_session_name("has space").is_err());
+    assert!(check_role_session_name("slash/name").is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_api_paths --exact --show-output
+#[test]
+fn test_api_paths() {
+    let paths = ApiPaths::new("/ext/bc/P", "/ext/info", "/ext/bc/{blockchain_id}/rpc").unwrap();
+    assert_eq!(paths, ApiPaths::default());
+
+    let paths = ApiPaths::new(
+        "/avax/ext/bc/P",
+        "/avax/ext/info",
+        "/avax/ext/bc/{blockchain_id}/rpc",
+    )
+    .unwrap();
+    let blockchain_id =
+        ids::Id::from_str("2PsShLjrFFwR51DMcAh8pyuwzLn1Ym3zRhuXLTmLCR1STk2mL6").unwrap();
+    assert_eq!(
+        paths.evm(&blockchain_id),
+        "/avax/ext/bc/2PsShLjrFFwR51DMcAh8pyuwzLn1Ym3zRhuXLTmLCR1STk2mL6/rpc"
+    );
+
+    assert_eq!(
+        paths.base_url("https://gateway.example.com/"),
+        "https://gateway.example.com/avax"
+    );
+    assert_eq!(paths.health(), "/avax/ext/health");
+    assert_eq!(
+        ApiPaths::default().base_url("http://127.0.0.1:9650"),
+        "http://127.0.0.1:9650"
+    );
+
+    assert!(ApiPaths::new("ext/bc/P", "/ext/info", "/rpc").is_err());
+    assert!(ApiPaths::new("/ext/bc/P", "", "/rpc").is_err());
+    assert!(ApiPaths::new("/ext/bc/P", "/ext/info", "/ext/bc/ x/rpc").is_err());
+    // the wallet cannot honour the paths other than the standard ones behind a prefix
+    assert!(ApiPaths::new("/avax/ext/bc/P", "/other/ext/info", "/rpc").is_err());
+    assert!(ApiPaths::new("/p-chain", "/info", "/rpc").is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_partial_output --exact --show-output
+#[test]
+fn test_partial_output() {
+    let progress = Arc::new(Mutex::new(Progress::new(false)));
+    let mut output = InstallOutput {
+        run_id: String::from("1-abc"),
+        ..Default::default()
+    };
+    checkpoint(&progress, &output);
+
+    start_step(&progress, Step::UploadSubnetConfig);
+    skip_step(&progress, "no subnet config");
+    complete_step(&progress, &output, Step::UploadSubnetConfig);
+    start_step(&progress, Step::CreateSubnet);
+    output.subnet_id =
+        Some(ids::Id::from_str("2PsShLjrFFwR51DMcAh8pyuwzLn1Ym3zRhuXLTmLCR1STk2mL6").unwrap());
+    complete_step(&progress, &output, Step::CreateSubnet);
+    start_step(&progress, Step::AddSubnetValidators);
+
+    let progress = progress.lock().unwrap();
+    assert_eq!(progress.last_completed_step(), "creating a subnet");
+    let partial = progress.partial_output();
+    assert_eq!(partial.subnet_id, output.subnet_id);
+    assert_eq!(partial.step_timings.len(), 2);
+    assert_eq!(partial.step_timings[0].step, Step::UploadSubnetConfig);
+    assert!(partial.step_timings[0].skipped);
+    assert!(!partial.step_timings[1].skipped);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_output(dir.path().to_str().unwrap(), &partial, "partial-output").unwrap();
+    assert!(path.ends_with("1-abc-partial-output.json"));
+    let written: InstallOutput = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written, partial);
+    let written: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written["step_timings"][1]["step"], "create_subnet");
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::test_format_chain_alias_status --exact --show-output
+#[test]
+fn test_format_chain_alias_status() {
+    assert_eq!(format_chain_alias_status("", "/a.json", 0, 3), "(none)");
+    assert_eq!(
+        format_chain_alias_status("mychain", "/a.json", 3, 3),
+        "mychain (registered on all 3 node(s))"
+    );
+    assert_eq!(
+        format_chain_alias_status("mychain", "/a.json", 1, 3),
+        "mychain (registered on 1/3 node(s), the others after a restart with --chain-aliases-file '/a.json')"
+    );
 }
@@ -0,0 +1,263 @@
+//! Pre-flight IAM permission checks, simulating the actions of the run
+//! for the caller before any change is made.
+
+use std::{
+    collections::HashMap,
+    io::{self, Error, ErrorKind},
+    time::Duration,
+};
+
+use aws_credential_types::provider::SharedCredentialsProvider;
+
+use super::{load_aws_config, AWS_RUN_SHELL_SCRIPT};
+
+/// Returns the IAM principal ARN to simulate for the caller ARN
+/// (the assumed role session "arn:aws:sts::[ACCOUNT]:assumed-role/[ROLE]/[SESSION]"
+/// is simulated as the role "arn:aws:iam::[ACCOUNT]:role/[ROLE]", assuming no role path).
+pub fn simulation_principal_arn(caller_arn: &str) -> String {
+    let parts: Vec<&str> = caller_arn.splitn(6, ':').collect();
+    if parts.len() == 6 && parts[2] == "sts" {
+        if let Some(rest) = parts[5].strip_prefix("assumed-role/") {
+            let role_name = rest.split('/').next().unwrap_or_default();
+            return format!("arn:{}:iam::{}:role/{role_name}", parts[1], parts[4]);
+        }
+    }
+    caller_arn.to_string()
+}
+
+/// Returns the actions with the resource ARNs that the run needs: uploading the
+/// artifacts (and SSM outputs) to the bucket, tagging them if "--s3-object-tags"
+/// is set ("require_tagging"), and sending/polling the SSM commands
+/// (with "AWS-RunShellScript" for the avalanchego config check).
+#[allow(clippy::too_many_arguments)]
+pub fn required_permissions(
+    caller_arn: &str,
+    account_id: &str,
+    s3_bucket: &str,
+    s3_key_prefix: &str,
+    region_to_instance_ids: &HashMap<String, Vec<String>>,
+    region_to_ssm_doc: &HashMap<String, String>,
+    check_avalanchego_config: bool,
+    require_tagging: bool,
+) -> Vec<(String, Vec<String>)> {
+    let partition = caller_arn.split(':').nth(1).unwrap_or("aws");
+
+    let mut regions: Vec<&String> = region_to_instance_ids.keys().collect();
+    regions.sort();
+    let mut ssm_resources = Vec::new();
+    for region in regions {
+        if let Some(ssm_doc) = region_to_ssm_doc.get(region) {
+            ssm_resources.push(format!(
+                "arn:{partition}:ssm:{region}:{account_id}:document/{ssm_doc}"
+            ));
+        }
+        // owned by AWS, thus no account Id
+        if check_avalanchego_config {
+            ssm_resources.push(format!(
+                "arn:{partition}:ssm:{region}::document/{AWS_RUN_SHELL_SCRIPT}"
+            ));
+        }
+        for instance_id in region_to_instance_ids[region].iter() {
+            ssm_resources.push(format!(
+                "arn:{partition}:ec2:{region}:{account_id}:instance/{instance_id}"
+            ));
+        }
+    }
+
+    let mut required = vec![
+        (
+            String::from("s3:GetBucketLocation"),
+            vec![format!("arn:{partition}:s3:::{s3_bucket}")],
+        ),
+        (
+            String::from("s3:PutObject"),
+            vec![format!("arn:{partition}:s3:::{s3_bucket}/{s3_key_prefix}*")],
+        ),
+    ];
+    if require_tagging {
+        required.push((
+            String::from("s3:PutObjectTagging"),
+            vec![format!("arn:{partition}:s3:::{s3_bucket}/{s3_key_prefix}*")],
+        ));
+    }
+    if !ssm_resources.is_empty() {
+        required.push((String::from("ssm:SendCommand"), ssm_resources));
+        required.push((
+            String::from("ssm:GetCommandInvocation"),
+            vec![String::from("*")],
+        ));
+    }
+    required
+}
+
+/// Simulates the required actions for the caller via "SimulatePrincipalPolicy",
+/// and fails with every action and resource that is not allowed.
+/// ref. <https://docs.aws.amazon.com/IAM/latest/APIReference/API_SimulatePrincipalPolicy.html>
+pub async fn check_permissions(
+    region: &str,
+    profile_name: &str,
+    credentials_provider: &Option<SharedCredentialsProvider>,
+    caller_arn: &str,
+    required: &[(String, Vec<String>)],
+) -> io::Result<()> {
+    let principal_arn = simulation_principal_arn(caller_arn);
+    log::info!(
+        "simulating {} action(s) for '{principal_arn}'",
+        required.len()
+    );
+
+    let shared_config = load_aws_config(
+        region,
+        profile_name,
+        Duration::from_secs(30),
+        credentials_provider,
+    )
+    .await;
+    let iam_cli = aws_sdk_iam::Client::new(&shared_config);
+
+    let mut denied = Vec::new();
+    for (action, resource_arns) in required.iter() {
+        let out = iam_cli
+            .simulate_principal_policy()
+            .policy_source_arn(&principal_arn)
+            .action_names(action)
+            .set_resource_arns(Some(resource_arns.clone()))
+            .send()
+            .await
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::PermissionDenied,
+                    format!(
+                        "failed to simulate '{action}' for '{principal_arn}', is 'iam:SimulatePrincipalPolicy' allowed? ({:?})",
+                        e
+                    ),
+                )
+            })?;
+        for result in out.evaluation_results().unwrap_or_default() {
+            let decision = result
+                .eval_decision()
+                .map(|d| d.as_str())
+                .unwrap_or("unknown");
+            let resource = result.eval_resource_name().unwrap_or("*");
+            if decision == "allowed" {
+                log::info!("'{action}' on '{resource}' allowed");
+            } else {
+                denied.push(format!("'{action}' on '{resource}' ({decision})"));
+            }
+        }
+    }
+    if denied.is_empty() {
+        log::info!("'{principal_arn}' has all the required permissions");
+        return Ok(());
+    }
+
+    Err(Error::new(
+        ErrorKind::PermissionDenied,
+        format!(
+            "'{principal_arn}' is missing {} permission(s): {}",
+            denied.len(),
+            denied.join(", ")
+        ),
+    ))
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::permissions::test_required_permissions --exact --show-output
+#[test]
+fn test_required_permissions() {
+    assert_eq!(
+        simulation_principal_arn("arn:aws:sts::123456789012:assumed-role/ops-role/session-1"),
+        "arn:aws:iam::123456789012:role/ops-role"
+    );
+    assert_eq!(
+        simulation_principal_arn("arn:aws-cn:sts::123456789012:assumed-role/ops-role/s"),
+        "arn:aws-cn:iam::123456789012:role/ops-role"
+    );
+    assert_eq!(
+        simulation_principal_arn("arn:aws:iam::123456789012:user/alice"),
+        "arn:aws:iam::123456789012:user/alice"
+    );
+
+    let mut region_to_instance_ids = HashMap::new();
+    region_to_instance_ids.insert(String::from("us-west-2"), vec![String::from("i-1")]);
+    let mut region_to_ssm_doc = HashMap::new();
+    region_to_ssm_doc.insert(String::from("us-west-2"), String::from("install-doc"));
+
+    let required = required_permissions(
+        "arn:aws:iam::123456789012:user/alice",
+        "123456789012",
+        "my-bucket",
+        "subnet/",
+        &region_to_instance_ids,
+        &region_to_ssm_doc,
+        true,
+        true,
+    );
+    assert_eq!(
+        required,
+        vec![
+            (
+                String::from("s3:GetBucketLocation"),
+                vec![String::from("arn:aws:s3:::my-bucket")]
+            ),
+            (
+                String::from("s3:PutObject"),
+                vec![String::from("arn:aws:s3:::my-bucket/subnet/*")]
+            ),
+            (
+                String::from("s3:PutObjectTagging"),
+                vec![String::from("arn:aws:s3:::my-bucket/subnet/*")]
+            ),
+            (
+                String::from("ssm:SendCommand"),
+                vec![
+                    String::from("arn:aws:ssm:us-west-2:123456789012:document/install-doc"),
+                    String::from("arn:aws:ssm:us-west-2::document/AWS-RunShellScript"),
+                    String::from("arn:aws:ec2:us-west-2:123456789012:instance/i-1"),
+                ]
+            ),
+            (
+                String::from("ssm:GetCommandInvocation"),
+                vec![String::from("*")]
+            ),
+        ]
+    );
+
+    // no SSM permissions needed without target nodes
+    assert_eq!(
+        required_permissions(
+            "arn:aws:iam::123456789012:user/alice",
+            "123456789012",
+            "my-bucket",
+            "",
+            &HashMap::new(),
+            &HashMap::new(),
+            true,
+            true,
+        )
+        .len(),
+        3
+    );
+    // no tagging permission needed without "--s3-object-tags"
+    assert_eq!(
+        required_permissions(
+            "arn:aws:iam::123456789012:user/alice",
+            "123456789012",
+            "my-bucket",
+            "",
+            &HashMap::new(),
+            &HashMap::new(),
+            true,
+            false,
+        ),
+        vec![
+            (
+                String::from("s3:GetBucketLocation"),
+                vec![String::from("arn:aws:s3:::my-bucket")]
+            ),
+            (
+                String::from("s3:PutObject"),
+                vec![String::from("arn:aws:s3:::my-bucket/*")]
+            ),
+        ]
+    );
+}
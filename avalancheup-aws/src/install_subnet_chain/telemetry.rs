@@ -0,0 +1,198 @@
+//! OpenTelemetry traces of the install, with one run-level span, a child span
+//! per step, and a grandchild span per SSM poll and per validator transaction.
+//! Requires the "otel" cargo feature, otherwise every call is a no-op.
+
+use std::io;
+
+/// Ends the span when dropped.
+pub struct SpanGuard {
+    #[cfg(feature = "otel")]
+    span: Option<opentelemetry::sdk::trace::Span>,
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(span) = &mut self.span {
+            use opentelemetry::trace::Span;
+            span.end();
+        }
+    }
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use std::{
+        io::{self, Error, ErrorKind},
+        sync::{Mutex, OnceLock},
+    };
+
+    use opentelemetry::{
+        sdk::{self, trace::Tracer as SdkTracer},
+        trace::{Span, TraceContextExt, Tracer},
+        Context, KeyValue,
+    };
+
+    const SERVICE_NAME: &str = "avalancheup-aws";
+
+    static TRACER: OnceLock<SdkTracer> = OnceLock::new();
+    static RUN_CONTEXT: Mutex<Option<Context>> = Mutex::new(None);
+    static STEP_CONTEXT: Mutex<Option<Context>> = Mutex::new(None);
+
+    /// Installs the OTLP (gRPC) exporter once per process, and starts the run-level span.
+    pub fn init(endpoint: &str) -> io::Result<()> {
+        if TRACER.get().is_none() {
+            let tracer = opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .tonic()
+                        .with_endpoint(endpoint),
+                )
+                .with_trace_config(sdk::trace::config().with_resource(sdk::Resource::new(vec![
+                    KeyValue::new("service.name", SERVICE_NAME),
+                ])))
+                .install_batch(opentelemetry::runtime::Tokio)
+                .map_err(|e| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "failed to install the OTLP exporter for '{endpoint}' ({})",
+                            e
+                        ),
+                    )
+                })?;
+            let _ = TRACER.set(tracer);
+        }
+        let tracer = TRACER.get().unwrap();
+
+        let run_cx = Context::new().with_span(tracer.start(crate::install_subnet_chain::NAME));
+        log::info!(
+            "exporting OTel traces to '{endpoint}' (trace id {})",
+            run_cx.span().span_context().trace_id()
+        );
+        *RUN_CONTEXT.lock().unwrap() = Some(run_cx);
+        Ok(())
+    }
+
+    fn parent() -> Option<Context> {
+        STEP_CONTEXT
+            .lock()
+            .unwrap()
+            .clone()
+            .or_else(|| RUN_CONTEXT.lock().unwrap().clone())
+    }
+
+    pub fn set_run_attribute(key: &'static str, value: String) {
+        if let Some(cx) = RUN_CONTEXT.lock().unwrap().as_ref() {
+            cx.span().set_attribute(KeyValue::new(key, value));
+        }
+    }
+
+    pub fn start_step(step: &str) {
+        end_step();
+        let (tracer, run_cx) = match (TRACER.get(), RUN_CONTEXT.lock().unwrap().clone()) {
+            (Some(tracer), Some(run_cx)) => (tracer, run_cx),
+            _ => return,
+        };
+        let span = tracer.start_with_context(step.to_string(), &run_cx);
+        *STEP_CONTEXT.lock().unwrap() = Some(run_cx.with_span(span));
+    }
+
+    pub fn end_step() {
+        if let Some(cx) = STEP_CONTEXT.lock().unwrap().take() {
+            cx.span().end();
+        }
+    }
+
+    pub fn span(name: &str, attributes: &[(&'static str, String)]) -> Option<sdk::trace::Span> {
+        let tracer = TRACER.get()?;
+        let parent_cx = parent()?;
+        let mut span = tracer.start_with_context(name.to_string(), &parent_cx);
+        for (k, v) in attributes.iter() {
+            span.set_attribute(KeyValue::new(*k, v.clone()));
+        }
+        Some(span)
+    }
+
+    /// Ends the run-level span and flushes the pending spans to the collector.
+    pub fn finish(err: Option<&io::Error>) {
+        end_step();
+        if let Some(cx) = RUN_CONTEXT.lock().unwrap().take() {
+            if let Some(e) = err {
+                cx.span()
+                    .set_status(opentelemetry::trace::Status::error(e.to_string()));
+            }
+            cx.span().end();
+        }
+        if let Some(provider) = TRACER.get().and_then(|t| t.provider()) {
+            for res in provider.force_flush() {
+                if let Err(e) = res {
+                    log::warn!("failed to flush OTel spans ({})", e);
+                }
+            }
+        }
+    }
+}
+
+/// Starts the run-level span if the endpoint is set.
+pub fn init(endpoint: &str) -> io::Result<()> {
+    if endpoint.is_empty() {
+        return Ok(());
+    }
+    #[cfg(feature = "otel")]
+    {
+        otel::init(endpoint)
+    }
+    #[cfg(not(feature = "otel"))]
+    {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("--otel-endpoint '{endpoint}' requires avalancheup-aws built with the 'otel' feature (cargo build --features otel)"),
+        ))
+    }
+}
+
+/// Sets the attribute (e.g., subnet Id) on the run-level span.
+pub fn set_run_attribute(key: &'static str, value: String) {
+    #[cfg(feature = "otel")]
+    otel::set_run_attribute(key, value);
+    #[cfg(not(feature = "otel"))]
+    let _ = (key, value);
+}
+
+/// Starts the span of the step, ending the previous one if still open.
+pub fn start_step(step: &str) {
+    #[cfg(feature = "otel")]
+    otel::start_step(step);
+    #[cfg(not(feature = "otel"))]
+    let _ = step;
+}
+
+pub fn end_step() {
+    #[cfg(feature = "otel")]
+    otel::end_step();
+}
+
+/// Starts a span under the current step (e.g., per SSM poll), ended when the guard drops.
+pub fn span(name: &str, attributes: &[(&'static str, String)]) -> SpanGuard {
+    #[cfg(feature = "otel")]
+    {
+        SpanGuard {
+            span: otel::span(name, attributes),
+        }
+    }
+    #[cfg(not(feature = "otel"))]
+    {
+        let _ = (name, attributes);
+        SpanGuard {}
+    }
+}
+
+/// Ends the run-level span (marked failed with the error) and flushes the spans.
+pub fn finish(err: Option<&io::Error>) {
+    #[cfg(feature = "otel")]
+    otel::finish(err);
+    #[cfg(not(feature = "otel"))]
+    let _ = err;
+}
@@ -0,0 +1,288 @@
+//! P-chain UTXO set of the funding key: fetching, decoding the unlocked
+//! balance, and the snapshots for the pre-flight balance check.
+
+use std::{
+    fs,
+    io::{self, Error, ErrorKind},
+    path::Path,
+};
+
+use avalanche_types::ids;
+use serde::{Deserialize, Serialize};
+
+use super::{call_json_rpc, ApiPaths};
+
+/// Maximum number of UTXOs per "platform.getUTXOs" page.
+const UTXOS_PAGE_LIMIT: u64 = 1024;
+
+/// Sums the unlocked outputs of the staking asset in the P-chain address's UTXO set.
+/// Returns the balance and the number of UTXOs.
+pub async fn fetch_p_chain_balance_from_utxos(
+    chain_rpc_url: &str,
+    p_address: &str,
+    asset_id: &ids::Id,
+    rpc_headers: &[(String, String)],
+    api_paths: &ApiPaths,
+) -> io::Result<(u64, usize)> {
+    let utxos = fetch_p_chain_utxos(chain_rpc_url, p_address, rpc_headers, api_paths).await?;
+    p_chain_balance_from_utxos(&utxos, asset_id, super::unix_now())
+}
+
+/// Fetches the hex-encoded UTXO set of the P-chain address, paging through "platform.getUTXOs".
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetutxos>
+pub async fn fetch_p_chain_utxos(
+    chain_rpc_url: &str,
+    p_address: &str,
+    rpc_headers: &[(String, String)],
+    api_paths: &ApiPaths,
+) -> io::Result<Vec<String>> {
+    let mut utxos = Vec::new();
+    let mut start_index = serde_json::Value::Null;
+    loop {
+        let mut params = serde_json::json!({
+            "addresses": [p_address],
+            "limit": UTXOS_PAGE_LIMIT,
+            "encoding": "hex",
+        });
+        if !start_index.is_null() {
+            params["startIndex"] = start_index.clone();
+        }
+        let result = call_json_rpc(
+            chain_rpc_url,
+            &api_paths.p_chain,
+            "platform.getUTXOs",
+            params,
+            rpc_headers,
+        )
+        .await?;
+
+        let page = result["utxos"].as_array().cloned().unwrap_or_default();
+        utxos.extend(
+            page.iter()
+                .map(|utxo| utxo.as_str().unwrap_or_default().to_string()),
+        );
+
+        let num_fetched = result["numFetched"]
+            .as_str()
+            .and_then(|n| n.parse::<u64>().ok())
+            .unwrap_or(page.len() as u64);
+        if num_fetched < UTXOS_PAGE_LIMIT {
+            break;
+        }
+        start_index = result["endIndex"].clone();
+    }
+    log::info!("fetched {} UTXO(s) of '{p_address}'", utxos.len());
+    Ok(utxos)
+}
+
+/// Sums the outputs of the asset in the hex-encoded UTXOs that are unlocked at "now" (unix seconds).
+/// Returns the balance and the number of UTXOs.
+pub fn p_chain_balance_from_utxos(
+    utxos: &[String],
+    asset_id: &ids::Id,
+    now: u64,
+) -> io::Result<(u64, usize)> {
+    let mut balance: u64 = 0;
+    for d in utxos.iter() {
+        let b = hex::decode(d.trim_start_matches("0x")).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("failed to decode UTXO '{d}' ({})", e),
+            )
+        })?;
+        let (utxo_asset_id, amount) = parse_utxo_amount(&b, now)?;
+        if utxo_asset_id != *asset_id {
+            continue;
+        }
+        if let Some(amount) = amount {
+            balance = balance.checked_add(amount).ok_or_else(|| {
+                Error::new(ErrorKind::InvalidData, "P-chain balance overflows u64")
+            })?;
+        }
+    }
+    Ok((balance, utxos.len()))
+}
+
+/// Snapshot of the P-chain UTXO set of an address, to skip fetching large
+/// UTXO sets for the pre-flight balance in repeated runs with the same key.
+/// The wallet still fetches the live UTXOs to build the transactions, so a
+/// stale snapshot only misstates the balance checked before the run.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct UtxoSnapshot {
+    pub network_id: u32,
+    pub p_address: String,
+    /// Unix seconds when the UTXO set was fetched.
+    pub fetched_at: u64,
+    /// Hex-encoded UTXOs as returned by "platform.getUTXOs".
+    pub utxos: Vec<String>,
+}
+
+/// Snapshots older than this are reported as likely stale.
+const UTXO_SNAPSHOT_STALE_SECS: u64 = 24 * 60 * 60;
+
+impl UtxoSnapshot {
+    pub fn load(file_path: &str) -> io::Result<Self> {
+        let d = fs::read(file_path).map_err(|e| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("failed to read UTXO snapshot '{file_path}' ({})", e),
+            )
+        })?;
+        serde_json::from_slice(&d).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid UTXO snapshot '{file_path}' ({})", e),
+            )
+        })
+    }
+
+    pub fn sync(&self, file_path: &str) -> io::Result<()> {
+        if let Some(parent_dir) = Path::new(file_path).parent() {
+            if !parent_dir.as_os_str().is_empty() {
+                fs::create_dir_all(parent_dir)?;
+            }
+        }
+        fs::write(file_path, serde_json::to_vec_pretty(self).unwrap())
+    }
+
+    /// Fails if the snapshot is of another network or address, and warns if it is stale.
+    pub fn check(&self, network_id: u32, p_address: &str, now: u64) -> io::Result<()> {
+        if self.network_id != network_id || self.p_address != p_address {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "UTXO snapshot is of '{}' (network Id {}), not '{p_address}' (network Id {network_id}), use --refresh-balance-utxos",
+                    self.p_address, self.network_id
+                ),
+            ));
+        }
+        let age = now.saturating_sub(self.fetched_at);
+        if age > UTXO_SNAPSHOT_STALE_SECS {
+            log::warn!(
+                "UTXO snapshot was fetched {} hour(s) ago, the balance may be stale (use --refresh-balance-utxos)",
+                age / 3600
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Parses the asset Id and the unlocked amount of the codec-encoded P-chain UTXO
+/// (codec version, tx Id, output index, asset Id, output type Id, output).
+/// The amount is None for the outputs still locked at "now" (unix seconds),
+/// or non-transfer outputs (e.g., "StakeableLockOut").
+/// ref. <https://github.com/ava-labs/avalanchego/blob/master/vms/components/avax/utxo.go>
+pub fn parse_utxo_amount(d: &[u8], now: u64) -> io::Result<(ids::Id, Option<u64>)> {
+    // 2-byte codec version + 32-byte tx Id + 4-byte output index
+    const ASSET_ID_OFFSET: usize = 2 + 32 + 4;
+    const TYPE_ID_OFFSET: usize = ASSET_ID_OFFSET + 32;
+    // ref. <https://github.com/ava-labs/avalanchego/blob/master/vms/platformvm/txs/codec.go>
+    const SECP256K1_TRANSFER_OUTPUT_TYPE_ID: u32 = 7;
+
+    if d.len() < TYPE_ID_OFFSET + 4 + 16 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("UTXO too short ({} bytes)", d.len()),
+        ));
+    }
+    let asset_id = ids::Id::from_slice(&d[ASSET_ID_OFFSET..TYPE_ID_OFFSET]);
+    let type_id = u32::from_be_bytes(d[TYPE_ID_OFFSET..TYPE_ID_OFFSET + 4].try_into().unwrap());
+    if type_id != SECP256K1_TRANSFER_OUTPUT_TYPE_ID {
+        return Ok((asset_id, None));
+    }
+
+    let amount_offset = TYPE_ID_OFFSET + 4;
+    let amount = u64::from_be_bytes(d[amount_offset..amount_offset + 8].try_into().unwrap());
+    let locktime = u64::from_be_bytes(d[amount_offset + 8..amount_offset + 16].try_into().unwrap());
+    if locktime > now {
+        return Ok((asset_id, None));
+    }
+    Ok((asset_id, Some(amount)))
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::utxo::test_parse_utxo_amount --exact --show-output
+#[test]
+fn test_parse_utxo_amount() {
+    use std::str::FromStr;
+
+    let asset_id = ids::Id::from_str("FvwEAhmxKfeiG8SnEvq42hc6whRyY3EFYAvebMqDNDGCgxN5Z").unwrap();
+    let encode = |type_id: u32, amount: u64, locktime: u64| {
+        let mut d = vec![0u8; 2]; // codec version
+        d.extend_from_slice(&[1u8; 32]); // tx Id
+        d.extend_from_slice(&0u32.to_be_bytes()); // output index
+        d.extend_from_slice(asset_id.as_ref());
+        d.extend_from_slice(&type_id.to_be_bytes());
+        d.extend_from_slice(&amount.to_be_bytes());
+        d.extend_from_slice(&locktime.to_be_bytes());
+        d.extend_from_slice(&1u32.to_be_bytes()); // threshold
+        d.extend_from_slice(&0u32.to_be_bytes()); // addresses
+        d
+    };
+
+    let now = 1_700_000_000;
+    assert_eq!(
+        parse_utxo_amount(&encode(7, 2_000_000_000, 0), now).unwrap(),
+        (asset_id, Some(2_000_000_000))
+    );
+    // locked until after now
+    assert_eq!(
+        parse_utxo_amount(&encode(7, 2_000_000_000, now + 1), now).unwrap(),
+        (asset_id, None)
+    );
+    // unlocked exactly at now
+    assert_eq!(
+        parse_utxo_amount(&encode(7, 2_000_000_000, now), now).unwrap(),
+        (asset_id, Some(2_000_000_000))
+    );
+    // "StakeableLockOut"
+    assert_eq!(
+        parse_utxo_amount(&encode(22, 2_000_000_000, 0), now).unwrap(),
+        (asset_id, None)
+    );
+    assert!(parse_utxo_amount(&[0u8; 10], now).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::utxo::test_utxo_snapshot --exact --show-output
+#[test]
+fn test_utxo_snapshot() {
+    use std::str::FromStr;
+
+    let asset_id = ids::Id::from_str("FvwEAhmxKfeiG8SnEvq42hc6whRyY3EFYAvebMqDNDGCgxN5Z").unwrap();
+    let encode = |amount: u64| {
+        let mut d = vec![0u8; 2]; // codec version
+        d.extend_from_slice(&[1u8; 32]); // tx Id
+        d.extend_from_slice(&0u32.to_be_bytes()); // output index
+        d.extend_from_slice(asset_id.as_ref());
+        d.extend_from_slice(&7u32.to_be_bytes()); // "TransferableOutput"
+        d.extend_from_slice(&amount.to_be_bytes());
+        d.extend_from_slice(&0u64.to_be_bytes()); // locktime
+        d.extend_from_slice(&1u32.to_be_bytes()); // threshold
+        d.extend_from_slice(&0u32.to_be_bytes()); // addresses
+        format!("0x{}", hex::encode(d))
+    };
+    let utxos = vec![encode(1_000), encode(2_000)];
+    assert_eq!(
+        p_chain_balance_from_utxos(&utxos, &asset_id, 1_700_000_000).unwrap(),
+        (3_000, 2)
+    );
+    assert!(p_chain_balance_from_utxos(&[String::from("0xzz")], &asset_id, 1_700_000_000).is_err());
+
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("utxos.json").display().to_string();
+    let snapshot = UtxoSnapshot {
+        network_id: 5,
+        p_address: String::from("P-fuji1abc"),
+        fetched_at: 1_000,
+        utxos,
+    };
+    snapshot.sync(&file_path).unwrap();
+    let loaded = UtxoSnapshot::load(&file_path).unwrap();
+    assert_eq!(loaded, snapshot);
+
+    assert!(loaded.check(5, "P-fuji1abc", 2_000).is_ok());
+    assert!(loaded
+        .check(5, "P-fuji1abc", 1_000 + 2 * UTXO_SNAPSHOT_STALE_SECS)
+        .is_ok());
+    assert!(loaded.check(1, "P-fuji1abc", 2_000).is_err());
+    assert!(loaded.check(5, "P-fuji1xyz", 2_000).is_err());
+}
@@ -0,0 +1,239 @@
+use std::{
+    fs::{self, File},
+    io::{self, Error, ErrorKind, Read},
+};
+
+use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+/// Computes the SHA-256 digest of the file, without loading it into memory.
+pub fn sha256_file(file_path: &str) -> io::Result<[u8; 32]> {
+    let mut f = File::open(file_path).map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to open '{file_path}' ({})", e),
+        )
+    })?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut f, &mut hasher)?;
+    Ok(hasher.finalize().into())
+}
+
+/// Verifies the VM binary against the expected SHA-256 hex digest (if not empty),
+/// and the detached secp256k1 ECDSA signature over its SHA-256 digest (if not empty).
+/// The signature file is either DER-encoded (e.g., "openssl dgst -sha256 -sign")
+/// or the raw 64-byte "r || s", and the public key is hex-encoded SEC1 (33 or 65 bytes).
+/// Returns the computed hex digest, to be recorded.
+pub fn verify(
+    file_path: &str,
+    expected_sha256: &str,
+    sig_path: &str,
+    pubkey_hex: &str,
+) -> io::Result<String> {
+    let digest = sha256_file(file_path)?;
+    let computed = hex::encode(digest);
+    log::info!("VM binary '{file_path}' SHA-256 {computed}");
+
+    if !expected_sha256.is_empty() {
+        let expected = expected_sha256.trim().to_lowercase();
+        if computed != expected {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("VM binary '{file_path}' SHA-256 {computed} does not match the expected {expected}"),
+            ));
+        }
+        log::info!("VM binary '{file_path}' SHA-256 matches the expected digest");
+    }
+
+    if !sig_path.is_empty() {
+        verify_signature(&digest, sig_path, pubkey_hex)?;
+        log::info!("VM binary '{file_path}' signature '{sig_path}' verified");
+    }
+    Ok(computed)
+}
+
+/// Checks that the VM binary embeds the VM Id, either as the CB58 string or
+/// as the raw 32 bytes (e.g., subnet-evm "ids.ID{'s', 'u', 'b', 'n', 'e', 't', 'e', 'v', 'm'}"),
+/// since avalanchego refuses to load a plugin whose Id does not match.
+/// Only meaningful for the VMs that embed their own Id.
+pub fn verify_embedded_id(file_path: &str, vm_id: &str, vm_id_bytes: &[u8]) -> io::Result<()> {
+    let found = file_contains(file_path, &[vm_id.as_bytes(), vm_id_bytes])?;
+    if found.iter().any(|f| *f) {
+        log::info!(
+            "VM binary '{file_path}' embeds the VM Id '{vm_id}' ({})",
+            if found[0] { "CB58" } else { "raw bytes" }
+        );
+        return Ok(());
+    }
+    Err(Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "VM binary '{file_path}' does not embed the VM Id '{vm_id}' (neither as the CB58 string nor as the raw bytes), the nodes would refuse to load the plugin (check --vm-id or --vm-id-derivation)"
+        ),
+    ))
+}
+
+/// Returns whether the file contains each pattern, reading the file in chunks
+/// (with an overlap of the longest pattern) without loading it into memory.
+fn file_contains(file_path: &str, patterns: &[&[u8]]) -> io::Result<Vec<bool>> {
+    const CHUNK_SIZE: usize = 1024 * 1024;
+
+    let mut f = File::open(file_path).map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to open '{file_path}' ({})", e),
+        )
+    })?;
+    let overlap = patterns
+        .iter()
+        .map(|p| p.len())
+        .max()
+        .unwrap_or(0)
+        .saturating_sub(1);
+    let mut found = vec![false; patterns.len()];
+    let mut buf: Vec<u8> = Vec::with_capacity(CHUNK_SIZE + overlap);
+    let mut chunk = vec![0u8; CHUNK_SIZE];
+    loop {
+        let n = f.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        for (i, pattern) in patterns.iter().enumerate() {
+            if !found[i] && !pattern.is_empty() && buf.windows(pattern.len()).any(|w| w == *pattern)
+            {
+                found[i] = true;
+            }
+        }
+        if found.iter().all(|f| *f) {
+            break;
+        }
+        // keep the tail, for the patterns across the chunk boundary
+        let keep_from = buf.len().saturating_sub(overlap);
+        buf.drain(..keep_from);
+    }
+    Ok(found)
+}
+
+fn verify_signature(digest: &[u8; 32], sig_path: &str, pubkey_hex: &str) -> io::Result<()> {
+    let pubkey_bytes = hex::decode(pubkey_hex.trim().trim_start_matches("0x")).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid hex public key '{pubkey_hex}' ({})", e),
+        )
+    })?;
+    let verifying_key = VerifyingKey::from_sec1_bytes(&pubkey_bytes).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid secp256k1 public key '{pubkey_hex}' ({})", e),
+        )
+    })?;
+
+    let sig_bytes = fs::read(sig_path).map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to read signature '{sig_path}' ({})", e),
+        )
+    })?;
+    let sig = Signature::from_der(&sig_bytes)
+        .or_else(|_| Signature::from_slice(&sig_bytes))
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "invalid signature '{sig_path}' (expected DER or 64-byte r || s, {})",
+                    e
+                ),
+            )
+        })?;
+
+    verifying_key.verify_prehash(digest, &sig).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "signature '{sig_path}' does not verify with public key '{pubkey_hex}' ({})",
+                e
+            ),
+        )
+    })
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::vm_binary::test_verify --exact --show-output
+#[test]
+fn test_verify() {
+    use k256::ecdsa::{signature::hazmat::PrehashSigner, SigningKey};
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let dir = tempfile::tempdir().unwrap();
+    let vm_path = dir.path().join("vm");
+    fs::write(&vm_path, b"hello").unwrap();
+    let vm_path = vm_path.to_str().unwrap();
+
+    // echo -n hello | sha256sum
+    let expected = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+    assert_eq!(verify(vm_path, expected, "", "").unwrap(), expected);
+    assert_eq!(
+        verify(vm_path, &expected.to_uppercase(), "", "").unwrap(),
+        expected
+    );
+    assert!(verify(vm_path, &"0".repeat(64), "", "").is_err());
+
+    let signing_key = SigningKey::from_slice(&[7; 32]).unwrap();
+    let pubkey_hex = hex::encode(
+        signing_key
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes(),
+    );
+    let digest = sha256_file(vm_path).unwrap();
+    let sig: Signature = signing_key.sign_prehash(&digest).unwrap();
+
+    let der_sig_path = dir.path().join("vm.sig");
+    fs::write(&der_sig_path, sig.to_der().as_bytes()).unwrap();
+    let der_sig_path = der_sig_path.to_str().unwrap();
+    assert!(verify(vm_path, expected, der_sig_path, &pubkey_hex).is_ok());
+
+    let raw_sig_path = dir.path().join("vm.raw.sig");
+    fs::write(&raw_sig_path, sig.to_bytes()).unwrap();
+    assert!(verify(vm_path, "", raw_sig_path.to_str().unwrap(), &pubkey_hex).is_ok());
+
+    // tampered binary
+    fs::write(vm_path, b"hello!").unwrap();
+    assert!(verify(vm_path, "", der_sig_path, &pubkey_hex).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --lib -- install_subnet_chain::vm_binary::test_verify_embedded_id --exact --show-output
+#[test]
+fn test_verify_embedded_id() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    // subnet-evm VM Id
+    let vm_id = "srEXiWaHuhNyGwPUi444Tu47ZEDwxTWrbQiuD7FmgSAQ6X7Dy";
+    let mut vm_id_bytes = [0u8; 32];
+    vm_id_bytes[..9].copy_from_slice(b"subnetevm");
+
+    let dir = tempfile::tempdir().unwrap();
+    let vm_path = dir.path().join("vm");
+    let vm_path_str = vm_path.to_str().unwrap();
+
+    // raw bytes across the chunk boundary
+    let mut d = vec![0xffu8; 1024 * 1024 - 5];
+    d.extend_from_slice(&vm_id_bytes);
+    d.extend_from_slice(&[0xff; 100]);
+    fs::write(&vm_path, &d).unwrap();
+    assert!(verify_embedded_id(vm_path_str, vm_id, &vm_id_bytes).is_ok());
+
+    fs::write(&vm_path, format!("\x7fELF...{vm_id}...")).unwrap();
+    assert!(verify_embedded_id(vm_path_str, vm_id, &vm_id_bytes).is_ok());
+
+    fs::write(&vm_path, b"\x7fELF...subnetevm...").unwrap();
+    let err = verify_embedded_id(vm_path_str, vm_id, &vm_id_bytes).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
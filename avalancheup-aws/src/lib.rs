@@ -0,0 +1,2 @@
+pub mod install_subnet_chain;
+pub mod networks;
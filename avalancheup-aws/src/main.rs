@@ -1,15 +1,27 @@
 mod add_primary_network_validators;
 mod apply;
+mod create_ssm_doc;
 mod default_spec;
 mod delete;
+mod deploy_report;
 mod endpoints;
-mod install_subnet_chain;
+mod estimate_cost;
+mod predict_ids;
+mod prune_s3_artifacts;
+mod renew_validators;
+mod rotate_key_plan;
 mod subnet_config;
 mod subnet_evm;
+mod subnet_validators;
+mod wallet_address;
+mod wallet_balance;
 
 use std::{collections::HashMap, io};
 
+use avalanche_types::key;
+use avalancheup_aws::{install_subnet_chain, networks};
 use clap::{crate_version, Command};
+use zeroize::Zeroizing;
 
 const APP_NAME: &str = "avalancheup-aws";
 
@@ -17,7 +29,7 @@ const APP_NAME: &str = "avalancheup-aws";
 /// (e.g., multiple restarts should not recreate the same CloudFormation stacks)
 #[tokio::main]
 async fn main() -> io::Result<()> {
-    let matches = Command::new(APP_NAME)
+    let mut matches = Command::new(APP_NAME)
         .version(crate_version!())
         .about("AvalancheUp control plane on AWS (requires avalanched)")
         .subcommands(vec![
@@ -26,13 +38,29 @@ async fn main() -> io::Result<()> {
             delete::command(),
             add_primary_network_validators::command(),
             install_subnet_chain::command(),
+            estimate_cost::command(),
+            predict_ids::command(),
+            prune_s3_artifacts::command(),
+            create_ssm_doc::command(),
             subnet_evm::command(),
             subnet_config::command(),
+            subnet_validators::command(),
+            renew_validators::command(),
+            deploy_report::command(),
             endpoints::command(),
+            networks::command(),
+            wallet_address::command(),
+            wallet_balance::command(),
+            rotate_key_plan::command(),
         ])
         .get_matches();
 
-    match matches.subcommand() {
+    // owned to move the hotkeys out of the matches (not cloned, thus scrubbed on drop)
+    let mut subcommand = matches.remove_subcommand();
+    match subcommand
+        .as_mut()
+        .map(|(name, sub_matches)| (name.as_str(), sub_matches))
+    {
         Some((default_spec::NAME, sub_matches)) => {
             let s = sub_matches
                 .get_one::<String>("SUBNET_EVM_PRIORITY_REGOSSIP_ADDRESSES")
@@ -303,7 +331,7 @@ async fn main() -> io::Result<()> {
                     .get_one::<String>("CHAIN_RPC_URL")
                     .unwrap()
                     .clone(),
-                key: sub_matches.get_one::<String>("KEY").unwrap().clone(),
+                key: Zeroizing::new(sub_matches.remove_one::<String>("KEY").unwrap()),
 
                 primary_network_validate_period_in_days: *sub_matches
                     .get_one::<u64>("PRIMARY_NETWORK_VALIDATE_PERIOD_IN_DAYS")
@@ -311,6 +339,11 @@ async fn main() -> io::Result<()> {
                 staking_amount_in_avax: *sub_matches
                     .get_one::<u64>("STAKING_AMOUNT_IN_AVAX")
                     .unwrap_or(&2000),
+                stake_asset_symbol: sub_matches
+                    .get_one::<String>("STAKE_ASSET_SYMBOL")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                staking_decimals: *sub_matches.get_one::<u32>("STAKING_DECIMALS").unwrap_or(&9),
 
                 target_node_ids,
             })
@@ -323,6 +356,10 @@ async fn main() -> io::Result<()> {
                 .get_one::<HashMap<String, String>>("SSM_DOCS")
                 .unwrap_or(&HashMap::new())
                 .clone();
+            let node_rpc_urls: HashMap<String, String> = sub_matches
+                .get_one::<HashMap<String, String>>("NODE_RPC_URLS")
+                .unwrap_or(&HashMap::new())
+                .clone();
             let target_nodes: HashMap<String, avalanche_ops::aws::spec::RegionMachineId> =
                 sub_matches
                     .get_one::<HashMap<String, avalanche_ops::aws::spec::RegionMachineId>>(
@@ -331,13 +368,66 @@ async fn main() -> io::Result<()> {
                     .unwrap_or(&HashMap::new())
                     .clone();
 
+            let s = sub_matches
+                .get_one::<String>("NON_VALIDATING_NODE_IDS")
+                .unwrap_or(&String::new())
+                .clone();
+            let ss: Vec<&str> = s.split(',').collect();
+            let mut non_validating_node_ids = Vec::new();
+            for node_id in ss.iter() {
+                let trimmed = node_id.trim().to_string();
+                if !trimmed.is_empty() {
+                    non_validating_node_ids.push(trimmed);
+                }
+            }
+
+            let s = sub_matches
+                .get_one::<String>("ALLOWED_NODES")
+                .unwrap_or(&String::new())
+                .clone();
+            let ss: Vec<&str> = s.split(',').collect();
+            let mut allowed_nodes = Vec::new();
+            for node_id in ss.iter() {
+                let trimmed = node_id.trim().to_string();
+                if !trimmed.is_empty() {
+                    allowed_nodes.push(trimmed);
+                }
+            }
+
+            let s = sub_matches
+                .get_one::<String>("ALLOC")
+                .unwrap_or(&String::new())
+                .clone();
+            let ss: Vec<&str> = s.split(',').collect();
+            let mut subnet_evm_allocs = Vec::new();
+            for alloc in ss.iter() {
+                let trimmed = alloc.trim().to_string();
+                if !trimmed.is_empty() {
+                    subnet_evm_allocs.push(trimmed);
+                }
+            }
+
+            let bls_signer_per_node: HashMap<String, key::bls::ProofOfPossession> = sub_matches
+                .get_one::<HashMap<String, key::bls::ProofOfPossession>>("BLS_SIGNER_PER_NODE")
+                .unwrap_or(&HashMap::new())
+                .clone();
+
             install_subnet_chain::execute(install_subnet_chain::Flags {
                 log_level: sub_matches
                     .get_one::<String>("LOG_LEVEL")
                     .unwrap_or(&String::from("info"))
                     .clone(),
+                log_file: sub_matches
+                    .get_one::<String>("LOG_FILE")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                no_color: sub_matches.get_flag("NO_COLOR"),
 
                 skip_prompt: sub_matches.get_flag("SKIP_PROMPT"),
+                confirm_phrase: sub_matches
+                    .get_one::<String>("CONFIRM_PHRASE")
+                    .unwrap_or(&String::new())
+                    .clone(),
                 spec_file_path: sub_matches
                     .get_one::<String>("SPEC_FILE_PATH")
                     .unwrap_or(&String::new())
@@ -353,12 +443,61 @@ async fn main() -> io::Result<()> {
                     .get_one::<u64>("S3_UPLOAD_TIMEOUT")
                     .unwrap_or(&30)
                     .clone(),
+                s3_object_tags: sub_matches
+                    .get_one::<HashMap<String, String>>("S3_OBJECT_TAGS")
+                    .unwrap_or(&HashMap::new())
+                    .clone(),
 
                 chain_rpc_url: sub_matches
                     .get_one::<String>("CHAIN_RPC_URL")
                     .unwrap()
                     .clone(),
-                key: sub_matches.get_one::<String>("KEY").unwrap().clone(),
+                p_chain_rpc_url: sub_matches
+                    .get_one::<String>("P_CHAIN_RPC_URL")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                tx_submit_rpc_url: sub_matches
+                    .get_one::<String>("TX_SUBMIT_RPC_URL")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                balance_utxo_snapshot: sub_matches
+                    .get_one::<String>("BALANCE_UTXO_SNAPSHOT")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                refresh_balance_utxos: sub_matches.get_flag("REFRESH_BALANCE_UTXOS"),
+                save_balance_utxo_snapshot: sub_matches
+                    .get_one::<String>("SAVE_BALANCE_UTXO_SNAPSHOT")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                query_rpc_headers: sub_matches
+                    .get_many::<String>("QUERY_RPC_HEADER")
+                    .map(|headers| headers.cloned().collect())
+                    .unwrap_or_default(),
+                p_chain_api_path: sub_matches
+                    .get_one::<String>("P_CHAIN_API_PATH")
+                    .unwrap()
+                    .clone(),
+                info_api_path: sub_matches
+                    .get_one::<String>("INFO_API_PATH")
+                    .unwrap()
+                    .clone(),
+                evm_api_path: sub_matches
+                    .get_one::<String>("EVM_API_PATH")
+                    .unwrap()
+                    .clone(),
+                node_rpc_urls,
+                key: Zeroizing::new(sub_matches.remove_one::<String>("KEY").unwrap()),
+                subnet_auth_keys: sub_matches
+                    .remove_one::<String>("SUBNET_AUTH_KEYS")
+                    .map(|s| {
+                        let s = Zeroizing::new(s);
+                        s.split(',')
+                            .map(|k| k.trim())
+                            .filter(|k| !k.is_empty())
+                            .map(|k| Zeroizing::new(k.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
 
                 primary_network_validate_period_in_days: *sub_matches
                     .get_one::<u64>("PRIMARY_NETWORK_VALIDATE_PERIOD_IN_DAYS")
@@ -366,9 +505,25 @@ async fn main() -> io::Result<()> {
                 subnet_validate_period_in_days: *sub_matches
                     .get_one::<u64>("SUBNET_VALIDATE_PERIOD_IN_DAYS")
                     .unwrap_or(&14),
+                subnet_validation_end_at: sub_matches
+                    .get_one::<String>("SUBNET_VALIDATION_END_AT")
+                    .unwrap_or(&String::new())
+                    .clone(),
                 staking_amount_in_avax: *sub_matches
                     .get_one::<u64>("STAKING_AMOUNT_IN_AVAX")
                     .unwrap_or(&2000),
+                min_balance_reserve_avax: *sub_matches
+                    .get_one::<u64>("MIN_BALANCE_RESERVE_AVAX")
+                    .unwrap_or(&0),
+                staking_asset_id: sub_matches
+                    .get_one::<String>("STAKING_ASSET_ID")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                staking_decimals: *sub_matches.get_one::<u32>("STAKING_DECIMALS").unwrap_or(&9),
+                stake_asset_symbol: sub_matches
+                    .get_one::<String>("STAKE_ASSET_SYMBOL")
+                    .unwrap_or(&String::new())
+                    .clone(),
 
                 subnet_config_local_path: sub_matches
                     .get_one::<String>("SUBNET_CONFIG_LOCAL_PATH")
@@ -378,24 +533,78 @@ async fn main() -> io::Result<()> {
                     .get_one::<String>("SUBNET_CONFIG_REMOTE_PATH")
                     .unwrap_or(&String::new())
                     .clone(),
+                subnet_config_overrides: sub_matches
+                    .get_one::<String>("SUBNET_CONFIG_OVERRIDES")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                validator_only: sub_matches.get_flag("VALIDATOR_ONLY"),
+                allowed_nodes,
 
                 vm_binary_local_path: sub_matches
                     .get_one::<String>("VM_BINARY_LOCAL_PATH")
-                    .unwrap()
+                    .unwrap_or(&String::new())
                     .clone(),
                 vm_binary_remote_dir: sub_matches
                     .get_one::<String>("VM_BINARY_REMOTE_DIR")
                     .unwrap()
                     .clone(),
+                skip_vm_binary_upload: sub_matches.get_flag("SKIP_VM_BINARY_UPLOAD"),
+                vm_binary_s3_key: sub_matches
+                    .get_one::<String>("VM_BINARY_S3_KEY")
+                    .unwrap_or(&String::new())
+                    .clone(),
 
                 vm_id: sub_matches
                     .get_one::<String>("VM_ID")
                     .unwrap_or(&String::new())
                     .clone(),
+                vm_id_derivation: sub_matches
+                    .get_one::<String>("VM_ID_DERIVATION")
+                    .unwrap_or(&String::from("name"))
+                    .clone(),
+                expected_vm_version: sub_matches
+                    .get_one::<String>("EXPECTED_VM_VERSION")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                verify_vm_id_in_binary: sub_matches.get_flag("VERIFY_VM_ID_IN_BINARY"),
+                vm_binary_sha256: sub_matches
+                    .get_one::<String>("VM_BINARY_SHA256")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                vm_binary_sig: sub_matches
+                    .get_one::<String>("VM_BINARY_SIG")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                vm_binary_pubkey: sub_matches
+                    .get_one::<String>("VM_BINARY_PUBKEY")
+                    .unwrap_or(&String::new())
+                    .clone(),
                 chain_name: sub_matches.get_one::<String>("CHAIN_NAME").unwrap().clone(),
                 chain_genesis_path: sub_matches
                     .get_one::<String>("CHAIN_GENESIS_PATH")
-                    .unwrap()
+                    .unwrap_or(&String::new())
+                    .clone(),
+                dry_run_genesis_diff: sub_matches.get_flag("DRY_RUN_GENESIS_DIFF"),
+                baseline_genesis_path: sub_matches
+                    .get_one::<String>("BASELINE_GENESIS_PATH")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                baseline_blockchain_id: sub_matches
+                    .get_one::<String>("BASELINE_BLOCKCHAIN_ID")
+                    .unwrap_or(&String::new())
+                    .clone(),
+
+                generate_subnet_evm_genesis: sub_matches.get_flag("GENERATE_SUBNET_EVM_GENESIS"),
+                subnet_evm_chain_id: *sub_matches.get_one::<u64>("CHAIN_ID").unwrap_or(&0),
+                subnet_evm_gas_limit: *sub_matches.get_one::<u64>("GAS_LIMIT").unwrap_or(&0),
+                subnet_evm_fee_config: sub_matches
+                    .get_one::<String>("FEE_CONFIG")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                subnet_evm_allocs,
+                output_dir: sub_matches
+                    .get_one::<String>("OUTPUT_DIR")
+                    .unwrap_or(&String::from("."))
                     .clone(),
 
                 chain_config_local_path: sub_matches
@@ -406,21 +615,462 @@ async fn main() -> io::Result<()> {
                     .get_one::<String>("CHAIN_CONFIG_REMOTE_PATH")
                     .unwrap_or(&String::new())
                     .clone(),
+                chain_config_remote_filename: sub_matches
+                    .get_one::<String>("CHAIN_CONFIG_REMOTE_FILENAME")
+                    .unwrap_or(&String::from("config.json"))
+                    .clone(),
+                skip_config_validation: sub_matches.get_flag("SKIP_CONFIG_VALIDATION"),
+                skip_chain_config_validation: sub_matches.get_flag("SKIP_CHAIN_CONFIG_VALIDATION"),
+
+                chain_alias: sub_matches
+                    .get_one::<String>("CHAIN_ALIAS")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                chain_aliases_remote_path: sub_matches
+                    .get_one::<String>("CHAIN_ALIASES_REMOTE_PATH")
+                    .unwrap_or(&String::new())
+                    .clone(),
 
                 avalanchego_config_remote_path: sub_matches
                     .get_one::<String>("AVALANCHEGO_CONFIG_REMOTE_PATH")
                     .unwrap()
                     .clone(),
+                skip_avalanchego_config_check: sub_matches
+                    .get_flag("SKIP_AVALANCHEGO_CONFIG_CHECK"),
+                track_subnets_mode: sub_matches
+                    .get_one::<String>("TRACK_SUBNETS_MODE")
+                    .unwrap_or(&String::from("merge"))
+                    .clone(),
+
+                backend: sub_matches
+                    .get_one::<String>("BACKEND")
+                    .unwrap_or(&String::from("ec2"))
+                    .clone(),
+                anr_endpoint: sub_matches
+                    .get_one::<String>("ANR_ENDPOINT")
+                    .unwrap_or(&String::from("http://127.0.0.1:8081"))
+                    .clone(),
+                anr_node_exec: sub_matches
+                    .get_one::<String>("ANR_NODE_EXEC")
+                    .unwrap_or(&String::new())
+                    .clone(),
 
                 ssm_docs,
+                ssm_doc_version: sub_matches
+                    .get_one::<String>("SSM_DOC_VERSION")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                ssm_max_attempts: *sub_matches.get_one::<u32>("SSM_MAX_ATTEMPTS").unwrap_or(&1),
+                region_concurrency: *sub_matches
+                    .get_one::<u32>("REGION_CONCURRENCY")
+                    .unwrap_or(&1),
+                max_validators: *sub_matches.get_one::<u32>("MAX_VALIDATORS").unwrap_or(&50),
+                otel_endpoint: sub_matches
+                    .get_one::<String>("OTEL_ENDPOINT")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                print_ssm_args: sub_matches.get_flag("PRINT_SSM_ARGS"),
+                check_permissions: sub_matches.get_flag("CHECK_PERMISSIONS"),
+                region_from_rpc: sub_matches.get_flag("REGION_FROM_RPC"),
+                verbose_timing: sub_matches.get_flag("VERBOSE_TIMING"),
+                wait_for_bootstrap_minutes: *sub_matches
+                    .get_one::<u64>("WAIT_FOR_BOOTSTRAP_MINUTES")
+                    .unwrap_or(&0),
+                print_env: sub_matches.get_flag("PRINT_ENV"),
+                plan_output: sub_matches
+                    .get_one::<String>("PLAN_OUTPUT")
+                    .unwrap_or(&String::new())
+                    .clone(),
                 target_nodes,
+                bls_signer_per_node,
+                non_validating_node_ids,
+                stake_unhealthy: sub_matches.get_flag("STAKE_UNHEALTHY"),
+                continue_on_existing_subnet_validator: *sub_matches
+                    .get_one::<bool>("CONTINUE_ON_EXISTING_SUBNET_VALIDATOR")
+                    .unwrap_or(&true),
+                batch_validator_adds: sub_matches.get_flag("BATCH_VALIDATOR_ADDS"),
                 profile_name: sub_matches
                     .get_one::<String>("PROFILE_NAME")
                     .unwrap()
                     .clone(),
+                assume_role_arn: sub_matches
+                    .get_one::<String>("ASSUME_ROLE_ARN")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                external_id: sub_matches
+                    .get_one::<String>("EXTERNAL_ID")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                session_name: sub_matches
+                    .get_one::<String>("SESSION_NAME")
+                    .unwrap_or(&String::new())
+                    .clone(),
+
+                emit_node_config: sub_matches
+                    .get_one::<String>("EMIT_NODE_CONFIG")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                post_install_hook: sub_matches
+                    .get_one::<String>("POST_INSTALL_HOOK")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                fail_on_hook_error: sub_matches.get_flag("FAIL_ON_HOOK_ERROR"),
+
+                overall_timeout_minutes: *sub_matches
+                    .get_one::<u64>("OVERALL_TIMEOUT_MINUTES")
+                    .unwrap_or(&0),
+
+                subnet_id: sub_matches
+                    .get_one::<String>("SUBNET_ID")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                subnet_control_keys: sub_matches
+                    .get_one::<String>("SUBNET_CONTROL_KEYS")
+                    .map(|s| {
+                        s.split(',')
+                            .map(|k| k.trim().to_string())
+                            .filter(|k| !k.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                subnet_threshold: *sub_matches.get_one::<u32>("SUBNET_THRESHOLD").unwrap_or(&1),
+                exit_after_create_subnet: sub_matches.get_flag("EXIT_AFTER_CREATE_SUBNET"),
+                strict_id_prediction: sub_matches.get_flag("STRICT_ID_PREDICTION"),
+                no_check_acceptance: sub_matches.get_flag("NO_CHECK_ACCEPTANCE"),
+                tx_status_poll_interval_ms: *sub_matches
+                    .get_one::<u64>("TX_STATUS_POLL_INTERVAL_MS")
+                    .unwrap_or(&1000),
+                acceptance_timeout_seconds: *sub_matches
+                    .get_one::<u64>("ACCEPTANCE_TIMEOUT_SECONDS")
+                    .unwrap_or(&120),
+            })
+            .await
+            .unwrap_or_else(|e| {
+                // distinct exit codes per failure class (see "install_subnet_chain::InstallError")
+                eprintln!("failed to execute 'install-subnet-chain' ({e})");
+                std::process::exit(install_subnet_chain::exit_code(&e));
+            });
+        }
+
+        Some((estimate_cost::NAME, sub_matches)) => {
+            estimate_cost::execute(estimate_cost::Flags {
+                log_level: sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+
+                network_id: *sub_matches.get_one::<u32>("NETWORK_ID").unwrap(),
+                chain_rpc_url: sub_matches
+                    .get_one::<String>("CHAIN_RPC_URL")
+                    .unwrap_or(&String::new())
+                    .clone(),
+
+                node_count: *sub_matches.get_one::<u64>("NODE_COUNT").unwrap(),
+                staking_amount_in_avax: *sub_matches
+                    .get_one::<u64>("STAKING_AMOUNT_IN_AVAX")
+                    .unwrap_or(&2000),
+                stake_asset_symbol: sub_matches
+                    .get_one::<String>("STAKE_ASSET_SYMBOL")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                staking_decimals: *sub_matches.get_one::<u32>("STAKING_DECIMALS").unwrap_or(&9),
+                primary_network_validate_period_in_days: *sub_matches
+                    .get_one::<u64>("PRIMARY_NETWORK_VALIDATE_PERIOD_IN_DAYS")
+                    .unwrap_or(&16),
+                subnet_validate_period_in_days: *sub_matches
+                    .get_one::<u64>("SUBNET_VALIDATE_PERIOD_IN_DAYS")
+                    .unwrap_or(&14),
+            })
+            .await
+            .expect("failed to execute 'estimate-cost'");
+        }
+
+        Some((subnet_validators::NAME, sub_matches)) => {
+            subnet_validators::execute(subnet_validators::Flags {
+                log_level: sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+
+                chain_rpc_url: sub_matches
+                    .get_one::<String>("CHAIN_RPC_URL")
+                    .unwrap()
+                    .clone(),
+                subnet_id: sub_matches.get_one::<String>("SUBNET_ID").unwrap().clone(),
+
+                output: sub_matches
+                    .get_one::<String>("OUTPUT")
+                    .unwrap_or(&String::from("text"))
+                    .clone(),
             })
             .await
-            .expect("failed to execute 'install-subnet-chain'");
+            .expect("failed to execute 'subnet-validators'");
+        }
+
+        Some((renew_validators::NAME, sub_matches)) => {
+            renew_validators::execute(renew_validators::Flags {
+                log_level: sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+
+                chain_rpc_url: sub_matches
+                    .get_one::<String>("CHAIN_RPC_URL")
+                    .unwrap()
+                    .clone(),
+                key: Zeroizing::new(sub_matches.remove_one::<String>("KEY").unwrap()),
+                subnet_id: sub_matches.get_one::<String>("SUBNET_ID").unwrap().clone(),
+
+                window_days: *sub_matches.get_one::<u64>("WINDOW_DAYS").unwrap_or(&7),
+                subnet_validate_period_in_days: *sub_matches
+                    .get_one::<u64>("SUBNET_VALIDATE_PERIOD_IN_DAYS")
+                    .unwrap_or(&14),
+
+                renew_primary_network: sub_matches.get_flag("RENEW_PRIMARY_NETWORK"),
+                primary_network_validate_period_in_days: *sub_matches
+                    .get_one::<u64>("PRIMARY_NETWORK_VALIDATE_PERIOD_IN_DAYS")
+                    .unwrap_or(&16),
+                staking_amount_in_avax: *sub_matches
+                    .get_one::<u64>("STAKING_AMOUNT_IN_AVAX")
+                    .unwrap_or(&2000),
+                stake_asset_symbol: sub_matches
+                    .get_one::<String>("STAKE_ASSET_SYMBOL")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                staking_decimals: *sub_matches.get_one::<u32>("STAKING_DECIMALS").unwrap_or(&9),
+
+                apply: sub_matches.get_flag("APPLY"),
+                skip_prompt: sub_matches.get_flag("SKIP_PROMPT"),
+            })
+            .await
+            .expect("failed to execute 'renew-validators'");
+        }
+
+        Some((deploy_report::NAME, sub_matches)) => {
+            let s = sub_matches
+                .get_one::<String>("NON_VALIDATING_NODE_IDS")
+                .unwrap_or(&String::new())
+                .clone();
+            let non_validating_node_ids: Vec<String> = s
+                .split(',')
+                .map(|node_id| node_id.trim().to_string())
+                .filter(|node_id| !node_id.is_empty())
+                .collect();
+
+            let report = deploy_report::execute(deploy_report::Flags {
+                log_level: sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+
+                chain_rpc_url: sub_matches
+                    .get_one::<String>("CHAIN_RPC_URL")
+                    .unwrap()
+                    .clone(),
+                subnet_id: sub_matches.get_one::<String>("SUBNET_ID").unwrap().clone(),
+
+                target_nodes: sub_matches
+                    .get_one::<HashMap<String, avalanche_ops::aws::spec::RegionMachineId>>(
+                        "TARGET_NODES",
+                    )
+                    .unwrap()
+                    .clone(),
+                non_validating_node_ids,
+
+                output: sub_matches
+                    .get_one::<String>("OUTPUT")
+                    .unwrap_or(&String::from("text"))
+                    .clone(),
+            })
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!("failed to execute 'deploy-report' ({e})");
+                std::process::exit(1);
+            });
+            if !report.in_sync {
+                eprintln!(
+                    "deployment does not match the intended nodes ({} mismatched node(s), {} unexpected validator(s))",
+                    report.mismatched_nodes(),
+                    report.unexpected_validators.len()
+                );
+                std::process::exit(deploy_report::EXIT_CODE_OUT_OF_SYNC);
+            }
+        }
+
+        Some((wallet_address::NAME, sub_matches)) => {
+            wallet_address::execute(wallet_address::Flags {
+                log_level: sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+
+                key: Zeroizing::new(sub_matches.remove_one::<String>("KEY").unwrap()),
+                network_id: *sub_matches.get_one::<u32>("NETWORK_ID").unwrap(),
+
+                output: sub_matches
+                    .get_one::<String>("OUTPUT")
+                    .unwrap_or(&String::from("text"))
+                    .clone(),
+            })
+            .expect("failed to execute 'wallet-address'");
+        }
+
+        Some((wallet_balance::NAME, sub_matches)) => {
+            wallet_balance::execute(wallet_balance::Flags {
+                log_level: sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+
+                chain_rpc_url: sub_matches
+                    .get_one::<String>("CHAIN_RPC_URL")
+                    .unwrap()
+                    .clone(),
+                key_type: sub_matches
+                    .get_one::<String>("KEY_TYPE")
+                    .unwrap_or(&String::from("hot"))
+                    .clone(),
+                key: Zeroizing::new(sub_matches.remove_one::<String>("KEY").unwrap()),
+
+                region: sub_matches
+                    .get_one::<String>("REGION")
+                    .unwrap_or(&String::from("us-west-2"))
+                    .clone(),
+                profile_name: sub_matches
+                    .get_one::<String>("PROFILE_NAME")
+                    .unwrap_or(&String::from("default"))
+                    .clone(),
+
+                output: sub_matches
+                    .get_one::<String>("OUTPUT")
+                    .unwrap_or(&String::from("text"))
+                    .clone(),
+            })
+            .await
+            .expect("failed to execute 'wallet-balance'");
+        }
+
+        Some((rotate_key_plan::NAME, sub_matches)) => {
+            let new_owners: Vec<String> = sub_matches
+                .get_one::<String>("NEW_OWNERS")
+                .unwrap_or(&String::new())
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            rotate_key_plan::execute(rotate_key_plan::Flags {
+                log_level: sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+                chain_rpc_url: sub_matches
+                    .get_one::<String>("CHAIN_RPC_URL")
+                    .unwrap()
+                    .clone(),
+                subnet_id: sub_matches.get_one::<String>("SUBNET_ID").unwrap().clone(),
+                old_key: Zeroizing::new(sub_matches.remove_one::<String>("OLD_KEY").unwrap()),
+                new_key: Zeroizing::new(sub_matches.remove_one::<String>("NEW_KEY").unwrap()),
+                new_owners,
+                new_threshold: *sub_matches.get_one::<u32>("NEW_THRESHOLD").unwrap_or(&1),
+            })
+            .await
+            .expect("failed to execute 'rotate-key-plan'");
+        }
+
+        Some((predict_ids::NAME, sub_matches)) => {
+            predict_ids::execute(predict_ids::Flags {
+                log_level: sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+
+                chain_rpc_url: sub_matches
+                    .get_one::<String>("CHAIN_RPC_URL")
+                    .unwrap()
+                    .clone(),
+                key: Zeroizing::new(sub_matches.remove_one::<String>("KEY").unwrap()),
+
+                vm_id: sub_matches
+                    .get_one::<String>("VM_ID")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                vm_id_derivation: sub_matches
+                    .get_one::<String>("VM_ID_DERIVATION")
+                    .unwrap()
+                    .clone(),
+                vm_binary_local_path: sub_matches
+                    .get_one::<String>("VM_BINARY_LOCAL_PATH")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                chain_name: sub_matches.get_one::<String>("CHAIN_NAME").unwrap().clone(),
+                chain_genesis_path: sub_matches
+                    .get_one::<String>("CHAIN_GENESIS_PATH")
+                    .unwrap_or(&String::new())
+                    .clone(),
+                subnet_id: sub_matches
+                    .get_one::<String>("SUBNET_ID")
+                    .unwrap_or(&String::new())
+                    .clone(),
+            })
+            .await
+            .expect("failed to execute 'predict-ids'");
+        }
+
+        Some((create_ssm_doc::NAME, sub_matches)) => {
+            let regions: Vec<String> = sub_matches
+                .get_one::<String>("REGIONS")
+                .unwrap()
+                .split(',')
+                .map(|region| region.trim().to_string())
+                .filter(|region| !region.is_empty())
+                .collect();
+
+            create_ssm_doc::execute(create_ssm_doc::Flags {
+                log_level: sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+
+                regions,
+                ssm_doc_name: sub_matches
+                    .get_one::<String>("SSM_DOC_NAME")
+                    .unwrap()
+                    .clone(),
+
+                profile_name: sub_matches
+                    .get_one::<String>("PROFILE_NAME")
+                    .unwrap()
+                    .clone(),
+            })
+            .await
+            .expect("failed to execute 'create-ssm-doc'");
+        }
+
+        Some((prune_s3_artifacts::NAME, sub_matches)) => {
+            prune_s3_artifacts::execute(prune_s3_artifacts::Flags {
+                log_level: sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+                skip_prompt: sub_matches.get_flag("SKIP_PROMPT"),
+
+                s3_region: sub_matches.get_one::<String>("S3_REGION").unwrap().clone(),
+                s3_bucket: sub_matches.get_one::<String>("S3_BUCKET").unwrap().clone(),
+                s3_key_prefix: sub_matches
+                    .get_one::<String>("S3_KEY_PREFIX")
+                    .unwrap()
+                    .clone(),
+
+                keep_last: *sub_matches.get_one::<usize>("KEEP_LAST").unwrap_or(&3),
+                dry_run: sub_matches.get_flag("DRY_RUN"),
+
+                profile_name: sub_matches
+                    .get_one::<String>("PROFILE_NAME")
+                    .unwrap()
+                    .clone(),
+            })
+            .await
+            .expect("failed to execute 'prune-s3-artifacts'");
         }
 
         Some((subnet_config::NAME, sub_matches)) => {
@@ -576,6 +1226,16 @@ async fn main() -> io::Result<()> {
             .expect("failed to execute 'endpoints'");
         }
 
+        Some((networks::NAME, sub_matches)) => {
+            networks::execute(
+                &sub_matches
+                    .get_one::<String>("LOG_LEVEL")
+                    .unwrap_or(&String::from("info"))
+                    .clone(),
+            )
+            .expect("failed to execute 'networks'");
+        }
+
         _ => unreachable!("unknown subcommand"),
     }
 
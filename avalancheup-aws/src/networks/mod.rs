@@ -0,0 +1,58 @@
+use std::io::{self, stdout};
+
+use clap::{Arg, Command};
+use crossterm::{
+    execute,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+
+pub const NAME: &str = "networks";
+
+/// Known network Ids and their human-readable names.
+/// ref. <https://github.com/ava-labs/avalanchego/blob/master/utils/constants/network_ids.go>
+pub const KNOWN_NETWORKS: &[(u32, &str)] = &[(1, "Mainnet"), (5, "Fuji")];
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Lists the known network Ids and their names")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+}
+
+/// Returns the human-readable network name (e.g., "Mainnet", "Fuji", "Local/Custom(1000)").
+pub fn display_name(network_id: u32) -> String {
+    for (id, name) in KNOWN_NETWORKS.iter() {
+        if *id == network_id {
+            return name.to_string();
+        }
+    }
+    format!("Local/Custom({network_id})")
+}
+
+pub fn execute(log_level: &str) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, log_level),
+    );
+
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Green),
+        Print("\nKNOWN NETWORKS\n"),
+        ResetColor
+    )?;
+    for (id, name) in KNOWN_NETWORKS.iter() {
+        println!("{id}: {name}");
+    }
+    println!("(any other network Id): Local/Custom(<id>)");
+
+    Ok(())
+}
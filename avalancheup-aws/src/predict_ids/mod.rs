@@ -0,0 +1,236 @@
+use std::{
+    fs::File,
+    io::{self, stdout, BufReader, Error, ErrorKind, Read},
+    path::Path,
+    str::FromStr,
+};
+
+use avalanche_types::ids;
+use clap::{Arg, Command};
+use crossterm::{
+    execute,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+pub const NAME: &str = "predict-ids";
+
+/// Defines "predict-ids" option.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Flags {
+    pub log_level: String,
+
+    pub chain_rpc_url: String,
+    pub key: Zeroizing<String>,
+
+    pub vm_id: String,
+    pub vm_id_derivation: String,
+    pub vm_binary_local_path: String,
+    pub chain_name: String,
+    pub chain_genesis_path: String,
+    pub subnet_id: String,
+}
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Predicts the Id of the next subnet (or blockchain, with --subnet-id) transaction from the wallet's current UTXO set, without issuing any transaction (not valid if any other transaction spends from the wallet first, e.g., the validator adds in 'install-subnet-chain')")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("CHAIN_RPC_URL")
+                .long("chain-rpc-url")
+                .help("Sets the P-chain or Avalanche RPC endpoint")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("KEY")
+                .long("key")
+                .help("Sets the key Id (if hotkey, use private key in hex format)")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("VM_ID")
+                .long("vm-id")
+                .help("Sets the 32-byte Vm Id for the Vm binary (if empty, derives the Id with --vm-id-derivation)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("VM_ID_DERIVATION")
+                .long("vm-id-derivation")
+                .help("Sets how to derive the Vm Id if --vm-id is empty (same as 'install-subnet-chain')")
+                .required(false)
+                .num_args(1)
+                .value_parser([
+                    avalancheup_aws::install_subnet_chain::VM_ID_DERIVATION_NAME,
+                    avalancheup_aws::install_subnet_chain::VM_ID_DERIVATION_SHA256_BINARY,
+                ])
+                .default_value(avalancheup_aws::install_subnet_chain::VM_ID_DERIVATION_NAME),
+        )
+        .arg(
+            Arg::new("VM_BINARY_LOCAL_PATH")
+                .long("vm-binary-local-path")
+                .help("VM binary local file path (required for --vm-id-derivation 'sha256-binary')")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("CHAIN_NAME")
+                .long("chain-name")
+                .help("Sets the chain name")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("CHAIN_GENESIS_PATH")
+                .long("chain-genesis-path")
+                .help("Chain genesis file path (if empty, uses empty genesis bytes for the blockchain Id prediction)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("SUBNET_ID")
+                .long("subnet-id")
+                .help("Sets the existing subnet Id to predict the blockchain Id of (if empty, only predicts the subnet Id)")
+                .required(false)
+                .num_args(1),
+        )
+}
+
+/// Predicts the Id of the next transaction using the wallet "dry mode".
+/// The transaction Id is derived from the wallet's current UTXOs, so the
+/// prediction only holds if the transaction is the next one issued by the
+/// wallet (e.g., not after the primary network validator adds in "install-subnet-chain").
+/// The blockchain Id is only predicted for an existing subnet, since the chain
+/// creation spends from the UTXOs left after the subnet creation.
+pub async fn execute(opts: Flags) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, opts.log_level),
+    );
+
+    if !opts.vm_id.is_empty()
+        && opts.vm_id_derivation != avalancheup_aws::install_subnet_chain::VM_ID_DERIVATION_NAME
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "--vm-id and --vm-id-derivation are mutually exclusive",
+        ));
+    }
+    avalancheup_aws::install_subnet_chain::validate_chain_name(
+        &opts.chain_name,
+        opts.vm_id.is_empty()
+            && opts.vm_id_derivation
+                == avalancheup_aws::install_subnet_chain::VM_ID_DERIVATION_NAME,
+    )?;
+
+    let mut chain_genesis_bytes = Vec::new();
+    if !opts.chain_genesis_path.is_empty() {
+        if !Path::new(&opts.chain_genesis_path).exists() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("chain genesis file '{}' not found", opts.chain_genesis_path),
+            ));
+        }
+        let f = File::open(&opts.chain_genesis_path).map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to open {} ({})", opts.chain_genesis_path, e),
+            )
+        })?;
+        let mut reader = BufReader::new(f);
+        reader.read_to_end(&mut chain_genesis_bytes)?;
+    }
+
+    let vm_id = if opts.vm_id.is_empty() {
+        avalancheup_aws::install_subnet_chain::derive_vm_id(
+            &opts.vm_id_derivation,
+            &opts.chain_name,
+            &opts.vm_binary_local_path,
+        )?
+    } else {
+        ids::Id::from_str(&opts.vm_id)?
+    };
+    log::info!("VM ID is {}", vm_id.to_string());
+
+    let network_id = avalancheup_aws::install_subnet_chain::fetch_network_id(
+        &opts.chain_rpc_url,
+        &[],
+        &avalancheup_aws::install_subnet_chain::ApiPaths::default(),
+    )
+    .await?;
+
+    let priv_key = avalancheup_aws::install_subnet_chain::load_private_key(&opts.key)?;
+    let wallet_to_spend =
+        avalancheup_aws::install_subnet_chain::build_wallet(&opts.chain_rpc_url, &priv_key).await?;
+    let p_chain_address = priv_key
+        .to_public_key()
+        .to_hrp_address(network_id, "P")
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("failed to derive the P-chain address ({})", e),
+            )
+        })?;
+    log::info!("loaded wallet '{p_chain_address}' (network id {network_id})");
+
+    let predicted = if opts.subnet_id.is_empty() {
+        let subnet_id = wallet_to_spend
+            .p()
+            .create_subnet()
+            .dry_mode(true)
+            .issue()
+            .await
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!("failed to predict the subnet Id ({})", e),
+                )
+            })?;
+        log::info!("[dry mode] subnet Id '{}'", subnet_id);
+        format!("subnet Id: {subnet_id}\nblockchain Id: (unknown until the subnet is created, re-run with --subnet-id)")
+    } else {
+        let subnet_id = ids::Id::from_str(&opts.subnet_id)?;
+        let blockchain_id = wallet_to_spend
+            .p()
+            .create_chain()
+            .subnet_id(subnet_id)
+            .genesis_data(chain_genesis_bytes)
+            .vm_id(vm_id)
+            .chain_name(opts.chain_name.clone())
+            .dry_mode(true)
+            .issue()
+            .await
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!("failed to predict the blockchain Id ({})", e),
+                )
+            })?;
+        log::info!("[dry mode] blockchain Id {blockchain_id} for subnet {subnet_id}");
+        format!("subnet Id: {subnet_id} (existing)\nblockchain Id: {blockchain_id}")
+    };
+
+    println!();
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Blue),
+        Print(format!(
+            "\n\n\nPREDICTED (only if issued as the next transaction of the wallet '{p_chain_address}')\n{predicted}\nVM Id: {vm_id}\n\n"
+        )),
+        ResetColor
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, stdout, Error, ErrorKind},
+    str::FromStr,
+};
+
+use avalanche_types::ids;
+use aws_manager::{self, s3};
+use aws_sdk_s3::types::{Delete, ObjectIdentifier};
+use clap::{value_parser, Arg, Command};
+use crossterm::{
+    execute,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+use dialoguer::{theme::ColorfulTheme, Select};
+use serde::{Deserialize, Serialize};
+use tokio::time::Duration;
+
+pub const NAME: &str = "prune-s3-artifacts";
+
+/// Maximum number of keys per "DeleteObjects" request.
+/// ref. <https://docs.aws.amazon.com/AmazonS3/latest/API/API_DeleteObjects.html>
+const DELETE_OBJECTS_BATCH: usize = 1000;
+
+/// Defines "prune-s3-artifacts" option.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Flags {
+    pub log_level: String,
+    pub skip_prompt: bool,
+
+    pub s3_region: String,
+    pub s3_bucket: String,
+    pub s3_key_prefix: String,
+
+    pub keep_last: usize,
+    pub dry_run: bool,
+
+    pub profile_name: String,
+}
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Prunes old artifacts (VM binaries, configs) uploaded by 'install-subnet-chain'")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("SKIP_PROMPT")
+                .long("skip-prompt")
+                .help("Skips prompt mode")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("S3_REGION")
+                .long("s3-region")
+                .help("Sets the AWS S3 region")
+                .required(true)
+                .num_args(1)
+                .default_value("us-west-2"),
+        )
+        .arg(
+            Arg::new("S3_BUCKET")
+                .long("s3-bucket")
+                .help("Sets the S3 bucket")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("S3_KEY_PREFIX")
+                .long("s3-key-prefix")
+                .help("Sets the S3 key prefix to prune artifacts under")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("KEEP_LAST")
+                .long("keep-last")
+                .help("Sets the number of most recent objects to keep per artifact group")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(usize))
+                .default_value("3"),
+        )
+        .arg(
+            Arg::new("DRY_RUN")
+                .long("dry-run")
+                .help("Only lists the objects that would be deleted")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("PROFILE_NAME")
+                .long("profile-name")
+                .help("Sets the AWS credential profile name for API calls/endpoints")
+                .required(false)
+                .default_value("default")
+                .num_args(1),
+        )
+}
+
+/// Returns the artifact group of the S3 key.
+/// VM binaries are uploaded with the VM Id as the file name, thus grouped together.
+/// Other artifacts (e.g., configs, SSM output logs) are grouped by the file name.
+fn artifact_group(s3_key: &str) -> String {
+    let file_name = s3_key.rsplit('/').next().unwrap_or(s3_key);
+    if ids::Id::from_str(file_name).is_ok() {
+        String::from("vm-binary")
+    } else {
+        file_name.to_string()
+    }
+}
+
+pub async fn execute(mut opts: Flags) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, opts.log_level),
+    );
+
+    // never prune the whole bucket (errors on empty prefix)
+    opts.s3_key_prefix =
+        avalancheup_aws::install_subnet_chain::normalize_s3_key_prefix(&opts.s3_key_prefix)?;
+    log::info!("normalized S3 key prefix '{}'", opts.s3_key_prefix);
+
+    let shared_config = aws_manager::load_config(
+        Some(opts.s3_region.clone()),
+        Some(opts.profile_name.clone()),
+        Some(Duration::from_secs(30)),
+    )
+    .await;
+    let s3_manager = s3::Manager::new(&shared_config);
+
+    // sorted by the "last_modified" timestamps, latest first
+    let objects = s3_manager
+        .list_objects(
+            &opts.s3_bucket,
+            Some(&s3::append_slash(&opts.s3_key_prefix)),
+        )
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, format!("failed list_objects {}", e)))?;
+
+    let mut group_to_keys: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for obj in objects.iter() {
+        let k = obj.key().unwrap_or_default().to_string();
+        group_to_keys.entry(artifact_group(&k)).or_default().push(k);
+    }
+
+    let mut keys_to_delete = Vec::new();
+    for (group, keys) in group_to_keys.iter() {
+        log::info!(
+            "artifact group '{group}' has {} object(s), keeping {}",
+            keys.len(),
+            keys.len().min(opts.keep_last)
+        );
+        keys_to_delete.extend(keys.iter().skip(opts.keep_last).cloned());
+    }
+
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Red),
+        Print(format!(
+            "\n\n\n{} of {} object(s) under 's3://{}/{}' to delete (keeping last {} per group)\n",
+            keys_to_delete.len(),
+            objects.len(),
+            opts.s3_bucket,
+            s3::append_slash(&opts.s3_key_prefix),
+            opts.keep_last,
+        )),
+        ResetColor
+    )?;
+    for k in keys_to_delete.iter() {
+        println!("{k}");
+    }
+
+    if keys_to_delete.is_empty() || opts.dry_run {
+        return Ok(());
+    }
+
+    if !opts.skip_prompt {
+        let options = &[
+            format!(
+                "No, I am not ready to delete {} object(s)",
+                keys_to_delete.len()
+            ),
+            format!("Yes, let's delete {} object(s)", keys_to_delete.len()),
+        ];
+        let selected = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select your 'prune-s3-artifacts' option")
+            .items(&options[..])
+            .default(0)
+            .interact()
+            .unwrap();
+        if selected == 0 {
+            return Ok(());
+        }
+    }
+
+    for batch in keys_to_delete.chunks(DELETE_OBJECTS_BATCH) {
+        let object_ids: Vec<ObjectIdentifier> = batch
+            .iter()
+            .map(|k| ObjectIdentifier::builder().key(k.clone()).build())
+            .collect();
+        let deletes = Delete::builder().set_objects(Some(object_ids)).build();
+        s3_manager
+            .cli
+            .delete_objects()
+            .bucket(opts.s3_bucket.clone())
+            .delete(deletes)
+            .send()
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("failed delete_objects {}", e)))?;
+        log::info!("deleted {} object(s)", batch.len());
+    }
+
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Blue),
+        Print(format!(
+            "\n\n\nSUCCESS!\ndeleted {} object(s)\n\n",
+            keys_to_delete.len()
+        )),
+        ResetColor
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,586 @@
+use std::{
+    io::{self, stdout, Error, ErrorKind},
+    str::FromStr,
+};
+
+use avalanche_types::ids;
+use chrono::{TimeZone, Utc};
+use clap::{value_parser, Arg, Command};
+use crossterm::{
+    execute,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+use dialoguer::{theme::ColorfulTheme, Select};
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Duration, Instant};
+use zeroize::Zeroizing;
+
+use crate::subnet_validators::{self, SubnetValidator};
+
+pub const NAME: &str = "renew-validators";
+
+/// Seconds from the issue to the start of the new validation period
+/// (same offset as "install-subnet-chain").
+const START_OFFSET_SECS: u64 = 60;
+
+/// Maximum wait for an ended validator to leave the validator set
+/// (removed once the P-chain advances its time past the end time).
+const LEAVE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Defines "renew-validators" option.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Flags {
+    pub log_level: String,
+
+    pub chain_rpc_url: String,
+    pub key: Zeroizing<String>,
+    pub subnet_id: String,
+
+    pub window_days: u64,
+    pub subnet_validate_period_in_days: u64,
+
+    pub renew_primary_network: bool,
+    pub primary_network_validate_period_in_days: u64,
+    pub staking_amount_in_avax: u64,
+    pub stake_asset_symbol: String,
+    pub staking_decimals: u32,
+
+    pub apply: bool,
+    pub skip_prompt: bool,
+}
+
+/// Subnet validator to re-add for a new period.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Renewal {
+    pub node_id: String,
+    pub weight: u64,
+    pub end_time: u64,
+    /// None if the node is not a current primary network validator.
+    pub primary_end_time: Option<u64>,
+    /// True to re-add the primary network validator first, since its
+    /// validation ends before the new subnet validation does.
+    pub renew_primary: bool,
+    /// The P-chain rejects a node that is still in the validator set, so the
+    /// renewal is issued once the validation ending last (subnet, or primary
+    /// network if renewed) has ended.
+    pub issue_after: u64,
+    pub new_end_time: u64,
+    /// Set if the subnet validator cannot be renewed (thus skipped).
+    pub skip_reason: Option<String>,
+}
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Re-adds the subnet validators (and optionally their primary network validators) whose validation ends within the window, for a new period once it has ended (dry run unless --apply)")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("CHAIN_RPC_URL")
+                .long("chain-rpc-url")
+                .help("Sets the P-chain or Avalanche RPC endpoint")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("KEY")
+                .long("key")
+                .help("Hex-encoded hot key of the funding wallet (the subnet control key)")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("SUBNET_ID")
+                .long("subnet-id")
+                .help("Sets the subnet Id to renew the validators of")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("WINDOW_DAYS")
+                .long("window-days")
+                .help("Sets the number of days before the end of the validation to renew within")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("7"),
+        )
+        .arg(
+            Arg::new("SUBNET_VALIDATE_PERIOD_IN_DAYS")
+                .long("subnet-validate-period-in-days")
+                .help("Sets the number of days of the new subnet validation (capped at the end of the primary network validation unless renewed)")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("14"),
+        )
+        .arg(
+            Arg::new("RENEW_PRIMARY_NETWORK")
+                .long("renew-primary-network")
+                .help("Sets to also re-add the primary network validators that end before the new subnet validation")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("PRIMARY_NETWORK_VALIDATE_PERIOD_IN_DAYS")
+                .long("primary-network-validate-period-in-days")
+                .help("Sets the number of days of the new primary network validation")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("16"),
+        )
+        .arg(
+            Arg::new("STAKING_AMOUNT_IN_AVAX")
+                .long("staking-amount-in-avax")
+                .help("Sets the staking amount in the staking asset (e.g., AVAX, not in nAVAX) for the renewed primary network validators")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u64))
+                .default_value("2000"),
+        )
+        .args(avalancheup_aws::install_subnet_chain::staking_asset_args())
+        .arg(
+            Arg::new("APPLY")
+                .long("apply")
+                .help("Sets to issue the renewal transactions, each once the current validation has ended (otherwise, only prints the plan)")
+                .required(false)
+                .num_args(0),
+        )
+        .arg(
+            Arg::new("SKIP_PROMPT")
+                .long("skip-prompt")
+                .short('s')
+                .help("Skips prompt mode")
+                .required(false)
+                .num_args(0),
+        )
+}
+
+pub async fn execute(opts: Flags) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default()
+            .filter_or(env_logger::DEFAULT_FILTER_ENV, opts.log_level.as_str()),
+    );
+
+    let subnet_id = ids::Id::from_str(&opts.subnet_id).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid --subnet-id '{}' ({})", opts.subnet_id, e),
+        )
+    })?;
+    let priv_key = avalancheup_aws::install_subnet_chain::load_private_key(&opts.key)?;
+    let staking_asset = avalancheup_aws::install_subnet_chain::StakingAsset::new(
+        "",
+        &opts.stake_asset_symbol,
+        opts.staking_decimals,
+    )?;
+
+    // ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetcurrentvalidators>
+    let now = Utc::now().timestamp() as u64;
+    let subnet_result = avalancheup_aws::install_subnet_chain::call_json_rpc(
+        &opts.chain_rpc_url,
+        "/ext/bc/P",
+        "platform.getCurrentValidators",
+        serde_json::json!({ "subnetID": subnet_id.to_string() }),
+        &[],
+    )
+    .await?;
+    let validators = subnet_validators::parse_validators(&subnet_result, now)?;
+    let primary_result = avalancheup_aws::install_subnet_chain::call_json_rpc(
+        &opts.chain_rpc_url,
+        "/ext/bc/P",
+        "platform.getCurrentValidators",
+        serde_json::json!({}),
+        &[],
+    )
+    .await?;
+    let primary_validators = subnet_validators::parse_validators(&primary_result, now)?;
+
+    let renewals = plan_renewals(&opts, &validators, &primary_validators, now);
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Green),
+        Print(format!(
+            "\nRENEWAL PLAN ({subnet_id}, {} of {} validator(s) ending within {} days)\n",
+            renewals.len(),
+            validators.len(),
+            opts.window_days
+        )),
+        ResetColor
+    )?;
+    for r in renewals.iter() {
+        match &r.skip_reason {
+            Some(reason) => println!(
+                "{}\tend {}\tSKIP ({reason})",
+                r.node_id,
+                format_time(r.end_time)
+            ),
+            None => println!(
+                "{}\tend {}\t-> re-add after {}, new end {}{}",
+                r.node_id,
+                format_time(r.end_time),
+                format_time(r.issue_after),
+                format_time(r.new_end_time),
+                if r.renew_primary {
+                    format!(
+                        "\t(+ primary network validator for {} days, staking {} {})",
+                        opts.primary_network_validate_period_in_days,
+                        opts.staking_amount_in_avax,
+                        staking_asset.symbol()
+                    )
+                } else {
+                    String::new()
+                }
+            ),
+        }
+    }
+    let renewals: Vec<Renewal> = renewals
+        .into_iter()
+        .filter(|r| r.skip_reason.is_none())
+        .collect();
+    if renewals.is_empty() {
+        println!("\nnothing to renew");
+        return Ok(());
+    }
+    if !opts.apply {
+        println!("\ndry run, set --apply to issue the renewal transactions");
+        return Ok(());
+    }
+
+    if !opts.skip_prompt {
+        let options = &[
+            format!(
+                "No, I am not ready to renew {} validator(s) of the subnet {subnet_id}",
+                renewals.len()
+            ),
+            format!(
+                "Yes, let's renew {} validator(s) of the subnet {subnet_id}",
+                renewals.len()
+            ),
+        ];
+        let selected = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select your 'renew-validators' option")
+            .items(&options[..])
+            .default(0)
+            .interact()
+            .unwrap();
+        if selected == 0 {
+            return Ok(());
+        }
+    }
+
+    let stake_amount = staking_asset.to_base_units(opts.staking_amount_in_avax);
+    if stake_amount > primitive_types::U256::from(u64::MAX) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "staking amount {} {} overflows",
+                opts.staking_amount_in_avax,
+                staking_asset.symbol()
+            ),
+        ));
+    }
+    let stake_amount_in_navax = stake_amount.as_u64();
+    let wallet_to_spend =
+        avalancheup_aws::install_subnet_chain::build_wallet(&opts.chain_rpc_url, &priv_key).await?;
+
+    // issued one by one (in the order of "issue_after"), to prevent UTXO double
+    // spends from the same wallet
+    let mut failed = Vec::new();
+    for r in renewals.iter() {
+        let node_id = ids::node::Id::from_str(&r.node_id)?;
+        if let Err(e) = wait_until_left(&opts.chain_rpc_url, &subnet_id, r).await {
+            log::warn!("'{node_id}' is still a validator, skipping the renewal ({e})");
+            failed.push(format!("{node_id} (still validating: {e})"));
+            continue;
+        }
+        if r.renew_primary {
+            log::info!("re-adding '{node_id}' as a primary network validator");
+            match wallet_to_spend
+                .p()
+                .add_validator()
+                .node_id(node_id)
+                .stake_amount(stake_amount_in_navax)
+                .validate_period_in_days(
+                    opts.primary_network_validate_period_in_days,
+                    START_OFFSET_SECS,
+                )
+                .check_acceptance(true)
+                .issue()
+                .await
+            {
+                Ok((tx_id, added)) => {
+                    log::info!("primary network validator tx id {tx_id}, added {added}")
+                }
+                Err(e) => {
+                    log::warn!("failed add_validator for '{node_id}' ({e})");
+                    failed.push(format!("{node_id} (primary network: {e})"));
+                    continue;
+                }
+            }
+        }
+
+        log::info!("re-adding '{node_id}' as a subnet validator of '{subnet_id}'");
+        let end_time = Utc
+            .timestamp_opt(r.new_end_time as i64, 0)
+            .single()
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("end time {} is out of range", r.new_end_time),
+                )
+            })?;
+        match wallet_to_spend
+            .p()
+            .add_subnet_validator()
+            .node_id(node_id)
+            .subnet_id(subnet_id)
+            .validate_period_in_days(opts.subnet_validate_period_in_days, START_OFFSET_SECS)
+            .end_time(end_time)
+            .check_acceptance(true)
+            .issue()
+            .await
+        {
+            Ok((tx_id, added)) => {
+                log::info!("subnet validator tx id {tx_id}, added {added}")
+            }
+            Err(e) => {
+                log::warn!("failed add_subnet_validator for '{node_id}' ({e})");
+                failed.push(format!("{node_id} (subnet: {e})"));
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!(
+                "failed to renew {} of {} validator(s): {}",
+                failed.len(),
+                renewals.len(),
+                failed.join(", ")
+            ),
+        ));
+    }
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Green),
+        Print(format!("\nrenewed {} validator(s)\n", renewals.len())),
+        ResetColor
+    )?;
+    Ok(())
+}
+
+/// Sleeps until the renewal can be issued, then polls until the node has left
+/// the current subnet validator set (and the primary network validator set if
+/// renewed), since the P-chain rejects a node that is still validating.
+async fn wait_until_left(chain_rpc_url: &str, subnet_id: &ids::Id, r: &Renewal) -> io::Result<()> {
+    let now = Utc::now().timestamp() as u64;
+    if r.issue_after > now {
+        log::info!(
+            "waiting until {} for the validation of '{}' to end",
+            format_time(r.issue_after),
+            r.node_id
+        );
+        sleep(Duration::from_secs(r.issue_after - now)).await;
+    }
+
+    let mut validator_sets = vec![serde_json::json!({ "subnetID": subnet_id.to_string() })];
+    if r.renew_primary {
+        validator_sets.push(serde_json::json!({}));
+    }
+    let deadline = Instant::now() + LEAVE_TIMEOUT;
+    for params in validator_sets {
+        loop {
+            // ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetcurrentvalidators>
+            let result = avalancheup_aws::install_subnet_chain::call_json_rpc(
+                chain_rpc_url,
+                "/ext/bc/P",
+                "platform.getCurrentValidators",
+                params.clone(),
+                &[],
+            )
+            .await?;
+            let now = Utc::now().timestamp() as u64;
+            if !subnet_validators::parse_validators(&result, now)?
+                .iter()
+                .any(|v| v.node_id == r.node_id)
+            {
+                break;
+            }
+            if Instant::now() >= deadline {
+                return Err(Error::new(
+                    ErrorKind::TimedOut,
+                    format!(
+                        "'{}' did not leave the validator set in {:?}",
+                        r.node_id, LEAVE_TIMEOUT
+                    ),
+                ));
+            }
+            sleep(Duration::from_secs(10)).await;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the subnet validators ending within the window (sorted by the time
+/// to issue the renewal), with the end of the new validation capped at the end
+/// of the primary network validation, unless the primary network validator is
+/// renewed too. The new validation starts once the current one has ended.
+fn plan_renewals(
+    opts: &Flags,
+    subnet_validators: &[SubnetValidator],
+    primary_validators: &[SubnetValidator],
+    now: u64,
+) -> Vec<Renewal> {
+    let mut renewals = Vec::new();
+    for v in subnet_validators
+        .iter()
+        .filter(|v| v.remaining_secs <= opts.window_days * 86400)
+    {
+        let primary_end_time = primary_validators
+            .iter()
+            .find(|p| p.node_id == v.node_id)
+            .map(|p| p.end_time);
+
+        let subnet_start = now.max(v.end_time) + START_OFFSET_SECS;
+        let primary_ends_early = primary_end_time.map_or(true, |end| {
+            end < subnet_start + opts.subnet_validate_period_in_days * 86400
+        });
+        let renew_primary = primary_ends_early && opts.renew_primary_network;
+
+        // the renewed primary network validation must end before it is re-added
+        let issue_after = if renew_primary {
+            v.end_time.max(primary_end_time.unwrap_or_default())
+        } else {
+            v.end_time
+        };
+        let start = now.max(issue_after) + START_OFFSET_SECS;
+        let desired_end = start + opts.subnet_validate_period_in_days * 86400;
+        let renewed_primary_end = start + opts.primary_network_validate_period_in_days * 86400;
+
+        let new_end_time = if renew_primary {
+            desired_end.min(renewed_primary_end)
+        } else {
+            desired_end.min(primary_end_time.unwrap_or(desired_end))
+        };
+        let skip_reason = if primary_end_time.is_none() && !renew_primary {
+            Some(String::from(
+                "not a primary network validator, set --renew-primary-network",
+            ))
+        } else if new_end_time <= start {
+            Some(String::from(
+                "primary network validation ends before the new period, set --renew-primary-network",
+            ))
+        } else {
+            None
+        };
+
+        renewals.push(Renewal {
+            node_id: v.node_id.clone(),
+            weight: v.weight,
+            end_time: v.end_time,
+            primary_end_time,
+            renew_primary,
+            issue_after,
+            new_end_time,
+            skip_reason,
+        });
+    }
+    renewals.sort_by(|a, b| {
+        a.issue_after
+            .cmp(&b.issue_after)
+            .then_with(|| a.node_id.cmp(&b.node_id))
+    });
+    renewals
+}
+
+fn format_time(secs: u64) -> String {
+    Utc.timestamp_opt(secs as i64, 0)
+        .single()
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_else(|| secs.to_string())
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --bin avalancheup-aws -- renew_validators::test_plan_renewals --exact --show-output
+#[test]
+fn test_plan_renewals() {
+    let day = 86400;
+    let now = 1_000 * day;
+    let validator = |node_id: &str, end_time: u64| SubnetValidator {
+        node_id: node_id.to_string(),
+        weight: 100,
+        start_time: now - 30 * day,
+        end_time,
+        remaining_secs: end_time.saturating_sub(now),
+    };
+    let mut opts = Flags {
+        log_level: String::from("info"),
+        chain_rpc_url: String::new(),
+        key: Zeroizing::new(String::new()),
+        subnet_id: String::new(),
+        window_days: 7,
+        subnet_validate_period_in_days: 14,
+        renew_primary_network: false,
+        primary_network_validate_period_in_days: 16,
+        staking_amount_in_avax: 2000,
+        stake_asset_symbol: String::new(),
+        staking_decimals: 9,
+        apply: false,
+        skip_prompt: true,
+    };
+
+    let subnet_validators = vec![
+        validator("NodeID-A", now + 2 * day),
+        validator("NodeID-B", now + day),
+        validator("NodeID-C", now + 30 * day),
+        validator("NodeID-D", now + 3 * day),
+    ];
+    let primary_validators = vec![
+        validator("NodeID-A", now + 60 * day),
+        validator("NodeID-B", now + 5 * day),
+    ];
+
+    let renewals = plan_renewals(&opts, &subnet_validators, &primary_validators, now);
+    assert_eq!(renewals.len(), 3);
+
+    // issued after the current subnet validation ends, not while still validating
+    assert_eq!(renewals[0].node_id, "NodeID-B");
+    assert_eq!(renewals[0].issue_after, now + day);
+    // capped at the end of the primary network validation
+    assert_eq!(renewals[0].new_end_time, now + 5 * day);
+    assert!(renewals[0].skip_reason.is_none());
+    assert_eq!(renewals[1].node_id, "NodeID-A");
+    assert_eq!(renewals[1].issue_after, now + 2 * day);
+    assert_eq!(
+        renewals[1].new_end_time,
+        now + 2 * day + START_OFFSET_SECS + 14 * day
+    );
+    assert!(renewals[1].skip_reason.is_none());
+    assert_eq!(renewals[2].node_id, "NodeID-D");
+    assert!(renewals[2].skip_reason.is_some());
+
+    // the renewed primary network validation must end first
+    opts.renew_primary_network = true;
+    let renewals = plan_renewals(&opts, &subnet_validators, &primary_validators, now);
+    let b = renewals.iter().find(|r| r.node_id == "NodeID-B").unwrap();
+    assert!(b.renew_primary);
+    assert_eq!(b.issue_after, now + 5 * day);
+    assert_eq!(b.new_end_time, now + 5 * day + START_OFFSET_SECS + 14 * day);
+    assert!(b.skip_reason.is_none());
+    let d = renewals.iter().find(|r| r.node_id == "NodeID-D").unwrap();
+    assert!(d.renew_primary);
+    assert_eq!(d.issue_after, now + 3 * day);
+    assert!(renewals
+        .windows(2)
+        .all(|w| w[0].issue_after <= w[1].issue_after));
+}
@@ -0,0 +1,291 @@
+use std::{
+    collections::HashSet,
+    io::{self, stdout, Error, ErrorKind},
+    str::FromStr,
+};
+
+use avalanche_types::{ids, units};
+use clap::{value_parser, Arg, Command};
+use crossterm::{
+    execute,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+pub const NAME: &str = "rotate-key-plan";
+
+/// Defines "rotate-key-plan" option.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Flags {
+    pub log_level: String,
+    pub chain_rpc_url: String,
+    pub subnet_id: String,
+    pub old_key: Zeroizing<String>,
+    pub new_key: Zeroizing<String>,
+    pub new_owners: Vec<String>,
+    pub new_threshold: u32,
+}
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Plans the funding key rotation: checks the subnet ownership and the new owners, prints the ownership transfer and the P-chain balance sweep to issue, and exits non-zero since nothing is rotated")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("CHAIN_RPC_URL")
+                .long("chain-rpc-url")
+                .help("Sets the P-chain RPC endpoint")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("SUBNET_ID")
+                .long("subnet-id")
+                .help("Sets the subnet Id to transfer the ownership of")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("OLD_KEY")
+                .long("old-key")
+                .help("Sets the hex-encoded private key being rotated out (must be a subnet control key)")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("NEW_KEY")
+                .long("new-key")
+                .help("Sets the hex-encoded private key to rotate to (receives the P-chain balance, and owns the subnet unless --new-owners is set)")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("NEW_OWNERS")
+                .long("new-owners")
+                .help("Sets the comma-separated P-chain addresses of the new subnet owners (if empty, the new key address)")
+                .required(false)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("NEW_THRESHOLD")
+                .long("new-threshold")
+                .help("Sets the number of the new owners required to sign the subnet transactions")
+                .required(false)
+                .num_args(1)
+                .value_parser(value_parser!(u32))
+                .default_value("1"),
+        )
+}
+
+pub async fn execute(opts: Flags) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default()
+            .filter_or(env_logger::DEFAULT_FILTER_ENV, opts.log_level.as_str()),
+    );
+
+    let subnet_id = ids::Id::from_str(&opts.subnet_id)?;
+    let old_key = avalancheup_aws::install_subnet_chain::load_private_key(&opts.old_key)?;
+    let new_key = avalancheup_aws::install_subnet_chain::load_private_key(&opts.new_key)?;
+    let old_wallet =
+        avalancheup_aws::install_subnet_chain::build_wallet(&opts.chain_rpc_url, &old_key).await?;
+    let new_wallet =
+        avalancheup_aws::install_subnet_chain::build_wallet(&opts.chain_rpc_url, &new_key).await?;
+    if old_wallet.p_address == new_wallet.p_address {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "--old-key and --new-key are the same key",
+        ));
+    }
+
+    let new_owners = if opts.new_owners.is_empty() {
+        vec![new_wallet.p_address.clone()]
+    } else {
+        opts.new_owners.clone()
+    };
+    validate_owners(&new_owners, opts.new_threshold, &new_wallet.p_address)?;
+
+    let (control_keys, threshold) = fetch_subnet_owners(&opts.chain_rpc_url, &subnet_id).await?;
+    if !control_keys.contains(&old_wallet.p_address) {
+        return Err(Error::new(
+            ErrorKind::PermissionDenied,
+            format!(
+                "old key '{}' is not a control key of the subnet '{subnet_id}' (control keys {:?})",
+                old_wallet.p_address, control_keys
+            ),
+        ));
+    }
+    if threshold > 1 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("subnet '{subnet_id}' requires {threshold} control key signatures, only the single-key ownership can be rotated"),
+        ));
+    }
+
+    let balance = avalancheup_aws::install_subnet_chain::fetch_p_chain_balance(
+        &opts.chain_rpc_url,
+        &old_wallet,
+        &[],
+        &avalancheup_aws::install_subnet_chain::ApiPaths::default(),
+    )
+    .await?;
+
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Green),
+        Print(format!(
+            "\nKEY ROTATION PLAN ({})\n",
+            avalancheup_aws::networks::display_name(old_wallet.network_id)
+        )),
+        ResetColor
+    )?;
+    println!(
+        "subnet {subnet_id}: owners {:?} (threshold {threshold}) -> {:?} (threshold {})",
+        control_keys, new_owners, opts.new_threshold
+    );
+    println!(
+        "P-chain balance: {} nAVAX ({} AVAX) from {} -> {}",
+        balance,
+        units::cast_xp_navax_to_avax(primitive_types::U256::from(balance)),
+        old_wallet.p_address,
+        new_wallet.p_address
+    );
+
+    // "TransferSubnetOwnershipTx" and the P-chain "BaseTx" (to send the balance
+    // within the P-chain) were added in the Durango upgrade, and the wallet
+    // cannot issue either of them yet
+    // ref. <https://github.com/ava-labs/avalanchego/blob/master/vms/platformvm/txs/transfer_subnet_ownership_tx.go>
+    Err(Error::new(
+        ErrorKind::Unsupported,
+        "nothing was rotated: the wallet cannot issue the 'TransferSubnetOwnershipTx' and the P-chain 'BaseTx' yet, issue the plan above with avalanchego's wallet",
+    ))
+}
+
+/// Validates the new subnet owners: non-empty and unique addresses in the same
+/// network as the reference P-chain address, and the threshold within the owners.
+fn validate_owners(owners: &[String], threshold: u32, reference_address: &str) -> io::Result<()> {
+    if owners.is_empty() {
+        return Err(Error::new(ErrorKind::InvalidInput, "no new subnet owner"));
+    }
+    if threshold == 0 || threshold as usize > owners.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "--new-threshold {threshold} must be between 1 and the number of new owners {}",
+                owners.len()
+            ),
+        ));
+    }
+
+    // e.g., "P-fuji1..." has the prefix "P-fuji1"
+    let prefix = match reference_address.rfind('1') {
+        Some(i) => &reference_address[..=i],
+        None => reference_address,
+    };
+    let mut seen = HashSet::new();
+    for owner in owners.iter() {
+        if !owner.starts_with(prefix) || owner.len() <= prefix.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("new owner '{owner}' is not a P-chain address of the network (expected prefix '{prefix}')"),
+            ));
+        }
+        if !seen.insert(owner.to_lowercase()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("duplicate new owner '{owner}'"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Fetches the control keys and the threshold of the subnet.
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetsubnets>
+async fn fetch_subnet_owners(
+    chain_rpc_url: &str,
+    subnet_id: &ids::Id,
+) -> io::Result<(Vec<String>, u32)> {
+    let result = avalancheup_aws::install_subnet_chain::call_json_rpc(
+        chain_rpc_url,
+        "/ext/bc/P",
+        "platform.getSubnets",
+        serde_json::json!({ "ids": [subnet_id.to_string()] }),
+        &[],
+    )
+    .await?;
+    let subnet = &result["subnets"][0];
+    if subnet.is_null() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("subnet '{subnet_id}' not found"),
+        ));
+    }
+    let control_keys = subnet["controlKeys"]
+        .as_array()
+        .map(|keys| {
+            keys.iter()
+                .filter_map(|k| k.as_str().map(String::from))
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+    let threshold = subnet["threshold"]
+        .as_str()
+        .and_then(|t| t.parse::<u32>().ok())
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("subnet '{subnet_id}' has no valid threshold ({})", subnet),
+            )
+        })?;
+    Ok((control_keys, threshold))
+}
+
+/// RUST_LOG=debug cargo test --package avalancheup-aws --bin avalancheup-aws -- rotate_key_plan::test_validate_owners --exact --show-output
+#[test]
+fn test_validate_owners() {
+    let reference = "P-fuji1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq";
+    let owners = vec![
+        String::from("P-fuji1aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+        String::from("P-fuji1bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"),
+    ];
+    assert!(validate_owners(&owners, 1, reference).is_ok());
+    assert!(validate_owners(&owners, 2, reference).is_ok());
+
+    assert!(validate_owners(&[], 1, reference).is_err());
+    assert!(validate_owners(&owners, 0, reference).is_err());
+    assert!(validate_owners(&owners, 3, reference).is_err());
+
+    // other network, or not an address
+    let err = validate_owners(
+        &[String::from(
+            "P-avax1aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        )],
+        1,
+        reference,
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(validate_owners(&[String::from("P-fuji1")], 1, reference).is_err());
+
+    // duplicates are case-insensitive (bech32)
+    let err = validate_owners(
+        &[
+            owners[0].clone(),
+            owners[0].to_uppercase().replace("P-FUJI1", "P-fuji1"),
+        ],
+        1,
+        reference,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("duplicate"));
+}
@@ -0,0 +1,181 @@
+use std::{
+    io::{self, stdout, Error, ErrorKind},
+    str::FromStr,
+};
+
+use avalanche_types::ids;
+use chrono::{TimeZone, Utc};
+use clap::{Arg, Command};
+use crossterm::{
+    execute,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+use serde::{Deserialize, Serialize};
+
+pub const NAME: &str = "subnet-validators";
+
+/// Defines "subnet-validators" option.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Flags {
+    pub log_level: String,
+
+    pub chain_rpc_url: String,
+    pub subnet_id: String,
+
+    pub output: String,
+}
+
+/// Current validator of the subnet, with the time left until its validation ends.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct SubnetValidator {
+    pub node_id: String,
+    pub weight: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    /// Seconds until the end time (zero if already ended).
+    pub remaining_secs: u64,
+}
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Lists the current validators of a subnet with their remaining validation time, sorted by expiry")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("CHAIN_RPC_URL")
+                .long("chain-rpc-url")
+                .help("Sets the P-chain or Avalanche RPC endpoint")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("SUBNET_ID")
+                .long("subnet-id")
+                .help("Sets the subnet Id to list the current validators of")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("OUTPUT")
+                .long("output")
+                .short('o')
+                .help("Sets the output format")
+                .required(false)
+                .num_args(1)
+                .value_parser(["text", "json"])
+                .default_value("text"),
+        )
+}
+
+pub async fn execute(opts: Flags) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default()
+            .filter_or(env_logger::DEFAULT_FILTER_ENV, opts.clone().log_level),
+    );
+
+    let subnet_id = ids::Id::from_str(&opts.subnet_id).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid --subnet-id '{}' ({})", opts.subnet_id, e),
+        )
+    })?;
+
+    // ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetcurrentvalidators>
+    let result = avalancheup_aws::install_subnet_chain::call_json_rpc(
+        &opts.chain_rpc_url,
+        "/ext/bc/P",
+        "platform.getCurrentValidators",
+        serde_json::json!({ "subnetID": subnet_id.to_string() }),
+        &[],
+    )
+    .await?;
+    let validators = parse_validators(&result, Utc::now().timestamp() as u64)?;
+
+    if opts.output == "json" {
+        println!("{}", serde_json::to_string_pretty(&validators).unwrap());
+        return Ok(());
+    }
+
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Green),
+        Print(format!(
+            "\nSUBNET VALIDATORS ({subnet_id}, {} validator(s))\n",
+            validators.len()
+        )),
+        ResetColor
+    )?;
+    for v in validators.iter() {
+        println!(
+            "{}\tweight {}\tstart {}\tend {}\tremaining {}",
+            v.node_id,
+            v.weight,
+            format_time(v.start_time),
+            format_time(v.end_time),
+            format_remaining(v.remaining_secs)
+        );
+    }
+
+    Ok(())
+}
+
+/// Parses the "platform.getCurrentValidators" result, sorted by the end time.
+pub fn parse_validators(result: &serde_json::Value, now: u64) -> io::Result<Vec<SubnetValidator>> {
+    // the numbers are returned as strings
+    let field = |v: &serde_json::Value, k: &str| -> io::Result<u64> {
+        v[k].as_str()
+            .and_then(|s| s.parse::<u64>().ok())
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("unexpected '{k}' in validator {v}"),
+                )
+            })
+    };
+
+    let mut validators = Vec::new();
+    for v in result["validators"].as_array().cloned().unwrap_or_default() {
+        let end_time = field(&v, "endTime")?;
+        validators.push(SubnetValidator {
+            node_id: v["nodeID"].as_str().unwrap_or_default().to_string(),
+            weight: field(&v, "weight")?,
+            start_time: field(&v, "startTime")?,
+            end_time,
+            remaining_secs: end_time.saturating_sub(now),
+        });
+    }
+    validators.sort_by(|a, b| {
+        a.end_time
+            .cmp(&b.end_time)
+            .then_with(|| a.node_id.cmp(&b.node_id))
+    });
+    Ok(validators)
+}
+
+fn format_time(secs: u64) -> String {
+    Utc.timestamp_opt(secs as i64, 0)
+        .single()
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_else(|| secs.to_string())
+}
+
+fn format_remaining(secs: u64) -> String {
+    if secs == 0 {
+        return String::from("ended");
+    }
+    format!(
+        "{}d {}h {}m",
+        secs / 86400,
+        (secs % 86400) / 3600,
+        (secs % 3600) / 60
+    )
+}
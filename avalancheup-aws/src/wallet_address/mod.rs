@@ -0,0 +1,110 @@
+use std::io::{self, stdout};
+
+use clap::{value_parser, Arg, Command};
+use crossterm::{
+    execute,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+pub const NAME: &str = "wallet-address";
+
+/// Defines "wallet-address" option.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Flags {
+    pub log_level: String,
+
+    pub key: Zeroizing<String>,
+    pub network_id: u32,
+
+    pub output: String,
+}
+
+/// Addresses of the wallet across the P, X, and C chains.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Addresses {
+    pub network_id: u32,
+
+    pub p_chain_address: String,
+    pub x_chain_address: String,
+    pub c_chain_address: String,
+}
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Derives the P, X, and C-chain addresses of a hotkey offline (no RPC call, e.g., to pre-fund the wallet)")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("KEY")
+                .long("key")
+                .help("Hex-encoded hot key")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("NETWORK_ID")
+                .long("network-id")
+                .help("Sets the network Id for the address HRP (e.g., 1 for mainnet, 5 for fuji)")
+                .required(true)
+                .num_args(1)
+                .value_parser(value_parser!(u32)),
+        )
+        .arg(
+            Arg::new("OUTPUT")
+                .long("output")
+                .short('o')
+                .help("Sets the output format")
+                .required(false)
+                .num_args(1)
+                .value_parser(["text", "json"])
+                .default_value("text"),
+        )
+}
+
+pub fn execute(opts: Flags) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default()
+            .filter_or(env_logger::DEFAULT_FILTER_ENV, opts.log_level.as_str()),
+    );
+
+    let priv_key = avalancheup_aws::install_subnet_chain::load_private_key(&opts.key)?;
+    let pub_key = priv_key.to_public_key();
+    let addresses = Addresses {
+        network_id: opts.network_id,
+
+        p_chain_address: pub_key.to_hrp_address(opts.network_id, "P").unwrap(),
+        x_chain_address: pub_key.to_hrp_address(opts.network_id, "X").unwrap(),
+        c_chain_address: pub_key.to_eth_address(),
+    };
+
+    if opts.output == "json" {
+        println!("{}", serde_json::to_string_pretty(&addresses).unwrap());
+        return Ok(());
+    }
+
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Green),
+        Print(format!(
+            "\nWALLET ADDRESSES ({})\n",
+            avalancheup_aws::networks::display_name(addresses.network_id)
+        )),
+        ResetColor
+    )?;
+    println!("P-chain {}", addresses.p_chain_address);
+    println!("X-chain {}", addresses.x_chain_address);
+    println!("C-chain {}", addresses.c_chain_address);
+
+    Ok(())
+}
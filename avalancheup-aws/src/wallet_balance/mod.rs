@@ -0,0 +1,253 @@
+use std::{
+    io::{self, stdout, Error, ErrorKind},
+    str::FromStr,
+};
+
+use avalanche_types::{
+    jsonrpc::client::evm as json_client_evm,
+    key::secp256k1::{self, KeyType},
+    units, wallet,
+};
+use aws_manager::{self, kms};
+use clap::{Arg, Command};
+use crossterm::{
+    execute,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+use serde::{Deserialize, Serialize};
+use tokio::time::Duration;
+use zeroize::Zeroizing;
+
+pub const NAME: &str = "wallet-balance";
+
+/// Defines "wallet-balance" option.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Flags {
+    pub log_level: String,
+
+    pub chain_rpc_url: String,
+    pub key_type: String,
+    pub key: Zeroizing<String>,
+
+    pub region: String,
+    pub profile_name: String,
+
+    pub output: String,
+}
+
+/// Balances of the wallet across the P, X, and C chains.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Balances {
+    pub network_id: u32,
+
+    pub p_chain_address: String,
+    pub p_chain_balance_in_navax: u64,
+    pub p_chain_balance_in_avax: String,
+
+    pub x_chain_address: String,
+    pub x_chain_balance_in_navax: u64,
+    pub x_chain_balance_in_avax: String,
+
+    pub c_chain_address: String,
+    pub c_chain_balance_in_wei: String,
+    pub c_chain_balance_in_avax: String,
+}
+
+pub fn command() -> Command {
+    Command::new(NAME)
+        .about("Fetches the P, X, and C-chain balances of a hotkey or KMS key")
+        .arg(
+            Arg::new("LOG_LEVEL")
+                .long("log-level")
+                .short('l')
+                .help("Sets the log level")
+                .required(false)
+                .num_args(1)
+                .value_parser(["debug", "info"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("CHAIN_RPC_URL")
+                .long("chain-rpc-url")
+                .help("Sets the node RPC endpoint to build the wallet and fetch the balances")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("KEY_TYPE")
+                .long("key-type")
+                .help("Sets the key type")
+                .required(false)
+                .value_parser(["aws-kms", "hot"])
+                .num_args(1)
+                .default_value("hot"),
+        )
+        .arg(
+            Arg::new("KEY")
+                .long("key")
+                .help("Hex-encoded hot key or KMS key ARN")
+                .required(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("REGION")
+                .long("region")
+                .help("Sets the AWS region for the KMS key (only for 'aws-kms')")
+                .required(false)
+                .num_args(1)
+                .default_value("us-west-2"),
+        )
+        .arg(
+            Arg::new("PROFILE_NAME")
+                .long("profile-name")
+                .help("Sets the AWS credential profile name for API calls/endpoints")
+                .required(false)
+                .default_value("default")
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("OUTPUT")
+                .long("output")
+                .short('o')
+                .help("Sets the output format")
+                .required(false)
+                .num_args(1)
+                .value_parser(["text", "json"])
+                .default_value("text"),
+        )
+}
+
+pub async fn execute(opts: Flags) -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default()
+            .filter_or(env_logger::DEFAULT_FILTER_ENV, opts.log_level.as_str()),
+    );
+
+    let balances = match KeyType::from_str(&opts.key_type).unwrap() {
+        KeyType::AwsKms => {
+            let shared_config = aws_manager::load_config(
+                Some(opts.region.clone()),
+                Some(opts.profile_name.clone()),
+                Some(Duration::from_secs(30)),
+            )
+            .await;
+            let kms_manager = kms::Manager::new(&shared_config);
+            let key = secp256k1::kms::aws::Key::from_arn(kms_manager, &opts.key)
+                .await
+                .map_err(|e| {
+                    Error::new(
+                        ErrorKind::Other,
+                        format!("failed to load KMS key '{}' ({})", opts.key.as_str(), e),
+                    )
+                })?;
+            fetch_balances(&opts.chain_rpc_url, &key).await?
+        }
+        KeyType::Hot => {
+            let key = avalancheup_aws::install_subnet_chain::load_private_key(&opts.key)?;
+            fetch_balances(&opts.chain_rpc_url, &key).await?
+        }
+        KeyType::Unknown(s) => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("unknown key type {s}"),
+            ))
+        }
+    };
+
+    if opts.output == "json" {
+        println!("{}", serde_json::to_string_pretty(&balances).unwrap());
+        return Ok(());
+    }
+
+    execute!(
+        stdout(),
+        SetForegroundColor(Color::Green),
+        Print(format!(
+            "\nWALLET BALANCES ({})\n",
+            avalancheup_aws::networks::display_name(balances.network_id)
+        )),
+        ResetColor
+    )?;
+    println!(
+        "P-chain {}: {} nAVAX ({} AVAX)",
+        balances.p_chain_address,
+        balances.p_chain_balance_in_navax,
+        balances.p_chain_balance_in_avax
+    );
+    println!(
+        "X-chain {}: {} nAVAX ({} AVAX)",
+        balances.x_chain_address,
+        balances.x_chain_balance_in_navax,
+        balances.x_chain_balance_in_avax
+    );
+    println!(
+        "C-chain {}: {} wei ({} AVAX)",
+        balances.c_chain_address, balances.c_chain_balance_in_wei, balances.c_chain_balance_in_avax
+    );
+
+    Ok(())
+}
+
+/// Builds the wallet with the key and fetches its balances across the P, X, and C chains.
+async fn fetch_balances<T>(chain_rpc_url: &str, key: &T) -> io::Result<Balances>
+where
+    T: secp256k1::ReadOnly + secp256k1::SignOnly + Clone + Send + Sync,
+{
+    let w = wallet::Builder::new(key)
+        .base_http_url(chain_rpc_url.to_string())
+        .build()
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to build wallet with '{chain_rpc_url}' ({})", e),
+            )
+        })?;
+    log::info!("built wallet for '{}' via '{chain_rpc_url}'", w.p_address);
+
+    let p_chain_balance = w.p().balance().await.map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to fetch P-chain balance ({})", e),
+        )
+    })?;
+    let x_chain_balance = w.x().balance().await.map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to fetch X-chain balance ({})", e),
+        )
+    })?;
+
+    let c_chain_rpc_url = format!("{}/ext/bc/C/rpc", chain_rpc_url.trim_end_matches('/'));
+    let c_chain_balance = json_client_evm::get_balance(&c_chain_rpc_url, w.h160_address)
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to fetch C-chain balance ({})", e),
+            )
+        })?;
+
+    Ok(Balances {
+        network_id: w.network_id,
+
+        p_chain_address: w.p_address.clone(),
+        p_chain_balance_in_navax: p_chain_balance,
+        p_chain_balance_in_avax: units::cast_xp_navax_to_avax(primitive_types::U256::from(
+            p_chain_balance,
+        ))
+        .to_string(),
+
+        x_chain_address: w.x_address.clone(),
+        x_chain_balance_in_navax: x_chain_balance,
+        x_chain_balance_in_avax: units::cast_xp_navax_to_avax(primitive_types::U256::from(
+            x_chain_balance,
+        ))
+        .to_string(),
+
+        c_chain_address: w.eth_address.clone(),
+        c_chain_balance_in_wei: c_chain_balance.to_string(),
+        c_chain_balance_in_avax: units::cast_evm_navax_to_avax_i64(c_chain_balance).to_string(),
+    })
+}